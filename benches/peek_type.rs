@@ -0,0 +1,66 @@
+//! Compares `peek_type` with parsing the whole payload into `serde_json::Value` just to read its
+//! `type`, run with `cargo bench --bench peek_type`.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use rust_cast::message_manager::peek_type;
+
+const ITERATIONS: u32 = 100_000;
+
+/// Typical `MEDIA_STATUS` broadcast, `type` goes after the sizable `status`, as receivers send it.
+const MEDIA_STATUS: &str = r#"{
+    "requestId": 0,
+    "status": [{
+        "mediaSessionId": 1,
+        "playbackRate": 1,
+        "playerState": "PLAYING",
+        "currentTime": 42.5,
+        "supportedMediaCommands": 274447,
+        "volume": { "level": 1, "muted": false },
+        "media": {
+            "contentId": "http://commondatastorage.googleapis.com/gtv-videos-bucket/big_buck_bunny_1080p.mp4",
+            "contentType": "video/mp4",
+            "streamType": "BUFFERED",
+            "duration": 596.474195,
+            "metadata": {
+                "metadataType": 0,
+                "title": "Big Buck Bunny",
+                "images": [{ "url": "http://example.com/poster.jpg", "width": 480, "height": 270 }]
+            },
+            "tracks": []
+        },
+        "currentItemId": 1,
+        "items": [{ "itemId": 1, "autoplay": true, "orderId": 0 }],
+        "repeatMode": "REPEAT_OFF"
+    }],
+    "type": "MEDIA_STATUS"
+}"#;
+
+fn bench<F: Fn(&str) -> bool>(name: &str, f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        assert!(f(black_box(MEDIA_STATUS)));
+    }
+
+    let elapsed = start.elapsed();
+    println!("{:>10}: {:?} per payload", name, elapsed / ITERATIONS);
+    elapsed
+}
+
+fn main() {
+    let peek = bench("peek_type", |payload| {
+        peek_type(payload) == Some("MEDIA_STATUS")
+    });
+    let full = bench("Value", |payload| {
+        let value = serde_json::from_str::<serde_json::Value>(payload).unwrap();
+        value["type"] == "MEDIA_STATUS"
+    });
+
+    println!(
+        "peek_type is {:.1}x faster",
+        full.as_secs_f64() / peek.as_secs_f64()
+    );
+}
@@ -8,7 +8,6 @@ use serde::Deserialize;
 
 use rust_cast::{
     channels::{
-        heartbeat::HeartbeatResponse,
         media::{Media, StatusEntry, StreamType},
         receiver::CastDeviceApp,
     },
@@ -194,11 +193,18 @@ fn play_media(
             app.transport_id.as_str(),
             app.session_id.as_str(),
             &Media {
-                content_id: media,
+                content_id: Some(media),
+                content_url: None,
                 content_type: media_type,
                 stream_type: media_stream_type,
                 duration: None,
                 metadata: None,
+                tracks: vec![],
+                text_track_style: None,
+                hls_segment_format: None,
+                hls_video_segment_format: None,
+                breaks: vec![],
+                break_clips: vec![],
             },
         )
         .unwrap();
@@ -233,7 +239,7 @@ fn play_media(
             println!(
                 "{} {}",
                 Green.paint("Content Id:"),
-                Red.paint(media.content_id.as_str())
+                Red.paint(media.content_id.as_deref().unwrap_or_default())
             );
             println!(
                 "{} {}",
@@ -413,12 +419,10 @@ fn main() {
                             .unwrap(),
                     );
                 } else if args.flag_media_stop {
-                    status_entry = Some(
-                        cast_device
-                            .media
-                            .stop(app.transport_id.as_str(), status.media_session_id)
-                            .unwrap(),
-                    );
+                    status_entry = cast_device
+                        .media
+                        .stop(app.transport_id.as_str(), status.media_session_id)
+                        .unwrap();
                 } else if args.flag_media_seek.is_some() {
                     status_entry = Some(
                         cast_device
@@ -458,7 +462,7 @@ fn main() {
                         println!(
                             "{} {}",
                             Green.paint("Content Id:"),
-                            Red.paint(media.content_id.as_str())
+                            Red.paint(media.content_id.as_deref().unwrap_or_default())
                         );
                         println!(
                             "{} {}",
@@ -514,17 +518,18 @@ fn main() {
 
         loop {
             match cast_device.receive() {
-                Ok(ChannelMessage::Heartbeat(response)) => {
-                    println!("[Heartbeat] {:?}", response);
-
-                    if let HeartbeatResponse::Ping = response {
-                        cast_device.heartbeat.pong().unwrap();
-                    }
-                }
+                Ok(ChannelMessage::Heartbeat(response)) => println!("[Heartbeat] {:?}", response),
 
                 Ok(ChannelMessage::Connection(response)) => println!("[Connection] {:?}", response),
                 Ok(ChannelMessage::Media(response)) => println!("[Media] {:?}", response),
-                Ok(ChannelMessage::Receiver(response)) => println!("[Receiver] {:?}", response),
+                Ok(ChannelMessage::Multizone(response)) => println!("[Multizone] {:?}", response),
+                Ok(ChannelMessage::Receiver(response)) => {
+                    println!("[Receiver] {:?}", response);
+
+                    if let Some(event) = cast_device.receiver.last_event() {
+                        println!("[Receiver] {:?}", event);
+                    }
+                }
                 Ok(ChannelMessage::Raw(response)) => println!(
                     "Support for the following message type is not yet supported: {:?}",
                     response
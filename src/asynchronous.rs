@@ -0,0 +1,1032 @@
+//! Asynchronous counterpart of the [`MessageManager`](crate::message_manager::MessageManager)
+//! built on top of `tokio::io::AsyncRead + AsyncWrite` streams, along with the asynchronous
+//! connection, heartbeat, receiver and media channels. Message framing, (de)serialization and
+//! parsing of the replies are shared with the blocking implementation, channels cover the most
+//! common requests (e.g. launching application, loading media and controlling playback), anything
+//! else can be sent with `AsyncMessageManager` directly.
+
+use std::{
+    borrow::Cow,
+    num::NonZeroU32,
+    sync::{Arc, Mutex},
+};
+
+use serde::Serialize;
+use tokio::io::{split, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, ReadHalf, WriteHalf};
+
+use crate::{
+    cast::proxies,
+    channels::{
+        self, connection,
+        heartbeat::{self, HeartbeatResponse},
+        media::{self, LoadOptions, Media, MediaResponse, Status, StatusEntry},
+        receiver::{
+            self, Application, CastDeviceApp, ReceiverResponse, Status as ReceiverStatus, Volume,
+        },
+    },
+    errors::Error,
+    message_manager::{
+        decode_frame, encode_frame, extract_request_id, log_message, CastMessage, Direction,
+        FrameBuffer, DEFAULT_MAX_MESSAGE_SIZE, READ_CHUNK_LENGTH,
+    },
+    namespaces,
+};
+
+/// Asynchronous structure that is responsible for (de)serializing and sending/receiving Cast
+/// protocol messages.
+pub struct AsyncMessageManager<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    message_buffer: Mutex<Vec<CastMessage>>,
+    /// Read and write halves are locked separately, so that sending isn't blocked by the pending
+    /// read (e.g. while waiting for the next message).
+    reader: tokio::sync::Mutex<(ReadHalf<S>, FrameBuffer)>,
+    writer: tokio::sync::Mutex<WriteHalf<S>>,
+    request_counter: Mutex<NonZeroU32>,
+    max_message_size: usize,
+}
+
+impl<S> AsyncMessageManager<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    pub fn new(stream: S) -> Self {
+        let (reader, writer) = split(stream);
+
+        AsyncMessageManager {
+            reader: tokio::sync::Mutex::new((reader, FrameBuffer::default())),
+            writer: tokio::sync::Mutex::new(writer),
+            message_buffer: Mutex::new(vec![]),
+            request_counter: Mutex::new(NonZeroU32::MIN),
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+        }
+    }
+
+    /// Overrides maximum length (in bytes, length prefix excluded) of the incoming message, see
+    /// `MessageManager::with_max_message_size`.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_message_size` - maximum length of the incoming message in bytes.
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = max_message_size;
+        self
+    }
+
+    /// Sends `message` to the Cast Device.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - `CastMessage` instance to be sent to the Cast Device.
+    pub async fn send(&self, message: CastMessage) -> Result<(), Error> {
+        log_message(Direction::Sent, &message);
+
+        let (_, frame) = encode_frame(message)?;
+
+        let mut writer = self.writer.lock().await;
+
+        writer.write_all(&frame).await?;
+        writer.flush().await?;
+
+        Ok(())
+    }
+
+    /// Waits for the next `CastMessage` available. Can also return existing message from the
+    /// internal message buffer containing messages that have been received previously, but haven't
+    /// been consumed for some reason (e.g. during `receive_find_map` call).
+    ///
+    /// # Return value
+    ///
+    /// `Result` containing parsed `CastMessage` or `Error`.
+    pub async fn receive(&self) -> Result<CastMessage, Error> {
+        // If we have messages in the buffer, let's return them from it.
+        let buffered = {
+            let mut message_buffer = self.message_buffer.lock().unwrap();
+            if message_buffer.is_empty() {
+                None
+            } else {
+                Some(message_buffer.remove(0))
+            }
+        };
+
+        match buffered {
+            Some(message) => Ok(message),
+            None => self.read().await,
+        }
+    }
+
+    /// Waits for the next `CastMessage` for which `f` returns valid mapped value. Messages in which
+    /// `f` is not interested are placed into internal message buffer and can be later retrieved
+    /// with `receive`. This method always reads from the stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - Function that analyzes and maps `CastMessage` to any other type. If message doesn't
+    ///   look like something `f` is looking for, then `Ok(None)` should be returned so that message
+    ///   is not lost and placed into internal message buffer for later retrieval.
+    ///
+    /// # Return value
+    ///
+    /// `Result` containing parsed `CastMessage` or `Error`.
+    pub async fn receive_find_map<F, B>(&self, f: F) -> Result<B, Error>
+    where
+        F: Fn(&CastMessage) -> Result<Option<B>, Error>,
+    {
+        loop {
+            let message = self.read().await?;
+
+            // If message is found, just return mapped result, otherwise keep unprocessed message
+            // in the buffer, it can be later retrieved with `receive`.
+            match f(&message)? {
+                Some(r) => return Ok(r),
+                None => self.message_buffer.lock().unwrap().push(message),
+            }
+        }
+    }
+
+    /// Waits for the next message that carries `requestId` equal to `request_id`. Messages that
+    /// don't are placed into internal message buffer and can be later retrieved with `receive`.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_id` - identifier of the request to wait the reply for.
+    ///
+    /// # Return value
+    ///
+    /// `Result` containing reply `CastMessage` or `Error`.
+    pub async fn receive_reply(&self, request_id: u32) -> Result<CastMessage, Error> {
+        self.receive_find_map(|message| {
+            if extract_request_id(message) == Some(request_id) {
+                Ok(Some(message.clone()))
+            } else {
+                Ok(None)
+            }
+        })
+        .await
+    }
+
+    /// Generates unique integer number that is used in some requests to map them with the response.
+    ///
+    /// # Return value
+    ///
+    /// Unique (in the scope of this particular `AsyncMessageManager` instance) integer number.
+    pub fn generate_request_id(&self) -> NonZeroU32 {
+        let mut counter = self.request_counter.lock().unwrap();
+        let request_id = *counter;
+        *counter = counter.checked_add(1).unwrap();
+        request_id
+    }
+
+    /// Drains the internal message buffer.
+    pub fn drain(&self) {
+        self.message_buffer.lock().unwrap().clear();
+    }
+
+    /// Reads next `CastMessage` from the stream.
+    ///
+    /// Reading is cancel safe: bytes of the partially received frame are kept in the frame buffer
+    /// if returned future is dropped (e.g. in `tokio::select!`), and next read continues from there.
+    ///
+    /// # Return value
+    ///
+    /// `Result` containing parsed `CastMessage` or `Error`.
+    async fn read(&self) -> Result<CastMessage, Error> {
+        let mut reader = self.reader.lock().await;
+        let (ref mut stream, ref mut frame_buffer) = *reader;
+        let mut chunk = vec![0; READ_CHUNK_LENGTH];
+
+        let frame = loop {
+            if let Some(frame) = frame_buffer.next_frame(self.max_message_size)? {
+                break frame;
+            }
+
+            // `read` is cancel safe, bytes are moved into the frame buffer right after it returns.
+            match stream.read(&mut chunk).await? {
+                0 => return Err(Error::Io(std::io::ErrorKind::UnexpectedEof.into())),
+                read => frame_buffer.extend(&chunk[..read]),
+            }
+        };
+
+        let message = decode_frame(frame)?;
+
+        log_message(Direction::Received, &message);
+
+        Ok(message)
+    }
+}
+
+/// Sends `payload` serialized as JSON to `destination` within `namespace`.
+async fn send_json<S, P>(
+    message_manager: &AsyncMessageManager<S>,
+    namespace: &str,
+    source: &str,
+    destination: &str,
+    payload: &P,
+) -> Result<(), Error>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    P: Serialize,
+{
+    let message = CastMessage::builder()
+        .namespace(namespace)
+        .source(source)
+        .destination(destination)
+        .payload_json(payload)
+        .build()?;
+
+    message_manager.send(message).await
+}
+
+/// Waits for the message within `namespace` for which `f` returns valid mapped value, passing
+/// messages parsed with `parse` to it. Messages from other namespaces are kept in the internal
+/// message buffer, see `AsyncMessageManager::receive_find_map`.
+async fn receive_response<S, R, B, F>(
+    message_manager: &AsyncMessageManager<S>,
+    namespace: &str,
+    parse: fn(&str, serde_json::Value) -> Result<R, Error>,
+    f: F,
+) -> Result<B, Error>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+    F: Fn(R) -> Result<Option<B>, Error>,
+{
+    message_manager
+        .receive_find_map(|message| {
+            if message.namespace != namespace {
+                return Ok(None);
+            }
+
+            let (_, _, response) = channels::parse_envelope(message, namespace, parse)?;
+            f(response)
+        })
+        .await
+}
+
+/// Asynchronous counterpart of the `ConnectionChannel`, manages virtual connections.
+pub struct AsyncConnectionChannel<'a, S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    sender: Cow<'a, str>,
+    message_manager: Arc<AsyncMessageManager<S>>,
+}
+
+impl<'a, S> AsyncConnectionChannel<'a, S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    pub fn new<T>(sender: T, message_manager: Arc<AsyncMessageManager<S>>) -> Self
+    where
+        T: Into<Cow<'a, str>>,
+    {
+        AsyncConnectionChannel {
+            sender: sender.into(),
+            message_manager,
+        }
+    }
+
+    /// Establishes virtual connection with the specified `destination`, see
+    /// `ConnectionChannel::connect`.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - identifier of the receiver or application transport (e.g. `receiver-0`).
+    pub async fn connect(&self, destination: &str) -> Result<(), Error> {
+        self.send(destination, connection::MESSAGE_TYPE_CONNECT)
+            .await
+    }
+
+    /// Closes virtual connection with the specified `destination`.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - identifier of the receiver or application transport (e.g. `receiver-0`).
+    pub async fn disconnect(&self, destination: &str) -> Result<(), Error> {
+        self.send(destination, connection::MESSAGE_TYPE_CLOSE).await
+    }
+
+    async fn send(&self, destination: &str, message_type: &str) -> Result<(), Error> {
+        send_json(
+            &self.message_manager,
+            namespaces::CONNECTION,
+            &self.sender,
+            destination,
+            &proxies::connection::ConnectionRequest {
+                typ: message_type.to_string(),
+                user_agent: connection::CHANNEL_USER_AGENT.to_string(),
+                conn_type: None,
+                origin: None,
+                extra: serde_json::Map::new(),
+            },
+        )
+        .await
+    }
+}
+
+/// Asynchronous counterpart of the `HeartbeatChannel`, keeps connection alive.
+pub struct AsyncHeartbeatChannel<'a, S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    sender: Cow<'a, str>,
+    receiver: Cow<'a, str>,
+    message_manager: Arc<AsyncMessageManager<S>>,
+}
+
+impl<'a, S> AsyncHeartbeatChannel<'a, S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    pub fn new<T>(sender: T, receiver: T, message_manager: Arc<AsyncMessageManager<S>>) -> Self
+    where
+        T: Into<Cow<'a, str>>,
+    {
+        AsyncHeartbeatChannel {
+            sender: sender.into(),
+            receiver: receiver.into(),
+            message_manager,
+        }
+    }
+
+    pub async fn ping(&self) -> Result<(), Error> {
+        self.send(&self.receiver, heartbeat::MESSAGE_TYPE_PING)
+            .await
+    }
+
+    pub async fn pong(&self, destination: &str) -> Result<(), Error> {
+        self.send(destination, heartbeat::MESSAGE_TYPE_PONG).await
+    }
+
+    pub fn can_handle(&self, message: &CastMessage) -> bool {
+        message.namespace == namespaces::HEARTBEAT
+    }
+
+    pub fn parse(&self, message: &CastMessage) -> Result<HeartbeatResponse, Error> {
+        channels::parse_envelope(message, namespaces::HEARTBEAT, heartbeat::parse_response)
+            .map(|(_, _, response)| response)
+    }
+
+    async fn send(&self, destination: &str, message_type: &str) -> Result<(), Error> {
+        send_json(
+            &self.message_manager,
+            namespaces::HEARTBEAT,
+            &self.sender,
+            destination,
+            &proxies::heartbeat::HeartBeatRequest {
+                typ: message_type.to_string(),
+            },
+        )
+        .await
+    }
+}
+
+/// Asynchronous counterpart of the `ReceiverChannel`, manages receiver applications and volume.
+/// Unlike the blocking channel it doesn't bound waits with a timeout, use `tokio::time::timeout`
+/// for that.
+pub struct AsyncReceiverChannel<'a, S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    sender: Cow<'a, str>,
+    receiver: Cow<'a, str>,
+    message_manager: Arc<AsyncMessageManager<S>>,
+}
+
+impl<'a, S> AsyncReceiverChannel<'a, S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    pub fn new<T>(sender: T, receiver: T, message_manager: Arc<AsyncMessageManager<S>>) -> Self
+    where
+        T: Into<Cow<'a, str>>,
+    {
+        AsyncReceiverChannel {
+            sender: sender.into(),
+            receiver: receiver.into(),
+            message_manager,
+        }
+    }
+
+    /// Launches the specified receiver's application, see `ReceiverChannel::launch_app`.
+    ///
+    /// # Return value
+    ///
+    /// `Application` as reported by the receiver status.
+    pub async fn launch_app(&self, app: &CastDeviceApp) -> Result<Application, Error> {
+        let request_id = self.message_manager.generate_request_id().get();
+        let app_id = app.to_string();
+
+        self.send(&proxies::receiver::AppLaunchRequest {
+            typ: receiver::MESSAGE_TYPE_LAUNCH.to_string(),
+            request_id,
+            app_id: app_id.clone(),
+            app_params: None,
+        })
+        .await?;
+
+        self.receive(request_id, |response| match response {
+            ReceiverResponse::Status(mut status) => {
+                let position = status
+                    .applications
+                    .iter()
+                    .position(|application| application.app_id == app_id);
+
+                match position {
+                    Some(position) => Ok(Some(status.applications.remove(position))),
+                    None if status.request_id == request_id => {
+                        Err(Error::Internal("Could not run application".into()))
+                    }
+                    None => Ok(None),
+                }
+            }
+            _ => Ok(None),
+        })
+        .await
+    }
+
+    /// Stops currently active application using corresponding `session_id`.
+    pub async fn stop_app(&self, session_id: &str) -> Result<(), Error> {
+        let request_id = self.message_manager.generate_request_id().get();
+
+        self.send(&proxies::receiver::AppStopRequest {
+            typ: receiver::MESSAGE_TYPE_STOP.to_string(),
+            request_id,
+            session_id: Cow::from(session_id),
+        })
+        .await?;
+
+        self.receive(request_id, |response| match response {
+            ReceiverResponse::Status(status) if status.request_id == request_id => Ok(Some(())),
+            _ => Ok(None),
+        })
+        .await
+    }
+
+    /// Retrieves status of the cast device receiver.
+    pub async fn get_status(&self) -> Result<ReceiverStatus, Error> {
+        let request_id = self.message_manager.generate_request_id().get();
+
+        self.send(&proxies::receiver::GetStatusRequest {
+            typ: receiver::MESSAGE_TYPE_GET_STATUS.to_string(),
+            request_id,
+        })
+        .await?;
+
+        self.receive(request_id, |response| match response {
+            ReceiverResponse::Status(status) if status.request_id == request_id => Ok(Some(status)),
+            _ => Ok(None),
+        })
+        .await
+    }
+
+    /// Sets volume for the active cast device, see `ReceiverChannel::set_volume`.
+    ///
+    /// # Return value
+    ///
+    /// Actual `Volume` instance returned by receiver.
+    pub async fn set_volume<T>(&self, volume: T) -> Result<Volume, Error>
+    where
+        T: Into<Volume>,
+    {
+        let volume = volume.into();
+        let request_id = self.message_manager.generate_request_id().get();
+
+        self.send(&proxies::receiver::SetVolumeRequest {
+            typ: receiver::MESSAGE_TYPE_SET_VOLUME.to_string(),
+            request_id,
+            volume: proxies::receiver::Volume {
+                level: volume.level,
+                muted: volume.muted,
+                control_type: None,
+                step_interval: None,
+            },
+        })
+        .await?;
+
+        self.receive(request_id, |response| match response {
+            ReceiverResponse::Status(status) if status.request_id == request_id => {
+                Ok(Some(status.volume))
+            }
+            _ => Ok(None),
+        })
+        .await
+    }
+
+    pub fn can_handle(&self, message: &CastMessage) -> bool {
+        message.namespace == namespaces::RECEIVER
+    }
+
+    pub fn parse(&self, message: &CastMessage) -> Result<ReceiverResponse, Error> {
+        channels::parse_envelope(message, namespaces::RECEIVER, receiver::parse_response)
+            .map(|(_, _, response)| response)
+    }
+
+    async fn send<P: Serialize>(&self, payload: &P) -> Result<(), Error> {
+        send_json(
+            &self.message_manager,
+            namespaces::RECEIVER,
+            &self.sender,
+            &self.receiver,
+            payload,
+        )
+        .await
+    }
+
+    /// Waits for the reply to the request with `request_id`, error replies are turned into
+    /// `Error::CastError`.
+    async fn receive<B, F>(&self, request_id: u32, f: F) -> Result<B, Error>
+    where
+        F: Fn(ReceiverResponse) -> Result<Option<B>, Error>,
+    {
+        receive_response(
+            &self.message_manager,
+            namespaces::RECEIVER,
+            receiver::parse_response,
+            |response| match response.to_cast_error(request_id) {
+                Some(error) => Err(error),
+                None => f(response),
+            },
+        )
+        .await
+    }
+}
+
+/// Asynchronous counterpart of the `MediaChannel`, loads media and controls playback. Unlike the
+/// blocking channel it doesn't bound waits with a timeout, use `tokio::time::timeout` for that.
+pub struct AsyncMediaChannel<'a, S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    sender: Cow<'a, str>,
+    message_manager: Arc<AsyncMessageManager<S>>,
+}
+
+impl<'a, S> AsyncMediaChannel<'a, S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    pub fn new<T>(sender: T, message_manager: Arc<AsyncMessageManager<S>>) -> Self
+    where
+        T: Into<Cow<'a, str>>,
+    {
+        AsyncMediaChannel {
+            sender: sender.into(),
+            message_manager,
+        }
+    }
+
+    /// Retrieves status of the media application, see `MediaChannel::get_status`.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
+    /// * `media_session_id` - ID of the media session to get status for, all sessions if `None`.
+    pub async fn get_status(
+        &self,
+        destination: &str,
+        media_session_id: Option<i32>,
+    ) -> Result<Status, Error> {
+        let request_id = self.message_manager.generate_request_id().get();
+
+        self.send(
+            destination,
+            &proxies::media::GetStatusRequest {
+                typ: media::MESSAGE_TYPE_GET_STATUS.to_string(),
+                request_id,
+                media_session_id,
+            },
+        )
+        .await?;
+
+        self.receive_status(request_id).await
+    }
+
+    /// Loads provided media to the application, see `MediaChannel::load`.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - `protocol` of the application to load media with (e.g. `web-1`);
+    /// * `session_id` - current session identifier of the player application;
+    /// * `media` - `Media` instance that describes the media we'd like to load.
+    pub async fn load(
+        &self,
+        destination: &str,
+        session_id: &str,
+        media: &Media,
+    ) -> Result<Status, Error> {
+        self.load_with_options(destination, session_id, media, LoadOptions::default())
+            .await
+    }
+
+    /// Same as `load`, but allows to specify `LoadOptions`.
+    pub async fn load_with_options(
+        &self,
+        destination: &str,
+        session_id: &str,
+        media: &Media,
+        options: LoadOptions,
+    ) -> Result<Status, Error> {
+        media.validate()?;
+
+        let request_id = self.message_manager.generate_request_id().get();
+
+        self.send(
+            destination,
+            &media::load_request(request_id, session_id.to_string(), media, None, options),
+        )
+        .await?;
+
+        self.receive_status(request_id).await
+    }
+
+    /// Resumes playback of the media session.
+    pub async fn play(
+        &self,
+        destination: &str,
+        media_session_id: i32,
+    ) -> Result<StatusEntry, Error> {
+        self.playback(destination, media_session_id, media::MESSAGE_TYPE_PLAY)
+            .await?
+            .ok_or_else(|| media::media_session_not_found_error(media_session_id))
+    }
+
+    /// Pauses playback of the media session.
+    pub async fn pause(
+        &self,
+        destination: &str,
+        media_session_id: i32,
+    ) -> Result<StatusEntry, Error> {
+        self.playback(destination, media_session_id, media::MESSAGE_TYPE_PAUSE)
+            .await?
+            .ok_or_else(|| media::media_session_not_found_error(media_session_id))
+    }
+
+    /// Stops playback of the media session, see `MediaChannel::stop`.
+    ///
+    /// # Return value
+    ///
+    /// `StatusEntry` of the stopped media session, or `None` if receiver has dropped the session
+    /// from the status.
+    pub async fn stop(
+        &self,
+        destination: &str,
+        media_session_id: i32,
+    ) -> Result<Option<StatusEntry>, Error> {
+        self.playback(destination, media_session_id, media::MESSAGE_TYPE_STOP)
+            .await
+    }
+
+    pub fn can_handle(&self, message: &CastMessage) -> bool {
+        message.namespace == namespaces::MEDIA
+    }
+
+    pub fn parse(&self, message: &CastMessage) -> Result<MediaResponse, Error> {
+        channels::parse_envelope(message, namespaces::MEDIA, media::parse_response)
+            .map(|(_, _, response)| response)
+    }
+
+    /// Sends playback command of `message_type`, returns the entry of the media session reported
+    /// in reply, if any.
+    async fn playback(
+        &self,
+        destination: &str,
+        media_session_id: i32,
+        message_type: &str,
+    ) -> Result<Option<StatusEntry>, Error> {
+        let request_id = self.message_manager.generate_request_id().get();
+
+        self.send(
+            destination,
+            &proxies::media::PlaybackGenericRequest {
+                request_id,
+                media_session_id,
+                typ: message_type.to_string(),
+                custom_data: proxies::media::CustomData::new(),
+            },
+        )
+        .await?;
+
+        let mut status = self.receive_status(request_id).await?;
+        let position = status
+            .entries
+            .iter()
+            .position(|entry| entry.media_session_id == media_session_id);
+
+        Ok(position.map(|position| status.entries.remove(position)))
+    }
+
+    async fn send<P: Serialize>(&self, destination: &str, payload: &P) -> Result<(), Error> {
+        send_json(
+            &self.message_manager,
+            namespaces::MEDIA,
+            &self.sender,
+            destination,
+            payload,
+        )
+        .await
+    }
+
+    /// Waits for the status that replies to the request with `request_id`, error replies are
+    /// turned into `Error::CastError`.
+    async fn receive_status(&self, request_id: u32) -> Result<Status, Error> {
+        receive_response(
+            &self.message_manager,
+            namespaces::MEDIA,
+            media::parse_response,
+            |response| {
+                if let Some(error) = response.to_cast_error(request_id) {
+                    return Err(error);
+                }
+
+                match response {
+                    MediaResponse::Status(status) if status.request_id == request_id => {
+                        Ok(Some(status))
+                    }
+                    _ => Ok(None),
+                }
+            },
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::io::duplex;
+
+    use super::*;
+    use crate::message_manager::CastMessagePayload;
+
+    fn create_message(payload: &str) -> CastMessage {
+        CastMessage {
+            namespace: "urn:x-cast:com.google.cast.media".to_string(),
+            source: "receiver-0".to_string(),
+            destination: "sender-0".to_string(),
+            payload: CastMessagePayload::String(payload.to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_send_receive_reply() {
+        let (sender, receiver) = duplex(4096);
+        let sender = AsyncMessageManager::new(sender);
+        let receiver = AsyncMessageManager::new(receiver);
+
+        sender
+            .send(create_message("{\"type\":\"PING\"}"))
+            .await
+            .unwrap();
+        sender
+            .send(create_message(
+                "{\"type\":\"MEDIA_STATUS\",\"requestId\":7}",
+            ))
+            .await
+            .unwrap();
+
+        let reply = receiver.receive_reply(7).await.unwrap();
+        assert_eq!(
+            reply.payload.as_str(),
+            Some("{\"type\":\"MEDIA_STATUS\",\"requestId\":7}")
+        );
+
+        let buffered = receiver.receive().await.unwrap();
+        assert_eq!(buffered.payload.as_str(), Some("{\"type\":\"PING\"}"));
+    }
+
+    #[tokio::test]
+    async fn test_send_during_pending_receive() {
+        let (local, remote) = duplex(4096);
+        let local = AsyncMessageManager::new(local);
+        let remote = AsyncMessageManager::new(remote);
+
+        let (received, _) = tokio::time::timeout(Duration::from_secs(5), async {
+            tokio::join!(local.receive(), async {
+                // Remote replies only once it gets PING, which requires send not to wait for the
+                // pending receive.
+                local
+                    .send(create_message("{\"type\":\"PING\"}"))
+                    .await
+                    .unwrap();
+                remote.receive().await.unwrap();
+                remote
+                    .send(create_message("{\"type\":\"PONG\"}"))
+                    .await
+                    .unwrap();
+            })
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(
+            received.unwrap().payload.as_str(),
+            Some("{\"type\":\"PONG\"}")
+        );
+    }
+
+    /// Receives the next request on the `remote` side, checks its type and replies with the
+    /// `reply` payload within the same namespace, `requestId` of the request is set on the reply.
+    async fn reply<S>(remote: &AsyncMessageManager<S>, message_type: &str, reply: serde_json::Value)
+    where
+        S: AsyncRead + AsyncWrite + Unpin,
+    {
+        let request = remote.receive().await.unwrap();
+        let payload: serde_json::Value =
+            serde_json::from_str(request.payload.as_str().unwrap()).unwrap();
+        assert_eq!(payload["type"], message_type);
+
+        let mut reply = reply;
+        reply["requestId"] = payload["requestId"].clone();
+        remote
+            .send(CastMessage {
+                namespace: request.namespace,
+                source: request.destination,
+                destination: request.source,
+                payload: CastMessagePayload::String(reply.to_string()),
+            })
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_channels_launch_and_load() {
+        let (local, remote) = duplex(4096);
+        let local = Arc::new(AsyncMessageManager::new(local));
+        let remote = AsyncMessageManager::new(remote);
+
+        let connection = AsyncConnectionChannel::new("sender-0", Arc::clone(&local));
+        let receiver = AsyncReceiverChannel::new("sender-0", "receiver-0", Arc::clone(&local));
+        let media = AsyncMediaChannel::new("sender-0", Arc::clone(&local));
+        let media_status = serde_json::json!({
+            "type": "MEDIA_STATUS",
+            "status": [{"mediaSessionId": 1, "playerState": "PAUSED"}]
+        });
+
+        let (entry, _) = tokio::time::timeout(Duration::from_secs(5), async {
+            tokio::join!(
+                async {
+                    connection.connect("receiver-0").await.unwrap();
+                    let application = receiver
+                        .launch_app(&CastDeviceApp::DefaultMediaReceiver)
+                        .await
+                        .unwrap();
+                    connection.connect(&application.transport_id).await.unwrap();
+
+                    let media_to_load = Media {
+                        content_id: Some("https://example.com/video.mp4".to_string()),
+                        content_url: None,
+                        stream_type: media::StreamType::Buffered,
+                        content_type: "video/mp4".to_string(),
+                        metadata: None,
+                        tracks: vec![],
+                        text_track_style: None,
+                        hls_segment_format: None,
+                        hls_video_segment_format: None,
+                        duration: None,
+                        breaks: vec![],
+                        break_clips: vec![],
+                    };
+                    let status = media
+                        .load(
+                            &application.transport_id,
+                            &application.session_id,
+                            &media_to_load,
+                        )
+                        .await
+                        .unwrap();
+                    assert_eq!(status.entries.len(), 1);
+
+                    media
+                        .pause(
+                            &application.transport_id,
+                            status.entries[0].media_session_id,
+                        )
+                        .await
+                        .unwrap()
+                },
+                async {
+                    let request = remote.receive().await.unwrap();
+                    assert_eq!(request.namespace, namespaces::CONNECTION);
+                    assert_eq!(request.destination, "receiver-0");
+
+                    reply(
+                        &remote,
+                        "LAUNCH",
+                        serde_json::json!({
+                            "type": "RECEIVER_STATUS",
+                            "status": {
+                                "applications": [{
+                                    "appId": CastDeviceApp::DefaultMediaReceiver.to_string(),
+                                    "sessionId": "1",
+                                    "transportId": "web-1"
+                                }]
+                            }
+                        }),
+                    )
+                    .await;
+
+                    let request = remote.receive().await.unwrap();
+                    assert_eq!(request.namespace, namespaces::CONNECTION);
+                    assert_eq!(request.destination, "web-1");
+
+                    reply(&remote, "LOAD", media_status.clone()).await;
+                    reply(&remote, "PAUSE", media_status.clone()).await;
+                }
+            )
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(entry.media_session_id, 1);
+        assert_eq!(entry.player_state, media::PlayerState::Paused);
+    }
+
+    #[tokio::test]
+    async fn test_channels_error_replies() {
+        let (local, remote) = duplex(4096);
+        let local = Arc::new(AsyncMessageManager::new(local));
+        let remote = AsyncMessageManager::new(remote);
+
+        let receiver = AsyncReceiverChannel::new("sender-0", "receiver-0", Arc::clone(&local));
+        let media = AsyncMediaChannel::new("sender-0", Arc::clone(&local));
+
+        let ((launch, play), _) = tokio::time::timeout(Duration::from_secs(5), async {
+            tokio::join!(
+                async {
+                    (
+                        receiver
+                            .launch_app(&CastDeviceApp::Backdrop)
+                            .await
+                            .unwrap_err(),
+                        // Receiver replies with the status that doesn't list the session.
+                        media.play("web-1", 2).await.unwrap_err(),
+                    )
+                },
+                async {
+                    reply(
+                        &remote,
+                        "LAUNCH",
+                        serde_json::json!({"type": "LAUNCH_ERROR", "reason": "NOT_FOUND"}),
+                    )
+                    .await;
+                    reply(
+                        &remote,
+                        "PLAY",
+                        serde_json::json!({"type": "MEDIA_STATUS", "status": []}),
+                    )
+                    .await;
+                }
+            )
+        })
+        .await
+        .unwrap();
+
+        match launch {
+            Error::CastError { type_, reason, .. } => {
+                assert_eq!(type_, "LAUNCH_ERROR");
+                assert_eq!(reason.as_deref(), Some("NOT_FOUND"));
+            }
+            error => panic!("expected Error::CastError, but got {:?}", error),
+        }
+        assert!(
+            matches!(play, Error::CastError { ref type_, .. } if type_ == "INVALID_PLAYER_STATE")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_heartbeat_channel() {
+        let (local, remote) = duplex(4096);
+        let local = Arc::new(AsyncMessageManager::new(local));
+        let remote = AsyncMessageManager::new(remote);
+
+        let heartbeat = AsyncHeartbeatChannel::new("sender-0", "receiver-0", Arc::clone(&local));
+        heartbeat.ping().await.unwrap();
+
+        let ping = remote.receive().await.unwrap();
+        assert_eq!(ping.destination, "receiver-0");
+        assert!(heartbeat.can_handle(&ping));
+        assert!(matches!(
+            heartbeat.parse(&ping).unwrap(),
+            HeartbeatResponse::Ping
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_receive_keeps_partial_frame() {
+        let (local, mut remote) = duplex(4096);
+        let local = AsyncMessageManager::new(local);
+
+        let (_, frame) = encode_frame(create_message("{\"type\":\"PING\"}")).unwrap();
+        let (first, second) = frame.split_at(frame.len() / 2);
+
+        remote.write_all(first).await.unwrap();
+        let pending = tokio::time::timeout(Duration::from_millis(50), local.receive()).await;
+        assert!(pending.is_err());
+
+        remote.write_all(second).await.unwrap();
+        let message = local.receive().await.unwrap();
+        assert_eq!(message.payload.as_str(), Some("{\"type\":\"PING\"}"));
+    }
+}
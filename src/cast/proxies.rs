@@ -1,6 +1,9 @@
+// Reply proxies must tolerate firmware changes: unknown fields are ignored (never use
+// `deny_unknown_fields`) and fields that receivers may omit have defaults.
+
 /// Proxy classes for the `connection` channel.
 pub mod connection {
-    use serde::Serialize;
+    use serde::{Deserialize, Serialize};
 
     #[derive(Serialize, Debug)]
     pub struct ConnectionRequest {
@@ -8,6 +11,20 @@ pub mod connection {
         pub typ: String,
         #[serde(rename = "userAgent")]
         pub user_agent: String,
+        #[serde(rename = "connType", skip_serializing_if = "Option::is_none")]
+        pub conn_type: Option<i32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub origin: Option<serde_json::Value>,
+        #[serde(flatten)]
+        pub extra: serde_json::Map<String, serde_json::Value>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    pub struct CloseReply {
+        #[serde(default)]
+        pub reason: Option<String>,
+        #[serde(rename = "reasonCode", default)]
+        pub reason_code: Option<i32>,
     }
 }
 
@@ -24,9 +41,85 @@ pub mod heartbeat {
 
 /// Proxy classes for the `media` channel.
 pub mod media {
-    use std::ops::Range;
+    use std::{
+        convert::Infallible,
+        fmt::{self, Display, Formatter},
+        str::FromStr,
+    };
+
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    /// Describes how media queue should behave once the current item is finished.
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum RepeatMode {
+        /// Items are played in order, playback stops once the last item is finished.
+        Off,
+        /// Items are played in order, playback continues from the first item once the last one is
+        /// finished.
+        All,
+        /// The current item is repeated.
+        Single,
+        /// Items are played in order, once the last item is finished the queue is shuffled and
+        /// playback continues from the first item.
+        AllAndShuffle,
+        /// Repeat mode that is not known to this library, e.g. introduced by newer firmware.
+        Unknown(String),
+    }
 
-    use serde::{Deserialize, Serialize};
+    impl RepeatMode {
+        /// Returns the mode that follows this one when repeat button is pressed: `Off` -> `All` ->
+        /// `Single` -> `Off`. Any other mode is switched to `Off`.
+        pub fn next(&self) -> RepeatMode {
+            match *self {
+                RepeatMode::Off => RepeatMode::All,
+                RepeatMode::All => RepeatMode::Single,
+                _ => RepeatMode::Off,
+            }
+        }
+    }
+
+    impl FromStr for RepeatMode {
+        type Err = Infallible;
+
+        fn from_str(s: &str) -> Result<RepeatMode, Infallible> {
+            let repeat_mode = match s {
+                "REPEAT_OFF" => RepeatMode::Off,
+                "REPEAT_ALL" => RepeatMode::All,
+                "REPEAT_SINGLE" => RepeatMode::Single,
+                "REPEAT_ALL_AND_SHUFFLE" => RepeatMode::AllAndShuffle,
+                unknown => RepeatMode::Unknown(unknown.to_string()),
+            };
+
+            Ok(repeat_mode)
+        }
+    }
+
+    impl Display for RepeatMode {
+        fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+            let repeat_mode = match *self {
+                RepeatMode::Off => "REPEAT_OFF",
+                RepeatMode::All => "REPEAT_ALL",
+                RepeatMode::Single => "REPEAT_SINGLE",
+                RepeatMode::AllAndShuffle => "REPEAT_ALL_AND_SHUFFLE",
+                RepeatMode::Unknown(ref repeat_mode) => repeat_mode,
+            };
+
+            f.write_str(repeat_mode)
+        }
+    }
+
+    impl Serialize for RepeatMode {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(self)
+        }
+    }
+
+    impl<'de> Deserialize<'de> for RepeatMode {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let repeat_mode = String::deserialize(deserializer)?;
+            Ok(repeat_mode.parse().unwrap_or_else(|never| match never {}))
+        }
+    }
 
     #[derive(Serialize, Debug)]
     pub struct GetStatusRequest {
@@ -56,10 +149,190 @@ pub mod media {
         #[serde(rename = "currentTime", skip_serializing_if = "Option::is_none")]
         pub current_time: Option<f64>,
 
+        #[serde(rename = "activeTrackIds", skip_serializing_if = "Option::is_none")]
+        pub active_track_ids: Option<Vec<i32>>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub credentials: Option<String>,
+
+        #[serde(rename = "credentialsType", skip_serializing_if = "Option::is_none")]
+        pub credentials_type: Option<String>,
+
+        #[serde(rename = "queueData", skip_serializing_if = "Option::is_none")]
+        pub queue_data: Option<LoadQueueData>,
+
         #[serde(rename = "customData")]
-        pub custom_data: CustomData,
+        pub custom_data: serde_json::Value,
+
+        pub autoplay: bool,
+    }
+
+    #[derive(Serialize, Debug)]
+    pub struct LoadQueueData {
+        #[serde(rename = "repeatMode")]
+        pub repeat_mode: RepeatMode,
+    }
+
+    #[derive(Serialize, Debug)]
+    pub struct QueueLoadRequest {
+        #[serde(rename = "requestId")]
+        pub request_id: u32,
+
+        #[serde(rename = "sessionId")]
+        pub session_id: String,
+
+        #[serde(rename = "type")]
+        pub typ: String,
+
+        #[serde(flatten)]
+        pub queue_data: QueueData,
+    }
+
+    #[derive(Serialize, Debug)]
+    pub struct QueueInsertRequest {
+        #[serde(rename = "requestId")]
+        pub request_id: u32,
+
+        #[serde(rename = "mediaSessionId")]
+        pub media_session_id: i32,
+
+        #[serde(rename = "type")]
+        pub typ: String,
+
+        pub items: Vec<QueueItem>,
+
+        #[serde(rename = "insertBefore", skip_serializing_if = "Option::is_none")]
+        pub insert_before: Option<i32>,
+    }
+
+    #[derive(Serialize, Debug)]
+    pub struct QueueUpdateRequest {
+        #[serde(rename = "requestId")]
+        pub request_id: u32,
+
+        #[serde(rename = "mediaSessionId")]
+        pub media_session_id: i32,
+
+        #[serde(rename = "type")]
+        pub typ: String,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub jump: Option<i32>,
+
+        #[serde(rename = "currentItemId", skip_serializing_if = "Option::is_none")]
+        pub current_item_id: Option<i32>,
+
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        pub items: Vec<QueueItem>,
+
+        #[serde(rename = "repeatMode", skip_serializing_if = "Option::is_none")]
+        pub repeat_mode: Option<RepeatMode>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub shuffle: Option<bool>,
+    }
+
+    #[derive(Serialize, Debug)]
+    pub struct QueueRemoveRequest {
+        #[serde(rename = "requestId")]
+        pub request_id: u32,
+
+        #[serde(rename = "mediaSessionId")]
+        pub media_session_id: i32,
+
+        #[serde(rename = "type")]
+        pub typ: String,
+
+        #[serde(rename = "itemIds")]
+        pub item_ids: Vec<i32>,
+    }
+
+    #[derive(Serialize, Debug)]
+    pub struct QueueReorderRequest {
+        #[serde(rename = "requestId")]
+        pub request_id: u32,
+
+        #[serde(rename = "mediaSessionId")]
+        pub media_session_id: i32,
+
+        #[serde(rename = "type")]
+        pub typ: String,
+
+        #[serde(rename = "itemIds")]
+        pub item_ids: Vec<i32>,
+
+        #[serde(rename = "insertBefore", skip_serializing_if = "Option::is_none")]
+        pub insert_before: Option<i32>,
+    }
+
+    #[derive(Serialize, Debug)]
+    pub struct QueueGetItemIdsRequest {
+        #[serde(rename = "requestId")]
+        pub request_id: u32,
+
+        #[serde(rename = "mediaSessionId")]
+        pub media_session_id: i32,
+
+        #[serde(rename = "type")]
+        pub typ: String,
+    }
+
+    #[derive(Serialize, Debug)]
+    pub struct QueueGetItemsRequest {
+        #[serde(rename = "requestId")]
+        pub request_id: u32,
 
+        #[serde(rename = "mediaSessionId")]
+        pub media_session_id: i32,
+
+        #[serde(rename = "type")]
+        pub typ: String,
+
+        #[serde(rename = "itemIds")]
+        pub item_ids: Vec<i32>,
+    }
+
+    #[derive(Serialize, Debug)]
+    pub struct QueueData {
+        pub items: Vec<QueueItem>,
+
+        #[serde(rename = "startIndex")]
+        pub start_index: u32,
+
+        #[serde(rename = "repeatMode")]
+        pub repeat_mode: RepeatMode,
+    }
+
+    #[derive(Serialize, Deserialize, Debug)]
+    pub struct QueueItem {
+        #[serde(rename = "itemId", skip_serializing_if = "Option::is_none")]
+        pub item_id: Option<i32>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub media: Option<Media>,
+
+        #[serde(default = "default_autoplay")]
         pub autoplay: bool,
+
+        #[serde(rename = "preloadTime", skip_serializing_if = "Option::is_none")]
+        pub preload_time: Option<f64>,
+
+        #[serde(rename = "startTime", skip_serializing_if = "Option::is_none")]
+        pub start_time: Option<f64>,
+
+        #[serde(rename = "playbackDuration", skip_serializing_if = "Option::is_none")]
+        pub playback_duration: Option<f64>,
+
+        #[serde(rename = "customData", skip_serializing_if = "Option::is_none")]
+        pub custom_data: Option<serde_json::Value>,
+    }
+
+    fn default_autoplay() -> bool {
+        true
+    }
+
+    fn default_playback_rate() -> f32 {
+        1.0
     }
 
     #[derive(Serialize, Debug)]
@@ -77,6 +350,44 @@ pub mod media {
         pub custom_data: CustomData,
     }
 
+    #[derive(Serialize, Debug)]
+    pub struct PlaybackRateRequest {
+        #[serde(rename = "requestId")]
+        pub request_id: u32,
+
+        #[serde(rename = "mediaSessionId")]
+        pub media_session_id: i32,
+
+        #[serde(rename = "type")]
+        pub typ: String,
+
+        #[serde(rename = "playbackRate")]
+        pub playback_rate: f32,
+    }
+
+    #[derive(Serialize, Debug)]
+    pub struct StreamVolumeRequest {
+        #[serde(rename = "requestId")]
+        pub request_id: u32,
+
+        #[serde(rename = "mediaSessionId")]
+        pub media_session_id: i32,
+
+        #[serde(rename = "type")]
+        pub typ: String,
+
+        pub volume: StreamVolume,
+    }
+
+    #[derive(Serialize, Debug)]
+    pub struct StreamVolume {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub level: Option<f32>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub muted: Option<bool>,
+    }
+
     #[derive(Serialize, Debug)]
     pub struct PlaybackSeekRequest {
         #[serde(rename = "requestId")]
@@ -94,6 +405,9 @@ pub mod media {
         #[serde(rename = "currentTime")]
         pub current_time: Option<f32>,
 
+        #[serde(rename = "relativeTime", skip_serializing_if = "Option::is_none")]
+        pub relative_time: Option<f32>,
+
         #[serde(rename = "customData")]
         pub custom_data: CustomData,
     }
@@ -117,15 +431,24 @@ pub mod media {
 
         #[serde(skip_serializing_if = "Option::is_none")]
         pub language: Option<String>,
+
+        #[serde(rename = "textTrackStyle", skip_serializing_if = "Option::is_none")]
+        pub text_track_style: Option<TextTrackStyle>,
     }
 
     #[derive(Serialize, Deserialize, Debug)]
     pub struct Media {
-        #[serde(rename = "contentId")]
-        pub content_id: String,
+        #[serde(rename = "contentId", default, skip_serializing_if = "Option::is_none")]
+        pub content_id: Option<String>,
+        #[serde(
+            rename = "contentUrl",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub content_url: Option<String>,
         #[serde(rename = "streamType", default)]
         pub stream_type: String,
-        #[serde(rename = "contentType")]
+        #[serde(rename = "contentType", default)]
         pub content_type: String,
         #[serde(skip_serializing_if = "Option::is_none")]
         pub metadata: Option<Metadata>,
@@ -133,10 +456,81 @@ pub mod media {
         pub duration: Option<f32>,
         #[serde(default)]
         pub tracks: Vec<Track>,
+        #[serde(
+            rename = "textTrackStyle",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub text_track_style: Option<TextTrackStyle>,
+        #[serde(
+            rename = "hlsSegmentFormat",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub hls_segment_format: Option<String>,
+        #[serde(
+            rename = "hlsVideoSegmentFormat",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub hls_video_segment_format: Option<String>,
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        pub breaks: Vec<Break>,
+        #[serde(rename = "breakClips", default, skip_serializing_if = "Vec::is_empty")]
+        pub break_clips: Vec<BreakClip>,
         #[serde(rename = "customData", skip_serializing_if = "Option::is_none")]
         pub custom_data: Option<serde_json::Value>,
     }
 
+    #[derive(Serialize, Deserialize, Debug)]
+    pub struct Break {
+        pub id: String,
+        #[serde(rename = "breakClipIds", default)]
+        pub break_clip_ids: Vec<String>,
+        pub position: f32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub duration: Option<f32>,
+        #[serde(rename = "isWatched", default)]
+        pub is_watched: bool,
+        #[serde(rename = "isEmbedded", skip_serializing_if = "Option::is_none")]
+        pub is_embedded: Option<bool>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug)]
+    pub struct BreakClip {
+        pub id: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub title: Option<String>,
+        #[serde(rename = "contentId", skip_serializing_if = "Option::is_none")]
+        pub content_id: Option<String>,
+        #[serde(rename = "contentUrl", skip_serializing_if = "Option::is_none")]
+        pub content_url: Option<String>,
+        #[serde(rename = "contentType", skip_serializing_if = "Option::is_none")]
+        pub content_type: Option<String>,
+        #[serde(rename = "clickThroughUrl", skip_serializing_if = "Option::is_none")]
+        pub click_through_url: Option<String>,
+        #[serde(rename = "posterUrl", skip_serializing_if = "Option::is_none")]
+        pub poster_url: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub duration: Option<f32>,
+        #[serde(rename = "whenSkippable", skip_serializing_if = "Option::is_none")]
+        pub when_skippable: Option<f32>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    pub struct BreakStatus {
+        #[serde(rename = "currentBreakTime", default)]
+        pub current_break_time: f32,
+        #[serde(rename = "currentBreakClipTime", default)]
+        pub current_break_clip_time: f32,
+        #[serde(rename = "breakId")]
+        pub break_id: Option<String>,
+        #[serde(rename = "breakClipId")]
+        pub break_clip_id: Option<String>,
+        #[serde(rename = "whenSkippable")]
+        pub when_skippable: Option<f32>,
+    }
+
     #[derive(Serialize, Deserialize, Debug)]
     pub struct Metadata {
         #[serde(rename = "metadataType")]
@@ -204,6 +598,10 @@ pub mod media {
 
         #[serde(skip_serializing_if = "Option::is_none")]
         pub height: Option<u32>,
+
+        /// Properties of the metadata types that are not known.
+        #[serde(flatten)]
+        pub extra: serde_json::Map<String, serde_json::Value>,
     }
 
     impl Metadata {
@@ -231,6 +629,7 @@ pub mod media {
                 disc_number: None,
                 width: None,
                 height: None,
+                extra: serde_json::Map::new(),
             }
         }
     }
@@ -253,6 +652,16 @@ pub mod media {
         }
     }
 
+    #[derive(Deserialize, Debug)]
+    pub struct LiveSeekableRange {
+        pub start: Option<f32>,
+        pub end: Option<f32>,
+        #[serde(rename = "isMovingWindow", default)]
+        pub is_moving_window: bool,
+        #[serde(rename = "isLiveDone", default)]
+        pub is_live_done: bool,
+    }
+
     #[derive(Deserialize, Debug)]
     pub struct Status {
         #[serde(default, rename = "activeTrackIds")]
@@ -262,17 +671,31 @@ pub mod media {
         #[serde(default)]
         pub media: Option<Media>,
         #[serde(rename = "liveSeekableRange")]
-        pub live_seekable_range: Option<Range<f32>>,
-        #[serde(rename = "playbackRate")]
+        pub live_seekable_range: Option<LiveSeekableRange>,
+        #[serde(rename = "playbackRate", default = "default_playback_rate")]
         pub playback_rate: f32,
         #[serde(rename = "playerState")]
-        pub player_state: String,
+        pub player_state: Option<String>,
         #[serde(rename = "idleReason")]
         pub idle_reason: Option<String>,
         #[serde(rename = "currentTime")]
         pub current_time: Option<f32>,
-        #[serde(rename = "supportedMediaCommands")]
+        #[serde(rename = "supportedMediaCommands", default)]
         pub supported_media_commands: u32,
+        #[serde(default)]
+        pub volume: Option<super::receiver::Volume>,
+        #[serde(rename = "currentItemId")]
+        pub current_item_id: Option<i32>,
+        #[serde(rename = "loadingItemId")]
+        pub loading_item_id: Option<i32>,
+        #[serde(rename = "preloadedItemId")]
+        pub preloaded_item_id: Option<i32>,
+        #[serde(rename = "repeatMode")]
+        pub repeat_mode: Option<RepeatMode>,
+        #[serde(default)]
+        pub items: Vec<QueueItem>,
+        #[serde(rename = "breakStatus")]
+        pub break_status: Option<BreakStatus>,
     }
 
     #[derive(Deserialize, Debug)]
@@ -280,7 +703,7 @@ pub mod media {
         #[serde(rename = "requestId", default)]
         pub request_id: u32,
 
-        #[serde(rename = "type")]
+        #[serde(rename = "type", default)]
         pub typ: String,
 
         pub status: Vec<Status>,
@@ -296,32 +719,72 @@ pub mod media {
         pub name: Option<String>,
         #[serde(skip_serializing_if = "Option::is_none")]
         pub language: Option<String>,
+        #[serde(rename = "trackContentId", skip_serializing_if = "Option::is_none")]
+        pub track_content_id: Option<String>,
+        #[serde(rename = "trackContentType", skip_serializing_if = "Option::is_none")]
+        pub track_content_type: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub subtype: Option<String>,
+    }
+
+    #[derive(Serialize, Deserialize, Debug)]
+    pub struct TextTrackStyle {
+        #[serde(rename = "foregroundColor", skip_serializing_if = "Option::is_none")]
+        pub foreground_color: Option<String>,
+        #[serde(rename = "backgroundColor", skip_serializing_if = "Option::is_none")]
+        pub background_color: Option<String>,
+        #[serde(rename = "edgeType", skip_serializing_if = "Option::is_none")]
+        pub edge_type: Option<String>,
+        #[serde(rename = "edgeColor", skip_serializing_if = "Option::is_none")]
+        pub edge_color: Option<String>,
+        #[serde(rename = "fontScale", skip_serializing_if = "Option::is_none")]
+        pub font_scale: Option<f32>,
+        #[serde(rename = "fontFamily", skip_serializing_if = "Option::is_none")]
+        pub font_family: Option<String>,
+        #[serde(rename = "fontStyle", skip_serializing_if = "Option::is_none")]
+        pub font_style: Option<String>,
     }
 
     #[derive(Deserialize, Debug)]
-    pub struct LoadCancelledReply {
+    pub struct QueueItemIdsReply {
         #[serde(rename = "requestId")]
         pub request_id: u32,
+        #[serde(rename = "itemIds", default)]
+        pub item_ids: Vec<i32>,
     }
 
     #[derive(Deserialize, Debug)]
-    pub struct LoadFailedReply {
+    pub struct QueueItemsReply {
         #[serde(rename = "requestId")]
         pub request_id: u32,
+        #[serde(default)]
+        pub items: Vec<QueueItem>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    pub struct LoadCancelledReply {
+        #[serde(rename = "requestId", default)]
+        pub request_id: u32,
+    }
+
+    #[derive(Deserialize, Debug)]
+    pub struct LoadFailedReply {
+        #[serde(rename = "requestId", default)]
+        pub request_id: u32,
     }
 
     #[derive(Deserialize, Debug)]
     pub struct InvalidPlayerStateReply {
-        #[serde(rename = "requestId")]
+        #[serde(rename = "requestId", default)]
         pub request_id: u32,
     }
 
     #[derive(Deserialize, Debug)]
     pub struct InvalidRequestReply {
-        #[serde(rename = "requestId")]
+        #[serde(rename = "requestId", default)]
         pub request_id: u32,
 
-        #[serde(rename = "type")]
+        #[serde(rename = "type", default)]
         pub typ: String,
 
         pub reason: Option<String>,
@@ -339,9 +802,88 @@ pub mod media {
     }
 }
 
+/// Proxy classes for the `multizone` channel.
+pub mod multizone {
+    use serde::{Deserialize, Serialize};
+
+    use crate::cast::proxies::receiver::Volume;
+
+    #[derive(Serialize, Debug)]
+    pub struct GetStatusRequest {
+        #[serde(rename = "requestId")]
+        pub request_id: u32,
+
+        #[serde(rename = "type")]
+        pub typ: String,
+    }
+
+    #[derive(Serialize, Debug)]
+    pub struct SetDeviceVolumeRequest {
+        #[serde(rename = "requestId")]
+        pub request_id: u32,
+
+        #[serde(rename = "type")]
+        pub typ: String,
+
+        #[serde(rename = "deviceId")]
+        pub device_id: String,
+
+        pub volume: Volume,
+    }
+
+    #[derive(Deserialize, Debug)]
+    pub struct Device {
+        #[serde(rename = "deviceId")]
+        pub device_id: String,
+
+        #[serde(default)]
+        pub name: String,
+
+        #[serde(default)]
+        pub capabilities: i32,
+
+        #[serde(default)]
+        pub volume: Volume,
+    }
+
+    #[derive(Deserialize, Debug)]
+    pub struct Status {
+        #[serde(default)]
+        pub devices: Vec<Device>,
+
+        #[serde(rename = "isMultichannel", default)]
+        pub is_multichannel: bool,
+    }
+
+    #[derive(Deserialize, Debug)]
+    pub struct StatusReply {
+        #[serde(rename = "requestId", default)]
+        pub request_id: u32,
+
+        pub status: Status,
+    }
+
+    #[derive(Deserialize, Debug)]
+    pub struct DeviceReply {
+        #[serde(rename = "requestId", default)]
+        pub request_id: u32,
+
+        pub device: Device,
+    }
+
+    #[derive(Deserialize, Debug)]
+    pub struct DeviceRemovedReply {
+        #[serde(rename = "requestId", default)]
+        pub request_id: u32,
+
+        #[serde(rename = "deviceId")]
+        pub device_id: String,
+    }
+}
+
 /// Proxy classes for the `receiver` channel.
 pub mod receiver {
-    use std::borrow::Cow;
+    use std::{borrow::Cow, collections::HashMap};
 
     use serde::{Deserialize, Serialize};
 
@@ -355,6 +897,9 @@ pub mod receiver {
 
         #[serde(rename = "appId")]
         pub app_id: String,
+
+        #[serde(rename = "appParams", skip_serializing_if = "Option::is_none")]
+        pub app_params: Option<serde_json::Value>,
     }
 
     #[derive(Serialize, Debug)]
@@ -378,6 +923,18 @@ pub mod receiver {
         pub typ: String,
     }
 
+    #[derive(Serialize, Debug)]
+    pub struct GetAppAvailabilityRequest {
+        #[serde(rename = "requestId")]
+        pub request_id: u32,
+
+        #[serde(rename = "type")]
+        pub typ: String,
+
+        #[serde(rename = "appId")]
+        pub app_id: Vec<String>,
+    }
+
     #[derive(Serialize, Debug)]
     pub struct SetVolumeRequest {
         #[serde(rename = "requestId")]
@@ -391,10 +948,10 @@ pub mod receiver {
 
     #[derive(Deserialize, Debug)]
     pub struct StatusReply {
-        #[serde(rename = "requestId")]
+        #[serde(rename = "requestId", default)]
         pub request_id: u32,
 
-        #[serde(rename = "type")]
+        #[serde(rename = "type", default)]
         pub typ: String,
 
         pub status: Status,
@@ -412,6 +969,7 @@ pub mod receiver {
         pub is_stand_by: bool,
 
         /// Volume parameters of the currently active cast device.
+        #[serde(default)]
         pub volume: Volume,
     }
 
@@ -429,11 +987,17 @@ pub mod receiver {
         #[serde(default)]
         pub namespaces: Vec<AppNamespace>,
 
-        #[serde(rename = "displayName")]
+        #[serde(rename = "displayName", default)]
         pub display_name: String,
 
-        #[serde(rename = "statusText")]
+        #[serde(rename = "statusText", default)]
         pub status_text: String,
+
+        #[serde(rename = "launchedFromCloud", default)]
+        pub launched_from_cloud: bool,
+
+        #[serde(rename = "isOnEntertainmentDevice", default)]
+        pub is_on_entertainment_device: Option<bool>,
     }
 
     #[derive(Deserialize, Debug)]
@@ -441,21 +1005,46 @@ pub mod receiver {
         pub name: String,
     }
 
-    /// Structure that describes possible cast device volume options.
-    #[derive(Deserialize, Serialize, Debug)]
+    /// Structure that describes possible cast device volume options. Every field is optional since
+    /// both requests and replies may describe partial volume update (e.g. only mute state).
+    #[derive(Deserialize, Serialize, Debug, Default)]
     pub struct Volume {
         /// Volume level.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         pub level: Option<f32>,
         /// Mute/unmute state.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         pub muted: Option<bool>,
+        /// The way volume can be controlled (`attenuation`, `fixed` or `master`).
+        #[serde(
+            rename = "controlType",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub control_type: Option<String>,
+        /// Minimal volume level change the cast device supports.
+        #[serde(
+            rename = "stepInterval",
+            default,
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub step_interval: Option<f32>,
+    }
+
+    #[derive(Deserialize, Debug)]
+    pub struct LaunchStatusReply {
+        #[serde(rename = "launchRequestId", default)]
+        pub launch_request_id: u32,
+
+        pub status: String,
     }
 
     #[derive(Deserialize, Debug)]
     pub struct LaunchErrorReply {
-        #[serde(rename = "requestId")]
+        #[serde(rename = "requestId", default)]
         pub request_id: u32,
 
-        #[serde(rename = "type")]
+        #[serde(rename = "type", default)]
         pub typ: String,
 
         pub reason: Option<String>,
@@ -463,12 +1052,21 @@ pub mod receiver {
 
     #[derive(Deserialize, Debug)]
     pub struct InvalidRequestReply {
-        #[serde(rename = "requestId")]
+        #[serde(rename = "requestId", default)]
         pub request_id: u32,
 
-        #[serde(rename = "type")]
+        #[serde(rename = "type", default)]
         pub typ: String,
 
         pub reason: Option<String>,
     }
+
+    #[derive(Deserialize, Debug)]
+    pub struct AppAvailabilityReply {
+        #[serde(rename = "requestId", default)]
+        pub request_id: u32,
+
+        #[serde(default)]
+        pub availability: HashMap<String, String>,
+    }
 }
@@ -1,27 +1,71 @@
 use std::{
     borrow::Cow,
-    io::{Read, Write}, collections::HashSet
+    collections::HashMap,
+    io::{Read, Write},
+    time::{Duration, Instant},
 };
 
 use log::trace;
 
 use crate::{
     cast::proxies,
+    channels,
     errors::Error,
-    message_manager::{CastMessage, CastMessagePayload, MessageManager},
-    Lrc, Lock,
+    message_manager::{CastMessage, MessageManager},
+    namespaces, Lock, Lrc,
 };
 
-const CHANNEL_NAMESPACE: &str = "urn:x-cast:com.google.cast.tp.connection";
-const CHANNEL_USER_AGENT: &str = "RustCast";
+pub(crate) const CHANNEL_USER_AGENT: &str = "RustCast";
 
-const MESSAGE_TYPE_CONNECT: &str = "CONNECT";
-const MESSAGE_TYPE_CLOSE: &str = "CLOSE";
+pub(crate) const MESSAGE_TYPE_CONNECT: &str = "CONNECT";
+pub(crate) const MESSAGE_TYPE_CLOSE: &str = "CLOSE";
+
+/// Describes the type of the virtual connection.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ConnectionType {
+    /// Regular connection, receiver application is stopped once all strong connections are closed.
+    Strong,
+    /// Connection that doesn't keep receiver application alive, used to join an already running
+    /// application (e.g. as a secondary controller).
+    Weak,
+    /// Connection that is not visible to the receiver application.
+    Invisible,
+}
+
+impl From<ConnectionType> for i32 {
+    fn from(conn_type: ConnectionType) -> i32 {
+        match conn_type {
+            ConnectionType::Strong => 0,
+            ConnectionType::Weak => 1,
+            ConnectionType::Invisible => 2,
+        }
+    }
+}
+
+/// Additional options that can be sent along with CONNECT message.
+#[derive(Clone, Debug, Default)]
+pub struct ConnectionOptions {
+    /// Type of the virtual connection, receiver assumes `ConnectionType::Strong` if not provided.
+    pub conn_type: Option<ConnectionType>,
+    /// Information about the origin of the sender.
+    pub origin: Option<serde_json::Value>,
+    /// Application specific fields (e.g. connection credentials required by some licensed
+    /// receivers) that are merged into CONNECT message as is. Fields must not clash with the ones
+    /// channel sets itself (`type`, `userAgent`, `connType` and `origin`).
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
 
 #[derive(Clone, Debug)]
 pub enum ConnectionResponse {
     Connect,
-    Close,
+    /// Receiver has closed virtual connection, e.g. because application has been stopped or
+    /// another sender has taken over.
+    Close {
+        /// Reason of the close if receiver provided it, e.g. `CLOSED_BY_PEER`.
+        reason: Option<String>,
+        /// Numeric reason code if receiver provided it.
+        reason_code: Option<i32>,
+    },
     NotImplemented(String, serde_json::Value),
 }
 
@@ -30,8 +74,11 @@ where
     W: Read + Write,
 {
     sender: Cow<'a, str>,
+    user_agent: Cow<'a, str>,
     message_manager: Lrc<MessageManager<W>>,
-    connections: Lock<HashSet<String>>,
+    connections: Lock<HashMap<String, ConnectionOptions>>,
+    suppress_duplicates: bool,
+    keep_alive_interval: Option<Duration>,
 }
 
 impl<'a, W> ConnectionChannel<'a, W>
@@ -44,86 +91,659 @@ where
     {
         ConnectionChannel {
             sender: sender.into(),
+            user_agent: Cow::from(CHANNEL_USER_AGENT),
             message_manager,
-            connections: Lock::new(HashSet::new()),
+            connections: Lock::new(HashMap::new()),
+            suppress_duplicates: true,
+            keep_alive_interval: None,
         }
     }
 
+    /// Overrides user agent that is sent along with CONNECT and CLOSE messages (`RustCast` by
+    /// default).
+    ///
+    /// # Arguments
+    ///
+    /// * `user_agent` - user agent string that identifies sender application.
+    pub fn with_user_agent<S>(mut self, user_agent: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.user_agent = user_agent.into();
+        self
+    }
+
+    /// Controls whether `connect` calls are suppressed for destinations channel believes it's
+    /// already connected to (enabled by default). With suppression disabled every `connect` call
+    /// sends CONNECT message, which is handy when underlying socket may have been reset without
+    /// channel noticing.
+    ///
+    /// # Arguments
+    ///
+    /// * `suppress_duplicates` - whether repeated CONNECT messages should be suppressed.
+    pub fn with_duplicate_suppression(mut self, suppress_duplicates: bool) -> Self {
+        self.suppress_duplicates = suppress_duplicates;
+        self
+    }
+
+    /// Enables `tick` to re-CONNECT virtual connections that have been idle for `interval`, since
+    /// receiver may tear down idle connections to the application transport even if heartbeat is
+    /// alive. Disabled by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `interval` - how long virtual connection may stay idle before it's refreshed.
+    pub fn with_keep_alive_interval(mut self, interval: Duration) -> Self {
+        self.keep_alive_interval = Some(interval);
+        self
+    }
+
     pub fn connect<S>(&self, destination: S) -> Result<(), Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.connect_with_options(destination, ConnectionOptions::default())
+    }
+
+    /// Establishes virtual connection with the specified `destination` using additional connection
+    /// options (e.g. to join an already running application with a weak connection).
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - identifier of the receiver or application transport (e.g. `receiver-0`);
+    /// * `options` - `ConnectionOptions` to send along with CONNECT message.
+    pub fn connect_with_options<S>(
+        &self,
+        destination: S,
+        options: ConnectionOptions,
+    ) -> Result<(), Error>
     where
         S: Into<Cow<'a, str>>,
     {
         let destination = destination.into();
 
-        if self.connections.borrow().contains(destination.as_ref()) {
-            trace!("Suppressed call to connect to {} as channel is already connected", destination);
+        if self.suppress_duplicates && self.is_connected(destination.as_ref()) {
+            trace!(
+                destination = destination.as_ref();
+                "Suppressed call to connect to {} as channel is already connected",
+                destination
+            );
             return Ok(());
         }
 
-        let payload = serde_json::to_string(&proxies::connection::ConnectionRequest {
-            typ: MESSAGE_TYPE_CONNECT.to_string(),
-            user_agent: CHANNEL_USER_AGENT.to_string(),
-        })?;
-
-
-        self.message_manager.send(CastMessage {
-            namespace: CHANNEL_NAMESPACE.to_string(),
-            source: self.sender.to_string(),
-            destination: destination.to_string(),
-            payload: CastMessagePayload::String(payload),
-        })?;
-
-        self.connections.borrow_mut().insert(destination.into());
+        self.send_connect(destination, options)
+    }
 
-        Ok(())
+    /// Establishes virtual connection with the specified `destination` even if channel believes
+    /// it's already connected to it.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - identifier of the receiver or application transport (e.g. `receiver-0`).
+    pub fn connect_force<S>(&self, destination: S) -> Result<(), Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.send_connect(destination.into(), ConnectionOptions::default())
     }
 
     pub fn disconnect<S>(&self, destination: S) -> Result<(), Error>
     where
         S: Into<Cow<'a, str>>,
     {
-        let payload = serde_json::to_string(&proxies::connection::ConnectionRequest {
-            typ: MESSAGE_TYPE_CLOSE.to_string(),
-            user_agent: CHANNEL_USER_AGENT.to_string(),
-        })?;
-
-        self.message_manager.send(CastMessage {
-            namespace: CHANNEL_NAMESPACE.to_string(),
-            source: self.sender.to_string(),
-            destination: destination.into().to_string(),
-            payload: CastMessagePayload::String(payload),
-        })
+        let destination = destination.into();
+
+        let message = CastMessage::builder()
+            .namespace(namespaces::CONNECTION)
+            .source(self.sender.as_ref())
+            .destination(destination.as_ref())
+            .payload_json(&proxies::connection::ConnectionRequest {
+                typ: MESSAGE_TYPE_CLOSE.to_string(),
+                user_agent: self.user_agent.to_string(),
+                conn_type: None,
+                origin: None,
+                extra: serde_json::Map::new(),
+            })
+            .build()?;
+
+        self.message_manager.send(message)?;
+
+        self.connections.borrow_mut().remove(destination.as_ref());
+
+        Ok(())
+    }
+
+    /// Checks whether virtual connection with the specified `destination` is currently established.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - identifier of the receiver or application transport (e.g. `receiver-0`).
+    pub fn is_connected(&self, destination: &str) -> bool {
+        self.connections.borrow().contains_key(destination)
+    }
+
+    /// Forgets about all established virtual connections, so that subsequent `connect` calls send
+    /// CONNECT message again. Should be used once underlying stream is re-established.
+    pub fn reset(&self) {
+        self.connections.borrow_mut().clear();
+    }
+
+    /// Returns the list of all destinations virtual connection is currently established with.
+    pub fn connected_destinations(&self) -> Vec<String> {
+        self.connections.borrow().keys().cloned().collect()
+    }
+
+    /// Keeps virtual connections alive if keep-alive interval is set (see
+    /// `with_keep_alive_interval`): sends CONNECT message (with the original options) to every
+    /// connected destination no message has been exchanged with within the interval. Should be
+    /// called periodically, e.g. along with `HeartbeatChannel::tick`.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - current moment, usually `Instant::now()`.
+    ///
+    /// # Return value
+    ///
+    /// Destinations CONNECT message has been sent to.
+    pub fn tick(&self, now: Instant) -> Result<Vec<String>, Error> {
+        let interval = match self.keep_alive_interval {
+            Some(interval) => interval,
+            None => return Ok(vec![]),
+        };
+
+        let idle: Vec<(String, ConnectionOptions)> = self
+            .connections
+            .borrow()
+            .iter()
+            .filter(|(destination, _)| {
+                self.message_manager
+                    .last_activity_with(destination)
+                    .is_none_or(|at| now.saturating_duration_since(at) >= interval)
+            })
+            .map(|(destination, options)| (destination.clone(), options.clone()))
+            .collect();
+
+        let mut refreshed = Vec::with_capacity(idle.len());
+        for (destination, options) in idle {
+            trace!(
+                destination = destination.as_str();
+                "Refreshing idle virtual connection to {}",
+                destination
+            );
+
+            self.send_connect(Cow::from(destination.clone()), options)?;
+            refreshed.push(destination);
+        }
+
+        Ok(refreshed)
     }
 
     pub fn can_handle(&self, message: &CastMessage) -> bool {
-        message.namespace == CHANNEL_NAMESPACE
+        message.namespace == namespaces::CONNECTION
     }
 
     pub fn parse(&self, message: &CastMessage) -> Result<ConnectionResponse, Error> {
-        let reply = match message.payload {
-            CastMessagePayload::String(ref payload) => {
-                serde_json::from_str::<serde_json::Value>(payload)?
+        self.parse_envelope(message)
+            .map(|(_, _, response)| response)
+    }
+
+    /// Same as `parse`, but also returns the raw JSON payload the response has been parsed from,
+    /// e.g. to log or archive exactly what the cast device has sent.
+    ///
+    /// # Return value
+    ///
+    /// Tuple of the parsed response and raw payload.
+    pub fn parse_with_raw(
+        &self,
+        message: &CastMessage,
+    ) -> Result<(ConnectionResponse, serde_json::Value), Error> {
+        channels::parse_with_raw(message, |message| self.parse(message))
+    }
+
+    /// Same as `parse`, but also returns `requestId` (if any) and message type (empty for binary
+    /// payload) of `message`, e.g. for custom dispatching and logging.
+    ///
+    /// # Return value
+    ///
+    /// Tuple of the request id, message type and parsed response.
+    pub fn parse_envelope(
+        &self,
+        message: &CastMessage,
+    ) -> Result<(Option<i64>, String, ConnectionResponse), Error> {
+        channels::parse_envelope(message, namespaces::CONNECTION, |message_type, reply| {
+            Ok(match message_type {
+                MESSAGE_TYPE_CONNECT => ConnectionResponse::Connect,
+                MESSAGE_TYPE_CLOSE => {
+                    // Receiver has closed virtual connection on its side, so the next `connect` call
+                    // should not be suppressed.
+                    self.connections.borrow_mut().remove(&message.source);
+
+                    let reply: proxies::connection::CloseReply =
+                        serde_json::value::from_value(reply)?;
+
+                    ConnectionResponse::Close {
+                        reason: reply.reason,
+                        reason_code: reply.reason_code,
+                    }
+                }
+                _ => ConnectionResponse::NotImplemented(message_type.to_string(), reply),
+            })
+        })
+    }
+
+    /// Sends CONNECT message to the `destination` and remembers it as connected.
+    fn send_connect(
+        &self,
+        destination: Cow<'a, str>,
+        options: ConnectionOptions,
+    ) -> Result<(), Error> {
+        let message = CastMessage::builder()
+            .namespace(namespaces::CONNECTION)
+            .source(self.sender.as_ref())
+            .destination(destination.as_ref())
+            .payload_json(&proxies::connection::ConnectionRequest {
+                typ: MESSAGE_TYPE_CONNECT.to_string(),
+                user_agent: self.user_agent.to_string(),
+                conn_type: options.conn_type.map(i32::from),
+                origin: options.origin.clone(),
+                extra: options.extra.clone(),
+            })
+            .build()?;
+
+        self.message_manager.send(message)?;
+
+        self.connections
+            .borrow_mut()
+            .insert(destination.into(), options);
+
+        Ok(())
+    }
+}
+
+impl<'a, W> Drop for ConnectionChannel<'a, W>
+where
+    W: Read + Write,
+{
+    /// Closes all virtual connections that are still established. Failures are only logged since
+    /// the underlying stream may be already dead at this point.
+    fn drop(&mut self) {
+        // Stream lock may be poisoned or already borrowed if we're unwinding, don't risk a panic.
+        if std::thread::panicking() {
+            return;
+        }
+
+        for destination in self.connected_destinations() {
+            trace!(
+                destination = destination.as_str();
+                "Closing virtual connection with {}.",
+                destination
+            );
+
+            if let Err(err) = self.disconnect(destination.clone()) {
+                log::warn!(
+                    destination = destination.as_str();
+                    "Failed to close virtual connection with {}: {}",
+                    destination,
+                    err
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        channels::tests::MockTcpStream, message_manager::CastMessagePayload, DEFAULT_RECEIVER_ID,
+        DEFAULT_SENDER_ID,
+    };
+
+    use super::*;
+
+    fn create_channel<'a>() -> ConnectionChannel<'a, MockTcpStream> {
+        ConnectionChannel::new(
+            DEFAULT_SENDER_ID,
+            Lrc::new(MessageManager::new(MockTcpStream::new())),
+        )
+    }
+
+    /// Counts CONNECT messages channel has written so far.
+    fn connect_count(channel: &ConnectionChannel<MockTcpStream>) -> usize {
+        String::from_utf8_lossy(&channel.message_manager.inner().write_buffer)
+            .matches("\"CONNECT\"")
+            .count()
+    }
+
+    #[test]
+    fn test_connection_state_tracking() {
+        let channel = create_channel();
+
+        assert!(!channel.is_connected(DEFAULT_RECEIVER_ID));
+
+        channel.connect(DEFAULT_RECEIVER_ID).unwrap();
+        channel.connect("web-1").unwrap();
+
+        assert!(channel.is_connected(DEFAULT_RECEIVER_ID));
+        assert!(channel.is_connected("web-1"));
+
+        let mut destinations = channel.connected_destinations();
+        destinations.sort();
+        assert_eq!(
+            destinations,
+            vec!["receiver-0".to_string(), "web-1".to_string()]
+        );
+
+        channel.disconnect("web-1").unwrap();
+
+        assert!(!channel.is_connected("web-1"));
+        assert!(channel.is_connected(DEFAULT_RECEIVER_ID));
+    }
+
+    #[test]
+    fn test_reset_clears_connections() {
+        let channel = create_channel();
+
+        channel.connect(DEFAULT_RECEIVER_ID).unwrap();
+        channel.connect("web-1").unwrap();
+
+        assert_eq!(connect_count(&channel), 2);
+
+        channel.reset();
+
+        assert!(channel.connected_destinations().is_empty());
+
+        // Plain connect isn't suppressed anymore once connections are forgotten.
+        channel.connect(DEFAULT_RECEIVER_ID).unwrap();
+
+        assert_eq!(connect_count(&channel), 3);
+        assert!(channel.is_connected(DEFAULT_RECEIVER_ID));
+    }
+
+    #[test]
+    fn test_connect_force_bypasses_suppression() {
+        let channel = create_channel();
+
+        channel.connect(DEFAULT_RECEIVER_ID).unwrap();
+        channel.connect(DEFAULT_RECEIVER_ID).unwrap();
+        assert_eq!(connect_count(&channel), 1);
+
+        channel.connect_force(DEFAULT_RECEIVER_ID).unwrap();
+
+        assert_eq!(connect_count(&channel), 2);
+        assert!(channel.is_connected(DEFAULT_RECEIVER_ID));
+    }
+
+    #[test]
+    fn test_custom_user_agent() {
+        let channel = create_channel().with_user_agent("MyCaster/1.0");
+
+        channel.connect(DEFAULT_RECEIVER_ID).unwrap();
+        channel.disconnect(DEFAULT_RECEIVER_ID).unwrap();
+
+        let written =
+            String::from_utf8_lossy(&channel.message_manager.inner().write_buffer).to_string();
+        let connect_at = written.find("\"CONNECT\"").unwrap();
+        let close_at = written.find("\"CLOSE\"").unwrap();
+        let user_agent_at = written
+            .match_indices("\"userAgent\":\"MyCaster/1.0\"")
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        // Each of the frames carries custom user agent.
+        assert_eq!(user_agent_at.len(), 2);
+        assert!(user_agent_at[0] < close_at && user_agent_at[1] > close_at);
+        assert!(connect_at < close_at);
+        assert!(!written.contains(CHANNEL_USER_AGENT));
+    }
+
+    #[test]
+    fn test_connect_without_duplicate_suppression() {
+        let channel = create_channel();
+        channel.connect(DEFAULT_RECEIVER_ID).unwrap();
+        channel.connect(DEFAULT_RECEIVER_ID).unwrap();
+        assert_eq!(connect_count(&channel), 1);
+
+        let channel = create_channel().with_duplicate_suppression(false);
+        channel.connect(DEFAULT_RECEIVER_ID).unwrap();
+        channel.connect(DEFAULT_RECEIVER_ID).unwrap();
+        assert_eq!(connect_count(&channel), 2);
+        assert!(channel.is_connected(DEFAULT_RECEIVER_ID));
+    }
+
+    #[test]
+    fn test_connect_with_extra_fields() {
+        let channel = create_channel();
+        channel.connect(DEFAULT_RECEIVER_ID).unwrap();
+
+        let mut extra = serde_json::Map::new();
+        extra.insert(
+            "atvCredentials".to_string(),
+            serde_json::json!({ "token": "secret" }),
+        );
+        channel
+            .connect_with_options(
+                "web-1",
+                ConnectionOptions {
+                    extra,
+                    ..ConnectionOptions::default()
+                },
+            )
+            .unwrap();
+
+        let written =
+            String::from_utf8_lossy(&channel.message_manager.inner().write_buffer).to_string();
+        assert!(written.contains(r#"{"type":"CONNECT","userAgent":"RustCast"}"#));
+        assert!(written.contains(
+            r#"{"type":"CONNECT","userAgent":"RustCast","atvCredentials":{"token":"secret"}}"#
+        ));
+    }
+
+    #[test]
+    fn test_keep_alive() {
+        let message_manager = Lrc::new(MessageManager::new(MockTcpStream::new()));
+        let channel = ConnectionChannel::new(DEFAULT_SENDER_ID, Lrc::clone(&message_manager));
+
+        channel.connect("web-1").unwrap();
+        assert!(channel
+            .tick(Instant::now() + Duration::from_secs(60))
+            .unwrap()
+            .is_empty());
+
+        let channel = channel.with_keep_alive_interval(Duration::from_secs(10));
+        channel
+            .connect_with_options(
+                "web-2",
+                ConnectionOptions {
+                    conn_type: Some(ConnectionType::Weak),
+                    ..ConnectionOptions::default()
+                },
+            )
+            .unwrap();
+        message_manager.inner().write_buffer.clear();
+
+        assert!(channel
+            .tick(Instant::now() + Duration::from_secs(5))
+            .unwrap()
+            .is_empty());
+
+        let mut refreshed = channel
+            .tick(Instant::now() + Duration::from_secs(10))
+            .unwrap();
+        refreshed.sort();
+        assert_eq!(refreshed, vec!["web-1".to_string(), "web-2".to_string()]);
+
+        let written = String::from_utf8_lossy(&message_manager.inner().write_buffer).to_string();
+        assert_eq!(written.matches("\"CONNECT\"").count(), 2);
+        assert_eq!(written.matches("\"connType\":1").count(), 1);
+    }
+
+    #[test]
+    fn test_parse_close_removes_connection() {
+        let channel = create_channel();
+        channel.connect(DEFAULT_RECEIVER_ID).unwrap();
+
+        let message = CastMessage {
+            namespace: namespaces::CONNECTION.to_string(),
+            source: DEFAULT_RECEIVER_ID.to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String("{\"type\":\"CLOSE\"}".to_string()),
+        };
+
+        let response = channel.parse(&message).unwrap();
+
+        assert!(matches!(
+            response,
+            ConnectionResponse::Close {
+                reason: None,
+                reason_code: None
+            }
+        ));
+        assert!(!channel.is_connected(DEFAULT_RECEIVER_ID));
+    }
+
+    #[test]
+    fn test_parse_close_reason() {
+        let channel = create_channel();
+
+        let message = CastMessage {
+            namespace: namespaces::CONNECTION.to_string(),
+            source: DEFAULT_RECEIVER_ID.to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String(
+                "{\"type\":\"CLOSE\",\"reason\":\"CLOSED_BY_PEER\",\"reasonCode\":5}".to_string(),
+            ),
+        };
+
+        match channel.parse(&message).unwrap() {
+            ConnectionResponse::Close {
+                reason,
+                reason_code,
+            } => {
+                assert_eq!(reason.as_deref(), Some("CLOSED_BY_PEER"));
+                assert_eq!(reason_code, Some(5));
             }
-            _ => {
-                return Err(Error::Internal(
-                    "Binary payload is not supported!".to_string(),
-                ))
+            response => panic!("expected ConnectionResponse::Close, but got {:?}", response),
+        }
+    }
+
+    #[test]
+    fn test_parse_binary_payload() {
+        let channel = create_channel();
+
+        let message = CastMessage {
+            namespace: namespaces::CONNECTION.to_string(),
+            source: DEFAULT_RECEIVER_ID.to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::Binary(vec![1, 2, 3]),
+        };
+
+        match channel.parse(&message).unwrap() {
+            ConnectionResponse::NotImplemented(message_type, payload) => {
+                assert!(message_type.is_empty());
+                assert_eq!(payload, serde_json::json!([1, 2, 3]));
             }
+            response => panic!("expected NotImplemented, but got {:?}", response),
+        }
+    }
+
+    #[test]
+    fn test_drop_closes_connections() {
+        let message_manager = Lrc::new(MessageManager::new(MockTcpStream::new()));
+        let channel = ConnectionChannel::new(DEFAULT_SENDER_ID, Lrc::clone(&message_manager));
+        channel.connect(DEFAULT_RECEIVER_ID).unwrap();
+        channel.connect("web-1").unwrap();
+        channel.disconnect("web-1").unwrap();
+
+        let written = message_manager.inner().write_buffer.len();
+        drop(channel);
+
+        let stream = message_manager.inner();
+        let closed = String::from_utf8_lossy(&stream.write_buffer[written..]);
+        assert!(closed.contains("CLOSE"));
+        assert!(closed.contains(DEFAULT_RECEIVER_ID));
+        assert!(!closed.contains("web-1"));
+    }
+
+    #[test]
+    fn test_drop_with_broken_stream() {
+        let message_manager = Lrc::new(MessageManager::new(MockTcpStream::new()));
+        let channel = ConnectionChannel::new(DEFAULT_SENDER_ID, Lrc::clone(&message_manager));
+        channel.connect(DEFAULT_RECEIVER_ID).unwrap();
+
+        message_manager.inner().fail_writes = true;
+
+        drop(channel);
+    }
+
+    #[test]
+    fn test_parse_envelope() {
+        let channel = create_channel();
+        let message = |payload: serde_json::Value| CastMessage {
+            namespace: namespaces::CONNECTION.to_string(),
+            source: DEFAULT_RECEIVER_ID.to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String(payload.to_string()),
+        };
+
+        let (request_id, message_type, response) = channel
+            .parse_envelope(&message(
+                serde_json::json!({ "type": "CLOSE", "requestId": 5 }),
+            ))
+            .unwrap();
+        assert_eq!(request_id, Some(5));
+        assert_eq!(message_type, "CLOSE");
+        assert!(matches!(response, ConnectionResponse::Close { .. }));
+
+        let (request_id, message_type, response) = channel
+            .parse_envelope(&message(serde_json::json!({ "type": "UNKNOWN" })))
+            .unwrap();
+        assert_eq!(request_id, None);
+        assert_eq!(message_type, "UNKNOWN");
+        assert!(matches!(response, ConnectionResponse::NotImplemented(..)));
+
+        // Request id isn't limited to the range of ids this library generates.
+        let (request_id, _, _) = channel
+            .parse_envelope(&message(
+                serde_json::json!({ "type": "CLOSE", "requestId": 5_000_000_000i64 }),
+            ))
+            .unwrap();
+        assert_eq!(request_id, Some(5_000_000_000));
+    }
+
+    #[test]
+    fn test_parse_with_raw() {
+        let channel = create_channel();
+        let message = CastMessage {
+            namespace: namespaces::CONNECTION.to_string(),
+            source: DEFAULT_RECEIVER_ID.to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String(
+                "{\"type\":\"CLOSE\",\"reasonCode\":5}".to_string(),
+            ),
         };
 
-        let message_type = reply
-            .as_object()
-            .and_then(|object| object.get("type"))
-            .and_then(|property| property.as_str())
-            .unwrap_or("")
-            .to_string();
-
-        let response = match message_type.as_ref() {
-            MESSAGE_TYPE_CONNECT => ConnectionResponse::Connect,
-            MESSAGE_TYPE_CLOSE => ConnectionResponse::Close,
-            _ => ConnectionResponse::NotImplemented(message_type.to_string(), reply),
+        let (response, raw) = channel.parse_with_raw(&message).unwrap();
+
+        assert!(matches!(response, ConnectionResponse::Close { .. }));
+        assert_eq!(raw, serde_json::json!({ "type": "CLOSE", "reasonCode": 5 }));
+    }
+
+    #[test]
+    fn test_parse_unexpected_namespace() {
+        let channel = create_channel();
+        let message = CastMessage {
+            namespace: "urn:x-cast:com.google.cast.tp.heartbeat".to_string(),
+            source: DEFAULT_RECEIVER_ID.to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String("{\"type\":\"CLOSE\"}".to_string()),
         };
 
-        Ok(response)
+        match channel.parse(&message) {
+            Err(Error::UnexpectedNamespace { expected, actual }) => {
+                assert_eq!(expected, namespaces::CONNECTION);
+                assert_eq!(actual, "urn:x-cast:com.google.cast.tp.heartbeat");
+            }
+            result => panic!("expected Error::UnexpectedNamespace, but got {:?}", result),
+        }
     }
 }
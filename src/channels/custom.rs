@@ -0,0 +1,148 @@
+use std::{
+    borrow::Cow,
+    io::{Read, Write},
+};
+
+use crate::{
+    errors::Error,
+    message_manager::{CastMessage, CastMessagePayload, MessageManager},
+    Lrc,
+};
+
+const CUSTOM_NAMESPACE_PREFIX: &str = "urn:x-cast:";
+
+/// Channel that exchanges arbitrary JSON messages in the custom `namespace` defined by the
+/// (usually third-party) receiver application, e.g. `urn:x-cast:com.example.app`.
+pub struct CustomChannel<'a, W>
+where
+    W: Read + Write,
+{
+    namespace: Cow<'a, str>,
+    sender: Cow<'a, str>,
+    message_manager: Lrc<MessageManager<W>>,
+}
+
+impl<'a, W> CustomChannel<'a, W>
+where
+    W: Read + Write,
+{
+    pub fn new<S>(
+        namespace: S,
+        sender: S,
+        message_manager: Lrc<MessageManager<W>>,
+    ) -> CustomChannel<'a, W>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        CustomChannel {
+            namespace: namespace.into(),
+            sender: sender.into(),
+            message_manager,
+        }
+    }
+
+    /// Returns the namespace this channel works with.
+    pub fn namespace(&self) -> &str {
+        &self.namespace
+    }
+
+    /// Sends JSON `value` to the receiver application.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - `transport_id` of the receiver application (e.g. `web-1`);
+    /// * `value` - JSON value to send.
+    ///
+    /// # Errors
+    ///
+    /// Fails with `Error::Namespace` if channel namespace doesn't start with `urn:x-cast:`.
+    pub fn send_json<S>(&self, destination: S, value: &serde_json::Value) -> Result<(), Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        if !self.namespace.starts_with(CUSTOM_NAMESPACE_PREFIX) {
+            return Err(Error::Namespace(format!(
+                "'{}' should start with '{}' prefix",
+                self.namespace, CUSTOM_NAMESPACE_PREFIX
+            )));
+        }
+
+        self.message_manager.send(CastMessage {
+            namespace: self.namespace.to_string(),
+            source: self.sender.to_string(),
+            destination: destination.into().to_string(),
+            payload: CastMessagePayload::String(serde_json::to_string(value)?),
+        })
+    }
+
+    pub fn can_handle(&self, message: &CastMessage) -> bool {
+        message.namespace == self.namespace
+    }
+
+    pub fn parse(&self, message: &CastMessage) -> Result<serde_json::Value, Error> {
+        if !self.can_handle(message) {
+            return Err(Error::UnexpectedNamespace {
+                expected: self.namespace.to_string(),
+                actual: message.namespace.clone(),
+            });
+        }
+
+        match message.payload {
+            CastMessagePayload::String(ref payload) => Ok(serde_json::from_str(payload)?),
+            CastMessagePayload::Binary(_) => Err(Error::Parsing(format!(
+                "Message in '{}' namespace has binary payload.",
+                self.namespace
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{channels::tests::MockTcpStream, DEFAULT_SENDER_ID};
+
+    use super::*;
+
+    const NAMESPACE: &str = "urn:x-cast:com.example.app";
+
+    #[test]
+    fn test_send_json_and_parse() {
+        let message_manager = Lrc::new(MessageManager::new(MockTcpStream::new()));
+        let channel =
+            CustomChannel::new(NAMESPACE, DEFAULT_SENDER_ID, Lrc::clone(&message_manager));
+
+        channel
+            .send_json("web-1", &serde_json::json!({ "command": "next" }))
+            .unwrap();
+
+        let written = String::from_utf8_lossy(&message_manager.inner().write_buffer).to_string();
+        assert!(written.contains(NAMESPACE));
+        assert!(written.contains("{\"command\":\"next\"}"));
+
+        let message = CastMessage {
+            namespace: NAMESPACE.to_string(),
+            source: "web-1".to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String("{\"score\":42}".to_string()),
+        };
+
+        assert!(channel.can_handle(&message));
+        assert_eq!(
+            channel.parse(&message).unwrap(),
+            serde_json::json!({ "score": 42 })
+        );
+    }
+
+    #[test]
+    fn test_send_json_invalid_namespace() {
+        let channel = CustomChannel::new(
+            "com.example.app",
+            DEFAULT_SENDER_ID,
+            Lrc::new(MessageManager::new(MockTcpStream::new())),
+        );
+
+        let result = channel.send_json("web-1", &serde_json::json!({}));
+
+        assert!(matches!(result, Err(Error::Namespace(_))));
+    }
+}
@@ -1,19 +1,22 @@
 use std::{
     borrow::Cow,
     io::{Read, Write},
+    time::{Duration, Instant},
 };
 
 use crate::{
     cast::proxies,
+    channels,
     errors::Error,
     message_manager::{CastMessage, CastMessagePayload, MessageManager},
-    Lrc,
+    namespaces, Lock, Lrc,
 };
 
-const CHANNEL_NAMESPACE: &str = "urn:x-cast:com.google.cast.tp.heartbeat";
+const DEFAULT_PING_INTERVAL: Duration = Duration::from_secs(5);
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(15);
 
-const MESSAGE_TYPE_PING: &str = "PING";
-const MESSAGE_TYPE_PONG: &str = "PONG";
+pub(crate) const MESSAGE_TYPE_PING: &str = "PING";
+pub(crate) const MESSAGE_TYPE_PONG: &str = "PONG";
 
 #[derive(Clone, Debug)]
 pub enum HeartbeatResponse {
@@ -22,6 +25,17 @@ pub enum HeartbeatResponse {
     NotImplemented(String, serde_json::Value),
 }
 
+/// Connection liveness reported by `HeartbeatChannel::tick`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum HeartbeatStatus {
+    /// PING message has just been sent to the device.
+    PingSent,
+    /// Device has sent PONG (or any other message) since the last PING, nothing had to be sent.
+    PongReceived,
+    /// Device hasn't replied to the last PING yet, but `timeout` hasn't elapsed either.
+    AwaitingPong,
+}
+
 pub struct HeartbeatChannel<'a, W>
 where
     W: Read + Write,
@@ -29,6 +43,10 @@ where
     sender: Cow<'a, str>,
     receiver: Cow<'a, str>,
     message_manager: Lrc<MessageManager<W>>,
+    ping_interval: Duration,
+    timeout: Duration,
+    created_at: Instant,
+    last_ping_at: Lock<Option<Instant>>,
 }
 
 impl<'a, W> HeartbeatChannel<'a, W>
@@ -47,16 +65,151 @@ where
             sender: sender.into(),
             receiver: receiver.into(),
             message_manager,
+            ping_interval: DEFAULT_PING_INTERVAL,
+            timeout: DEFAULT_TIMEOUT,
+            created_at: Instant::now(),
+            last_ping_at: Lock::new(None),
+        }
+    }
+
+    /// Overrides how often `tick` should send PING messages (5 seconds by default).
+    ///
+    /// # Arguments
+    ///
+    /// * `ping_interval` - minimal interval between two consecutive PING messages.
+    pub fn with_ping_interval(mut self, ping_interval: Duration) -> Self {
+        self.ping_interval = ping_interval;
+        self
+    }
+
+    /// Overrides how long `tick` should wait for any message from the device before considering
+    /// connection dead (15 seconds by default).
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - maximum allowed period of silence from the device.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Keeps connection alive: sends PING message if `ping_interval` has elapsed since the last one
+    /// and checks that the device is still responsive. Any message received from the device (not
+    /// only PONG) counts as a sign of life, so busy sessions are not considered dead.
+    ///
+    /// # Arguments
+    ///
+    /// * `now` - current moment, usually `Instant::now()`.
+    ///
+    /// # Return value
+    ///
+    /// Whether PING message has been sent, device has replied to the last one or hasn't replied
+    /// yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if nothing has been received from the device within `timeout`, or
+    /// an error if PING message can't be sent.
+    pub fn tick(&self, now: Instant) -> Result<HeartbeatStatus, Error> {
+        let last_received_at = self.message_manager.last_received_at();
+        let last_activity_at =
+            last_received_at.map_or(self.created_at, |at| at.max(self.created_at));
+
+        if now.saturating_duration_since(last_activity_at) >= self.timeout {
+            return Err(Error::Timeout(format!(
+                "No messages received from the device within {:?}.",
+                self.timeout
+            )));
+        }
+
+        let last_ping_at = *self.last_ping_at.borrow();
+        let last_ping_at = match last_ping_at {
+            Some(at) if now.saturating_duration_since(at) < self.ping_interval => at,
+            _ => {
+                self.send_ping()?;
+                *self.last_ping_at.borrow_mut() = Some(now);
+
+                return Ok(HeartbeatStatus::PingSent);
+            }
+        };
+
+        if last_received_at.is_some_and(|at| at >= last_ping_at) {
+            Ok(HeartbeatStatus::PongReceived)
+        } else {
+            Ok(HeartbeatStatus::AwaitingPong)
         }
     }
 
     pub fn ping(&self) -> Result<(), Error> {
+        self.send_ping()?;
+        *self.last_ping_at.borrow_mut() = Some(Instant::now());
+
+        Ok(())
+    }
+
+    /// Measures round-trip time to the device: sends PING message and waits for PONG reply. PONG
+    /// messages don't carry anything to correlate them with PING, so the next PONG received
+    /// within `timeout` is assumed to be the reply. Other messages received meanwhile are kept in
+    /// the buffer and can be later retrieved with `MessageManager::receive`.
+    ///
+    /// Timeout is checked the same way `MessageManager::receive_find_map_with_timeout` checks it.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - how long to wait for PONG reply.
+    ///
+    /// # Return value
+    ///
+    /// Time elapsed between sending PING and receiving PONG.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if PONG hasn't been received within `timeout`.
+    pub fn ping_rtt(&self, timeout: Duration) -> Result<Duration, Error> {
+        let sent_at = Instant::now();
+        let deadline = sent_at + timeout;
+        let timeout_error = || {
+            Error::Timeout(format!(
+                "No PONG received from the device within {:?}.",
+                timeout
+            ))
+        };
+
+        self.send_ping()?;
+        *self.last_ping_at.borrow_mut() = Some(sent_at);
+
+        loop {
+            let result = self.message_manager.receive_find_map(|message| {
+                if self.can_handle(message) {
+                    if let HeartbeatResponse::Pong = self.parse(message)? {
+                        return Ok(Some(sent_at.elapsed()));
+                    }
+                }
+
+                if Instant::now() >= deadline {
+                    // Message has been read already, so it shouldn't be lost along with the wait.
+                    self.message_manager.keep_out_of_band(message.clone());
+                    return Err(timeout_error());
+                }
+
+                Ok(None)
+            });
+
+            match result {
+                Err(Error::Timeout(_)) if Instant::now() < deadline => {}
+                Err(Error::Timeout(_)) => return Err(timeout_error()),
+                result => return result,
+            }
+        }
+    }
+
+    fn send_ping(&self) -> Result<(), Error> {
         let payload = serde_json::to_string(&proxies::heartbeat::HeartBeatRequest {
             typ: MESSAGE_TYPE_PING.to_string(),
         })?;
 
         self.message_manager.send(CastMessage {
-            namespace: CHANNEL_NAMESPACE.to_string(),
+            namespace: namespaces::HEARTBEAT.to_string(),
             source: self.sender.to_string(),
             destination: self.receiver.to_string(),
             payload: CastMessagePayload::String(payload),
@@ -69,7 +222,7 @@ where
         })?;
 
         self.message_manager.send(CastMessage {
-            namespace: CHANNEL_NAMESPACE.to_string(),
+            namespace: namespaces::HEARTBEAT.to_string(),
             source: self.sender.to_string(),
             destination: destination.into().to_string(),
             payload: CastMessagePayload::String(payload),
@@ -77,34 +230,153 @@ where
     }
 
     pub fn can_handle(&self, message: &CastMessage) -> bool {
-        message.namespace == CHANNEL_NAMESPACE
+        message.namespace == namespaces::HEARTBEAT
     }
 
     pub fn parse(&self, message: &CastMessage) -> Result<HeartbeatResponse, Error> {
-        let reply = match message.payload {
-            CastMessagePayload::String(ref payload) => {
-                serde_json::from_str::<serde_json::Value>(payload)?
-            }
-            _ => {
-                return Err(Error::Internal(
-                    "Binary payload is not supported!".to_string(),
-                ))
-            }
-        };
+        self.parse_envelope(message)
+            .map(|(_, _, response)| response)
+    }
 
-        let message_type = reply
-            .as_object()
-            .and_then(|object| object.get("type"))
-            .and_then(|property| property.as_str())
-            .unwrap_or("")
-            .to_string();
-
-        let response = match message_type.as_ref() {
-            MESSAGE_TYPE_PING => HeartbeatResponse::Ping,
-            MESSAGE_TYPE_PONG => HeartbeatResponse::Pong,
-            _ => HeartbeatResponse::NotImplemented(message_type.to_string(), reply),
-        };
+    /// Same as `parse`, but also returns the raw JSON payload the response has been parsed from,
+    /// e.g. to log or archive exactly what the cast device has sent.
+    ///
+    /// # Return value
+    ///
+    /// Tuple of the parsed response and raw payload.
+    pub fn parse_with_raw(
+        &self,
+        message: &CastMessage,
+    ) -> Result<(HeartbeatResponse, serde_json::Value), Error> {
+        channels::parse_with_raw(message, |message| self.parse(message))
+    }
+
+    /// Same as `parse`, but also returns `requestId` (if any) and message type (empty for binary
+    /// payload) of `message`, e.g. for custom dispatching and logging.
+    ///
+    /// # Return value
+    ///
+    /// Tuple of the request id, message type and parsed response.
+    pub fn parse_envelope(
+        &self,
+        message: &CastMessage,
+    ) -> Result<(Option<i64>, String, HeartbeatResponse), Error> {
+        channels::parse_envelope(message, namespaces::HEARTBEAT, parse_response)
+    }
+}
+
+/// Parses JSON payload of the heartbeat message with `message_type` into `HeartbeatResponse`.
+pub(crate) fn parse_response(
+    message_type: &str,
+    reply: serde_json::Value,
+) -> Result<HeartbeatResponse, Error> {
+    Ok(match message_type {
+        MESSAGE_TYPE_PING => HeartbeatResponse::Ping,
+        MESSAGE_TYPE_PONG => HeartbeatResponse::Pong,
+        _ => HeartbeatResponse::NotImplemented(message_type.to_string(), reply),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{channels::tests::MockTcpStream, DEFAULT_RECEIVER_ID, DEFAULT_SENDER_ID};
+
+    use super::*;
+
+    #[test]
+    fn test_tick_pings_and_times_out() {
+        let channel = HeartbeatChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::new(MessageManager::new(MockTcpStream::new())),
+        )
+        .with_ping_interval(Duration::from_secs(2))
+        .with_timeout(Duration::from_secs(10));
+
+        let start = channel.created_at;
+
+        assert_eq!(channel.tick(start).unwrap(), HeartbeatStatus::PingSent);
+        assert_eq!(
+            channel.tick(start + Duration::from_secs(1)).unwrap(),
+            HeartbeatStatus::AwaitingPong
+        );
+        assert_eq!(
+            channel.tick(start + Duration::from_secs(3)).unwrap(),
+            HeartbeatStatus::PingSent
+        );
+        match channel.tick(start + Duration::from_secs(10)) {
+            Err(Error::Timeout(_)) => {}
+            result => panic!("expected Error::Timeout, but got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_tick_reports_pong() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&heartbeat_message(MESSAGE_TYPE_PONG));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = HeartbeatChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::clone(&message_manager),
+        );
+
+        let start = Instant::now();
+        assert_eq!(channel.tick(start).unwrap(), HeartbeatStatus::PingSent);
+
+        message_manager.receive().unwrap();
+        assert_eq!(
+            channel.tick(start + Duration::from_millis(500)).unwrap(),
+            HeartbeatStatus::PongReceived
+        );
+    }
+
+    fn heartbeat_message(message_type: &str) -> CastMessage {
+        CastMessage {
+            namespace: namespaces::HEARTBEAT.to_string(),
+            source: DEFAULT_RECEIVER_ID.to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String(
+                serde_json::json!({ "type": message_type }).to_string(),
+            ),
+        }
+    }
+
+    #[test]
+    fn test_ping_rtt() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&heartbeat_message(MESSAGE_TYPE_PING));
+        stream.add_message(&heartbeat_message(MESSAGE_TYPE_PONG));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = HeartbeatChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::clone(&message_manager),
+        );
+
+        let rtt = channel.ping_rtt(Duration::from_secs(5)).unwrap();
+
+        assert!(rtt < Duration::from_secs(5));
+        // PING from the device isn't lost while waiting for PONG.
+        let message = message_manager.receive().unwrap();
+        assert!(matches!(
+            channel.parse(&message).unwrap(),
+            HeartbeatResponse::Ping
+        ));
+    }
+
+    #[test]
+    fn test_ping_rtt_timeout() {
+        let mut stream = MockTcpStream::new();
+        stream.timeout_when_empty = true;
+        let channel = HeartbeatChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::new(MessageManager::new(stream)),
+        );
+
+        let result = channel.ping_rtt(Duration::ZERO);
 
-        Ok(response)
+        assert!(matches!(result, Err(Error::Timeout(_))));
     }
 }
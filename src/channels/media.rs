@@ -1,35 +1,55 @@
 use std::{
     borrow::Cow,
-    io::{Read, Write},
+    fmt::{self, Display, Formatter},
+    io::{ErrorKind, Read, Write},
     str::FromStr,
-    string::ToString, ops::Range,
+    thread,
+    time::{Duration, Instant},
 };
 
 use crate::{
     cast::proxies,
+    channels::{self, connection, receiver, receiver::Volume},
     errors::Error,
-    message_manager::{CastMessage, CastMessagePayload, MessageManager},
-    Lrc,
+    message_manager::{CastMessage, CastMessagePayload, MessageManager, StopHandle},
+    namespaces, Lrc,
 };
 
-const CHANNEL_NAMESPACE: &str = "urn:x-cast:com.google.cast.media";
+pub use crate::cast::proxies::media::RepeatMode;
 
-const MESSAGE_TYPE_GET_STATUS: &str = "GET_STATUS";
+pub(crate) const MESSAGE_TYPE_GET_STATUS: &str = "GET_STATUS";
 const MESSAGE_TYPE_LOAD: &str = "LOAD";
-const MESSAGE_TYPE_PLAY: &str = "PLAY";
-const MESSAGE_TYPE_PAUSE: &str = "PAUSE";
-const MESSAGE_TYPE_STOP: &str = "STOP";
+pub(crate) const MESSAGE_TYPE_PLAY: &str = "PLAY";
+pub(crate) const MESSAGE_TYPE_PAUSE: &str = "PAUSE";
+pub(crate) const MESSAGE_TYPE_STOP: &str = "STOP";
 const MESSAGE_TYPE_SEEK: &str = "SEEK";
+const MESSAGE_TYPE_SET_PLAYBACK_RATE: &str = "SET_PLAYBACK_RATE";
+const MESSAGE_TYPE_SET_VOLUME: &str = "SET_VOLUME";
 const MESSAGE_TYPE_EDIT_TRACKS_INFO: &str = "EDIT_TRACKS_INFO";
+const MESSAGE_TYPE_QUEUE_LOAD: &str = "QUEUE_LOAD";
+const MESSAGE_TYPE_QUEUE_INSERT: &str = "QUEUE_INSERT";
+const MESSAGE_TYPE_QUEUE_UPDATE: &str = "QUEUE_UPDATE";
+const MESSAGE_TYPE_QUEUE_NEXT: &str = "QUEUE_NEXT";
+const MESSAGE_TYPE_QUEUE_PREV: &str = "QUEUE_PREV";
+const MESSAGE_TYPE_QUEUE_REMOVE: &str = "QUEUE_REMOVE";
+const MESSAGE_TYPE_QUEUE_REORDER: &str = "QUEUE_REORDER";
+const MESSAGE_TYPE_QUEUE_GET_ITEM_IDS: &str = "QUEUE_GET_ITEM_IDS";
+const MESSAGE_TYPE_QUEUE_GET_ITEMS: &str = "QUEUE_GET_ITEMS";
 const MESSAGE_TYPE_MEDIA_STATUS: &str = "MEDIA_STATUS";
+const MESSAGE_TYPE_QUEUE_ITEM_IDS: &str = "QUEUE_ITEM_IDS";
+const MESSAGE_TYPE_QUEUE_ITEMS: &str = "QUEUE_ITEMS";
 const MESSAGE_TYPE_LOAD_CANCELLED: &str = "LOAD_CANCELLED";
 const MESSAGE_TYPE_LOAD_FAILED: &str = "LOAD_FAILED";
 const MESSAGE_TYPE_INVALID_PLAYER_STATE: &str = "INVALID_PLAYER_STATE";
 const MESSAGE_TYPE_INVALID_REQUEST: &str = "INVALID_REQUEST";
 const MESSAGE_TYPE_ERROR: &str = "ERROR";
 
+/// Type of the `Error::CastError` pending media request fails with if the receiver application
+/// stops while the reply is awaited.
+pub const ERROR_TYPE_APP_STOPPED: &str = "APP_STOPPED";
+
 /// Describes the way cast device should stream content.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum StreamType {
     /// This variant allows cast device to automatically choose whatever way it's most comfortable
     /// with.
@@ -52,15 +72,113 @@ impl FromStr for StreamType {
     }
 }
 
-impl ToString for StreamType {
-    fn to_string(&self) -> String {
+impl Display for StreamType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         let stream_type = match *self {
             StreamType::None => "NONE",
             StreamType::Buffered => "BUFFERED",
             StreamType::Live => "LIVE",
         };
 
-        stream_type.to_string()
+        f.write_str(stream_type)
+    }
+}
+
+/// Checks that `url` starts with a scheme (RFC 3986), any scheme is accepted since receiver
+/// applications may use custom ones.
+fn has_url_scheme(url: &str) -> bool {
+    match url.split_once(':') {
+        Some((scheme, rest)) => {
+            !rest.is_empty()
+                && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+        }
+        None => false,
+    }
+}
+
+/// MIME content type of the HLS streams.
+pub const CONTENT_TYPE_HLS: &str = "application/vnd.apple.mpegurl";
+/// MIME content type of the MPEG-DASH streams.
+pub const CONTENT_TYPE_DASH: &str = "application/dash+xml";
+
+/// Format of the HLS audio segments.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum HlsSegmentFormat {
+    Aac,
+    Ac3,
+    EAc3,
+    Fmp4,
+    Mp3,
+    Ts,
+    TsAac,
+}
+
+impl FromStr for HlsSegmentFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<HlsSegmentFormat, Error> {
+        match s {
+            "aac" => Ok(HlsSegmentFormat::Aac),
+            "ac3" => Ok(HlsSegmentFormat::Ac3),
+            "e_ac3" => Ok(HlsSegmentFormat::EAc3),
+            "fmp4" => Ok(HlsSegmentFormat::Fmp4),
+            "mp3" => Ok(HlsSegmentFormat::Mp3),
+            "ts" => Ok(HlsSegmentFormat::Ts),
+            "ts_aac" => Ok(HlsSegmentFormat::TsAac),
+            _ => Err(Error::Internal(format!("Unknown HLS segment format {}", s))),
+        }
+    }
+}
+
+impl Display for HlsSegmentFormat {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let segment_format = match *self {
+            HlsSegmentFormat::Aac => "aac",
+            HlsSegmentFormat::Ac3 => "ac3",
+            HlsSegmentFormat::EAc3 => "e_ac3",
+            HlsSegmentFormat::Fmp4 => "fmp4",
+            HlsSegmentFormat::Mp3 => "mp3",
+            HlsSegmentFormat::Ts => "ts",
+            HlsSegmentFormat::TsAac => "ts_aac",
+        };
+
+        f.write_str(segment_format)
+    }
+}
+
+/// Format of the HLS video segments.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum HlsVideoSegmentFormat {
+    Fmp4,
+    Mpeg2Ts,
+}
+
+impl FromStr for HlsVideoSegmentFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<HlsVideoSegmentFormat, Error> {
+        match s {
+            "fmp4" => Ok(HlsVideoSegmentFormat::Fmp4),
+            "mpeg2_ts" => Ok(HlsVideoSegmentFormat::Mpeg2Ts),
+            _ => Err(Error::Internal(format!(
+                "Unknown HLS video segment format {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl Display for HlsVideoSegmentFormat {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let segment_format = match *self {
+            HlsVideoSegmentFormat::Fmp4 => "fmp4",
+            HlsVideoSegmentFormat::Mpeg2Ts => "mpeg2_ts",
+        };
+
+        f.write_str(segment_format)
     }
 }
 
@@ -74,6 +192,93 @@ pub enum Metadata {
     TvShow(TvShowMediaMetadata),
     MusicTrack(MusicTrackMediaMetadata),
     Photo(PhotoMediaMetadata),
+    /// Metadata of the type this library doesn't know about, holds raw JSON metadata object.
+    Unknown(serde_json::Value),
+}
+
+impl Metadata {
+    /// Returns images associated with the content, if metadata type supports them.
+    pub fn images(&self) -> &[Image] {
+        match *self {
+            Metadata::Generic(ref x) => &x.images,
+            Metadata::Movie(ref x) => &x.images,
+            Metadata::TvShow(ref x) => &x.images,
+            Metadata::MusicTrack(ref x) => &x.images,
+            Metadata::Photo(_) | Metadata::Unknown(_) => &[],
+        }
+    }
+
+    /// Picks the image that suits best to be displayed `target_width` pixels wide: the narrowest
+    /// one that is at least `target_width` wide, or the widest one if all images are narrower.
+    /// Images of unknown width are only picked if none of the images has known width.
+    ///
+    /// # Arguments
+    ///
+    /// * `target_width` - width (in pixels) image is going to be displayed with.
+    pub fn best_image(&self, target_width: u32) -> Option<&Image> {
+        let images = self.images();
+
+        images
+            .iter()
+            .filter(|image| image.width.is_some_and(|width| width >= target_width))
+            .min_by_key(|image| image.width)
+            .or_else(|| {
+                images
+                    .iter()
+                    .filter(|image| image.width.is_some())
+                    .max_by_key(|image| image.width)
+            })
+            .or_else(|| images.first())
+    }
+
+    fn decode(metadata: &proxies::media::Metadata) -> Metadata {
+        let images = || metadata.images.iter().map(Image::decode).collect();
+
+        match metadata.metadata_type {
+            0 => Metadata::Generic(GenericMediaMetadata {
+                title: metadata.title.clone(),
+                subtitle: metadata.subtitle.clone(),
+                images: images(),
+                release_date: metadata.release_date.clone(),
+            }),
+            1 => Metadata::Movie(MovieMediaMetadata {
+                title: metadata.title.clone(),
+                subtitle: metadata.subtitle.clone(),
+                studio: metadata.studio.clone(),
+                images: images(),
+                release_date: metadata.release_date.clone(),
+            }),
+            2 => Metadata::TvShow(TvShowMediaMetadata {
+                series_title: metadata.series_title.clone(),
+                episode_title: metadata.subtitle.clone(),
+                season: metadata.season,
+                episode: metadata.episode,
+                images: images(),
+                original_air_date: metadata.original_air_date.clone(),
+            }),
+            3 => Metadata::MusicTrack(MusicTrackMediaMetadata {
+                album_name: metadata.album_name.clone(),
+                title: metadata.title.clone(),
+                album_artist: metadata.album_artist.clone(),
+                artist: metadata.artist.clone(),
+                composer: metadata.composer.clone(),
+                track_number: metadata.track_number,
+                disc_number: metadata.disc_number,
+                images: images(),
+                release_date: metadata.release_date.clone(),
+            }),
+            4 => Metadata::Photo(PhotoMediaMetadata {
+                title: metadata.title.clone(),
+                artist: metadata.artist.clone(),
+                location: metadata.location.clone(),
+                latitude_longitude: metadata.latitude.zip(metadata.longitude),
+                width: metadata.width,
+                height: metadata.height,
+                creation_date_time: metadata.creation_date_time.clone(),
+            }),
+            _ => Metadata::Unknown(serde_json::to_value(metadata).unwrap_or_default()),
+        }
+    }
 }
 
 /// Generic media metadata.
@@ -165,8 +370,10 @@ pub struct PhotoMediaMetadata {
     pub location: Option<String>,
     /// Latitude and longitude of the location where the photograph was taken.
     pub latitude_longitude: Option<(f64, f64)>,
-    /// Width and height of the photograph in pixels.
-    pub dimensions: Option<(u32, u32)>,
+    /// Width of the photograph in pixels.
+    pub width: Option<u32>,
+    /// Height of the photograph in pixels.
+    pub height: Option<u32>,
     /// Date and time the photograph was taken, formatted as ISO 8601.
     pub creation_date_time: Option<String>,
 }
@@ -183,29 +390,40 @@ pub struct PhotoMediaMetadata {
 pub struct Image {
     /// URL of the image.
     pub url: String,
-    /// Width and height of the image.
-    pub dimensions: Option<(u32, u32)>,
+    /// Width of the image in pixels.
+    pub width: Option<u32>,
+    /// Height of the image in pixels.
+    pub height: Option<u32>,
 }
 
 impl Image {
     pub fn new(url: String) -> Image {
         Image {
             url,
-            dimensions: None,
+            width: None,
+            height: None,
         }
     }
 
     fn encode(&self) -> proxies::media::Image {
         proxies::media::Image {
             url: self.url.clone(),
-            width: self.dimensions.map(|d| d.0),
-            height: self.dimensions.map(|d| d.1),
+            width: self.width,
+            height: self.height,
+        }
+    }
+
+    fn decode(image: &proxies::media::Image) -> Image {
+        Image {
+            url: image.url.clone(),
+            width: image.width,
+            height: image.height,
         }
     }
 }
 
 /// Describes possible player states.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum PlayerState {
     /// Player has not been loaded yet.
     Idle,
@@ -215,6 +433,8 @@ pub enum PlayerState {
     Buffering,
     /// Player is paused.
     Paused,
+    /// Player is loading the media.
+    Loading,
 }
 
 impl FromStr for PlayerState {
@@ -226,26 +446,28 @@ impl FromStr for PlayerState {
             "PLAYING" => Ok(PlayerState::Playing),
             "BUFFERING" => Ok(PlayerState::Buffering),
             "PAUSED" => Ok(PlayerState::Paused),
+            "LOADING" => Ok(PlayerState::Loading),
             _ => Err(Error::Internal(format!("Unknown player state {}", s))),
         }
     }
 }
 
-impl ToString for PlayerState {
-    fn to_string(&self) -> String {
+impl Display for PlayerState {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         let player_state = match *self {
             PlayerState::Idle => "IDLE",
             PlayerState::Playing => "PLAYING",
             PlayerState::Buffering => "BUFFERING",
             PlayerState::Paused => "PAUSED",
+            PlayerState::Loading => "LOADING",
         };
 
-        player_state.to_string()
+        f.write_str(player_state)
     }
 }
 
 /// Describes possible player idle reasons.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum IdleReason {
     /// A sender requested to stop playback using the STOP command.
     Cancelled,
@@ -272,6 +494,19 @@ impl FromStr for IdleReason {
     }
 }
 
+impl Display for IdleReason {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let idle_reason = match *self {
+            IdleReason::Cancelled => "CANCELLED",
+            IdleReason::Interrupted => "INTERRUPTED",
+            IdleReason::Finished => "FINISHED",
+            IdleReason::Error => "ERROR",
+        };
+
+        f.write_str(idle_reason)
+    }
+}
+
 /// Describes the operation to perform with playback while seeking.
 #[derive(Copy, Clone, Debug)]
 pub enum ResumeState {
@@ -293,19 +528,37 @@ impl FromStr for ResumeState {
     }
 }
 
-impl ToString for ResumeState {
-    fn to_string(&self) -> String {
+impl Display for ResumeState {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         let resume_state = match *self {
             ResumeState::PlaybackStart => "PLAYBACK_START",
             ResumeState::PlaybackPause => "PLAYBACK_PAUSE",
         };
 
-        resume_state.to_string()
+        f.write_str(resume_state)
     }
 }
 
+/// Describes the position to seek to.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum SeekPosition {
+    /// Time in seconds since the beginning of the content.
+    Absolute(f32),
+    /// Time in seconds relative to the current position, negative values seek backwards.
+    Relative(f32),
+}
+
+/// Parameters of the SEEK request.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct SeekOptions {
+    /// Position to seek to, the current position is kept if not provided.
+    pub position: Option<SeekPosition>,
+    /// Playback state to apply after seeking, the current state is kept if not provided.
+    pub resume_state: Option<ResumeState>,
+}
+
 /// Media track type.
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum TrackType {
     /// Text track.
     Text,
@@ -328,6 +581,254 @@ impl FromStr for TrackType {
     }
 }
 
+impl Display for TrackType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let track_type = match *self {
+            TrackType::Text => "TEXT",
+            TrackType::Audio => "AUDIO",
+            TrackType::Video => "VIDEO",
+        };
+
+        f.write_str(track_type)
+    }
+}
+
+/// Type of the text track.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TextTrackType {
+    /// Transcription or translation of the dialogue, suitable for when the sound is available but
+    /// not understood.
+    Subtitles,
+    /// Transcription or translation of the dialogue, sound effects, relevant musical cues, and
+    /// other relevant audio information, suitable for when sound is unavailable or not clearly
+    /// audible.
+    Captions,
+    /// Textual descriptions of the video component of the media resource, intended for audio
+    /// synthesis when the visual component is obscured, unavailable, or unusable.
+    Descriptions,
+    /// Chapter titles, intended to be used for navigating the media resource.
+    Chapters,
+    /// Tracks intended for use from script.
+    Metadata,
+}
+
+impl FromStr for TextTrackType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<TextTrackType, Error> {
+        match s {
+            "SUBTITLES" => Ok(TextTrackType::Subtitles),
+            "CAPTIONS" => Ok(TextTrackType::Captions),
+            "DESCRIPTIONS" => Ok(TextTrackType::Descriptions),
+            "CHAPTERS" => Ok(TextTrackType::Chapters),
+            "METADATA" => Ok(TextTrackType::Metadata),
+            _ => Err(Error::Internal(format!("Unknown text track type {}", s))),
+        }
+    }
+}
+
+impl Display for TextTrackType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let text_track_type = match *self {
+            TextTrackType::Subtitles => "SUBTITLES",
+            TextTrackType::Captions => "CAPTIONS",
+            TextTrackType::Descriptions => "DESCRIPTIONS",
+            TextTrackType::Chapters => "CHAPTERS",
+            TextTrackType::Metadata => "METADATA",
+        };
+
+        f.write_str(text_track_type)
+    }
+}
+
+/// Color in RGBA format, represented by `#RRGGBBAA` (or `#RRGGBB` for opaque colors) hex string.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Color {
+    pub red: u8,
+    pub green: u8,
+    pub blue: u8,
+    pub alpha: u8,
+}
+
+impl Color {
+    pub fn new(red: u8, green: u8, blue: u8, alpha: u8) -> Color {
+        Color {
+            red,
+            green,
+            blue,
+            alpha,
+        }
+    }
+}
+
+impl FromStr for Color {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Color, Error> {
+        let invalid_color = || {
+            Error::Parsing(format!(
+                "Invalid color {}, expected #RRGGBBAA or #RRGGBB hex string",
+                s
+            ))
+        };
+
+        let hex = s.strip_prefix('#').ok_or_else(invalid_color)?;
+        if !(hex.len() == 6 || hex.len() == 8) || !hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(invalid_color());
+        }
+
+        let component = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).unwrap();
+
+        Ok(Color {
+            red: component(0),
+            green: component(2),
+            blue: component(4),
+            alpha: if hex.len() == 8 { component(6) } else { 0xFF },
+        })
+    }
+}
+
+impl Display for Color {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        write!(
+            f,
+            "#{:02X}{:02X}{:02X}{:02X}",
+            self.red, self.green, self.blue, self.alpha
+        )
+    }
+}
+
+/// Type of the edge of the text track font.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TextTrackEdgeType {
+    None,
+    Outline,
+    DropShadow,
+    Raised,
+    Depressed,
+}
+
+impl FromStr for TextTrackEdgeType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<TextTrackEdgeType, Error> {
+        match s {
+            "NONE" => Ok(TextTrackEdgeType::None),
+            "OUTLINE" => Ok(TextTrackEdgeType::Outline),
+            "DROP_SHADOW" => Ok(TextTrackEdgeType::DropShadow),
+            "RAISED" => Ok(TextTrackEdgeType::Raised),
+            "DEPRESSED" => Ok(TextTrackEdgeType::Depressed),
+            _ => Err(Error::Internal(format!(
+                "Unknown text track edge type {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl Display for TextTrackEdgeType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let edge_type = match *self {
+            TextTrackEdgeType::None => "NONE",
+            TextTrackEdgeType::Outline => "OUTLINE",
+            TextTrackEdgeType::DropShadow => "DROP_SHADOW",
+            TextTrackEdgeType::Raised => "RAISED",
+            TextTrackEdgeType::Depressed => "DEPRESSED",
+        };
+
+        f.write_str(edge_type)
+    }
+}
+
+/// Style of the text track font.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TextTrackFontStyle {
+    Normal,
+    Bold,
+    BoldItalic,
+    Italic,
+}
+
+impl FromStr for TextTrackFontStyle {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<TextTrackFontStyle, Error> {
+        match s {
+            "NORMAL" => Ok(TextTrackFontStyle::Normal),
+            "BOLD" => Ok(TextTrackFontStyle::Bold),
+            "BOLD_ITALIC" => Ok(TextTrackFontStyle::BoldItalic),
+            "ITALIC" => Ok(TextTrackFontStyle::Italic),
+            _ => Err(Error::Internal(format!(
+                "Unknown text track font style {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl Display for TextTrackFontStyle {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let font_style = match *self {
+            TextTrackFontStyle::Normal => "NORMAL",
+            TextTrackFontStyle::Bold => "BOLD",
+            TextTrackFontStyle::BoldItalic => "BOLD_ITALIC",
+            TextTrackFontStyle::Italic => "ITALIC",
+        };
+
+        f.write_str(font_style)
+    }
+}
+
+/// Describes the style of the text tracks (e.g. subtitles). Every property that is not provided
+/// is left up to the receiver application.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TextTrackStyle {
+    /// Foreground (text) color.
+    pub foreground_color: Option<Color>,
+    /// Background color.
+    pub background_color: Option<Color>,
+    /// Type of the font edge.
+    pub edge_type: Option<TextTrackEdgeType>,
+    /// Color of the font edge.
+    pub edge_color: Option<Color>,
+    /// Font scaling factor for the text track, the default is 1.0.
+    pub font_scale: Option<f32>,
+    /// Font family name, e.g. `Droid Sans`.
+    pub font_family: Option<String>,
+    /// Font style.
+    pub font_style: Option<TextTrackFontStyle>,
+}
+
+impl TextTrackStyle {
+    fn encode(&self) -> proxies::media::TextTrackStyle {
+        proxies::media::TextTrackStyle {
+            foreground_color: self.foreground_color.map(|color| color.to_string()),
+            background_color: self.background_color.map(|color| color.to_string()),
+            edge_type: self.edge_type.map(|edge_type| edge_type.to_string()),
+            edge_color: self.edge_color.map(|color| color.to_string()),
+            font_scale: self.font_scale,
+            font_family: self.font_family.clone(),
+            font_style: self.font_style.map(|font_style| font_style.to_string()),
+        }
+    }
+
+    fn decode(style: &proxies::media::TextTrackStyle) -> Result<TextTrackStyle, Error> {
+        fn parse<T: FromStr<Err = Error>>(value: &Option<String>) -> Result<Option<T>, Error> {
+            value.as_deref().map(T::from_str).transpose()
+        }
+
+        Ok(TextTrackStyle {
+            foreground_color: parse(&style.foreground_color)?,
+            background_color: parse(&style.background_color)?,
+            edge_type: parse(&style.edge_type)?,
+            edge_color: parse(&style.edge_color)?,
+            font_scale: style.font_scale,
+            font_family: style.font_family.clone(),
+            font_style: parse(&style.font_style)?,
+        })
+    }
+}
+
 #[derive(Clone, Debug, Default)]
 pub struct TrackSelection {
     /// Array of the Track trackIds that should be active.
@@ -347,6 +848,9 @@ pub struct TrackSelection {
     ///
     /// The language field will take precedence over activeTrackIds if both are specified.
     pub language: Option<String>,
+
+    /// Style of the text tracks, the current style is kept if not provided.
+    pub text_track_style: Option<TextTrackStyle>,
 }
 
 /// This data structure describes track metadata information.
@@ -360,6 +864,42 @@ pub struct Track {
     pub name: Option<String>,
     /// An RFC 5646 language tag. If the track subtype is Subtitles, this field is mandatory.
     pub language: Option<String>,
+    /// Identifier of the track content, usually URL of the track (e.g. WebVTT file).
+    pub track_content_id: Option<String>,
+    /// MIME content type of the track content, e.g. `text/vtt`.
+    pub track_content_type: Option<String>,
+    /// Type of the text track, only used for `TrackType::Text` tracks.
+    pub subtype: Option<TextTrackType>,
+}
+
+impl Track {
+    fn encode(&self) -> proxies::media::Track {
+        proxies::media::Track {
+            id: self.id,
+            typ: self.track_type.to_string(),
+            name: self.name.clone(),
+            language: self.language.clone(),
+            track_content_id: self.track_content_id.clone(),
+            track_content_type: self.track_content_type.clone(),
+            subtype: self.subtype.map(|subtype| subtype.to_string()),
+        }
+    }
+
+    fn decode(track: &proxies::media::Track) -> Result<Track, Error> {
+        Ok(Track {
+            id: track.id,
+            track_type: TrackType::from_str(track.typ.as_ref())?,
+            name: track.name.clone(),
+            language: track.language.clone(),
+            track_content_id: track.track_content_id.clone(),
+            track_content_type: track.track_content_type.clone(),
+            subtype: track
+                .subtype
+                .as_ref()
+                .map(|subtype| TextTrackType::from_str(subtype))
+                .transpose()?,
+        })
+    }
 }
 
 /// This data structure describes a media stream.
@@ -368,388 +908,90 @@ pub struct Media {
     /// Service-specific identifier of the content currently loaded by the media player. This is a
     /// free form string and is specific to the application. In most cases, this will be the URL to
     /// the media, but the sender can choose to pass a string that the receiver can interpret
-    /// properly. Max length: 1k.
-    pub content_id: String,
+    /// properly. Max length: 1k. May be `None` if `content_url` is provided, but at least one of them
+    /// should be.
+    pub content_id: Option<String>,
+    /// URL of the media, newer receivers use it to fetch the media instead of `content_id`, which
+    /// then can identify the content in the application-specific way (e.g. catalog ID).
+    pub content_url: Option<String>,
     /// Describes the type of media artifact.
     pub stream_type: StreamType,
-    /// MIME content type of the media being played.
+    /// MIME content type of the media being played (e.g. `CONTENT_TYPE_HLS`).
+    ///
+    /// Default Media Receiver supports MP4 (`video/mp4`, `audio/mp4`) and WebM (`video/webm`,
+    /// `audio/webm`) containers, MP3 (`audio/mpeg`), FLAC (`audio/flac`), WAV (`audio/wav`),
+    /// HLS (`CONTENT_TYPE_HLS`), MPEG-DASH (`CONTENT_TYPE_DASH`), Smooth Streaming
+    /// (`application/vnd.ms-sstr+xml`) as well as JPEG, PNG, GIF, BMP and WebP images. Other
+    /// receiver applications may support a different set.
     pub content_type: String,
     /// Generic, movie, TV show, music track, or photo metadata.
     pub metadata: Option<Metadata>,
     /// The media tracks.
     pub tracks: Vec<Track>,
+    /// Style of the text tracks.
+    pub text_track_style: Option<TextTrackStyle>,
+    /// Format of the HLS audio segments, helps receiver to pick the right demuxer.
+    pub hls_segment_format: Option<HlsSegmentFormat>,
+    /// Format of the HLS video segments, helps receiver to pick the right demuxer.
+    pub hls_video_segment_format: Option<HlsVideoSegmentFormat>,
     /// Duration of the currently playing stream in seconds.
     pub duration: Option<f32>,
+    /// Ad breaks (cue points) of the media.
+    pub breaks: Vec<Break>,
+    /// Ad creatives played during the `breaks`.
+    pub break_clips: Vec<BreakClip>,
 }
 
-/// Describes the current status of the media artifact with respect to the session.
-#[derive(Clone, Debug)]
-pub struct Status {
-    /// Unique id of the request that requested the status.
-    pub request_id: u32,
-    /// Detailed status of every media status entry.
-    pub entries: Vec<StatusEntry>,
-}
-
-/// Detailed status of the media artifact with respect to the session.
-#[derive(Clone, Debug)]
-pub struct StatusEntry {
-    /// List of IDs corresponding to the active Tracks.
-    pub active_track_ids: Vec<i32>,
-    /// Unique ID for the playback of this specific session. This ID is set by the receiver at LOAD
-    /// and can be used to identify a specific instance of a playback. For example, two playbacks of
-    /// "Wish you were here" within the same session would each have a unique mediaSessionId.
-    pub media_session_id: i32,
-    /// Full description of the content that is being played back. Only be returned in a status
-    /// messages if the Media has changed.
-    pub media: Option<Media>,
-    /// Seekable range of a live or event stream. It uses relative media time in seconds.
-    /// It will be undefined for VOD streams.
-    pub live_seekable_range: Option<Range<f32>>,
-    /// Indicates whether the media time is progressing, and at what rate. This is independent of
-    /// the player state since the media time can stop in any state. 1.0 is regular time, 0.5 is
-    /// slow motion.
-    pub playback_rate: f32,
-    /// Describes the state of the player.
-    pub player_state: PlayerState,
-    /// If the player_state is IDLE and the reason it became IDLE is known, this property is
-    /// provided. If the player is IDLE because it just started, this property will not be provided.
-    /// If the player is in any other state this property should not be provided.
-    pub idle_reason: Option<IdleReason>,
-    /// The current position of the media player since the beginning of the content, in seconds.
-    /// If this a live stream content, then this field represents the time in seconds from the
-    /// beginning of the event that should be known to the player.
-    pub current_time: Option<f32>,
-    /// Flags describing which media commands the media player supports:
-    /// * `1` `Pause`;
-    /// * `2` `Seek`;
-    /// * `4` `Stream volume`;
-    /// * `8` `Stream mute`;
-    /// * `16` `Skip forward`;
-    /// * `32` `Skip backward`;
-    /// * `1 << 12` `Unknown`;
-    /// * `1 << 13` `Unknown`;
-    /// * `1 << 18` `Unknown`.
-    /// Combinations are described as summations; for example, Pause+Seek+StreamVolume+Mute == 15.
-    pub supported_media_commands: u32,
-}
-
-/// Describes the load cancelled error.
-#[derive(Copy, Clone, Debug)]
-pub struct LoadCancelled {
-    /// Unique id of the request that caused this error.
-    pub request_id: u32,
-}
+impl Media {
+    /// Returns identifier the loaded media can be recognized by in the receiver status: either
+    /// `content_id` or `content_url` if the former is not provided.
+    fn content_key(&self) -> &str {
+        self.content_id
+            .as_deref()
+            .or(self.content_url.as_deref())
+            .unwrap_or_default()
+    }
 
-/// Describes the load failed error.
-#[derive(Copy, Clone, Debug)]
-pub struct LoadFailed {
-    /// Unique id of the request that caused this error.
-    pub request_id: u32,
-}
+    /// Checks that media can be loaded, i.e. that at least one of `content_id` and `content_url`
+    /// is provided.
+    pub(crate) fn validate(&self) -> Result<(), Error> {
+        if self.content_id.is_none() && self.content_url.is_none() {
+            return Err(Error::InvalidRequest(
+                "Either content id or content URL of the media should be provided.".to_string(),
+            ));
+        }
 
-/// Describes the invalid player state error.
-#[derive(Copy, Clone, Debug)]
-pub struct InvalidPlayerState {
-    /// Unique id of the request that caused this error.
-    pub request_id: u32,
-}
+        if let Some(ref content_url) = self.content_url {
+            if !has_url_scheme(content_url) {
+                return Err(Error::InvalidRequest(format!(
+                    "Content URL \"{}\" should be an absolute URL with a scheme.",
+                    content_url
+                )));
+            }
+        }
 
-/// Describes the invalid request error.
-#[derive(Clone, Debug)]
-pub struct InvalidRequest {
-    /// Unique id of the invalid request.
-    pub request_id: u32,
-    /// Description of the invalid request reason if available.
-    pub reason: Option<String>,
-}
+        // Empty content type is left for the receiver to guess, but anything else should at
+        // least look like a MIME type.
+        if !self.content_type.is_empty() {
+            let is_valid = self
+                .content_type
+                .split_once('/')
+                .is_some_and(|(type_, subtype)| {
+                    !type_.trim().is_empty() && !subtype.trim().is_empty()
+                });
+            if !is_valid {
+                return Err(Error::InvalidRequest(format!(
+                    "Content type \"{}\" is not a valid MIME type.",
+                    self.content_type
+                )));
+            }
+        }
 
-/// The media error encountered during media operations.
-#[derive(Clone, Debug, PartialEq)]
-pub struct MediaError {
-    /// The detailed error code associated with the media error.
-    pub detailed_error_code: MediaDetailedErrorCode,
-    /// The type of the error message.
-    pub message_type: String,
-}
+        Ok(())
+    }
 
-/// The detailed media error code.
-/// https://developers.google.com/android/reference/com/google/android/gms/cast/MediaError.DetailedErrorCode#constants
-#[derive(Clone, Debug, PartialEq)]
-pub enum MediaDetailedErrorCode {
-    /// An error occurs outside of the framework (e.g., if an event handler throws an error).
-    App = 900,
-    /// Break clip load interceptor fails.
-    BreakClipLoadingError = 901,
-    /// Break seek interceptor fails.
-    BreakSeekInterceptorError = 902,
-    /// A DASH manifest contains invalid segment info.
-    DashInvalidSegmentInfo = 423,
-    /// A DASH manifest is missing a MimeType.
-    DashManifestNoMimeType = 422,
-    /// A DASH manifest is missing periods.
-    DashManifestNoPeriods = 421,
-    /// An unknown error occurs while parsing a DASH manifest.
-    DashManifestUnknown = 420,
-    /// An unknown network error occurs while handling a DASH stream.
-    DashNetwork = 321,
-    /// A DASH stream is missing an init.
-    DashNoInit = 322,
-    /// Returned when an unknown error occurs.
-    Generic = 999,
-    /// An error occurs while parsing an HLS master manifest.
-    HlsManifestMaster = 411,
-    /// An error occurs while parsing an HLS playlist.
-    HlsManifestPlaylist = 412,
-    /// An HLS segment is invalid.
-    HlsNetworkInvalidSegment = 315,
-    /// A request for an HLS key fails before it is sent.
-    HlsNetworkKeyLoad = 314,
-    /// An HLS master playlist fails to download.
-    HlsNetworkMasterPlaylist = 311,
-    /// An HLS key fails to download.
-    HlsNetworkNoKeyResponse = 313,
-    /// An HLS playlist fails to download.
-    HlsNetworkPlaylist = 312,
-    /// An HLS segment fails to parse.
-    HlsSegmentParsing = 316,
-    /// When an image fails to load.
-    ImageError = 903,
-    /// A load command failed.
-    LoadFailed = 905,
-    /// A load was interrupted by an unload, or by another load.
-    LoadInterrupted = 904,
-    /// An unknown error occurs while parsing a manifest.
-    ManifestUnknown = 400,
-    /// There is a media keys failure due to a network issue.
-    MediakeysNetwork = 201,
-    /// There is an unknown error with media keys.
-    MediakeysUnknown = 200,
-    /// A MediaKeySession object cannot be created.
-    MediakeysUnsupported = 202,
-    /// Crypto failed.
-    MediakeysWebcrypto = 203,
-    /// The fetching process for the media resource was aborted by the user agent at the user's request.
-    MediaAborted = 101,
-    /// An error occurred while decoding the media resource, after the resource was established to be usable.
-    MediaDecode = 102,
-    /// An error message was sent to the sender.
-    MediaErrorMessage = 906,
-    /// A network error caused the user agent to stop fetching the media resource, after the resource was established to be usable.
-    MediaNetwork = 103,
-    /// The media resource indicated by the src attribute was not suitable.
-    MediaSrcNotSupported = 104,
-    /// The HTMLMediaElement throws an error, but CAF does not recognize the specific error.
-    MediaUnknown = 100,
-    /// There was an unknown network issue.
-    NetworkUnknown = 300,
-    /// A segment fails to download.
-    SegmentNetwork = 301,
-    /// An unknown segment error occurs.
-    SegmentUnknown = 500,
-    /// An error occurs while parsing a Smooth manifest.
-    SmoothManifest = 431,
-    /// An unknown network error occurs while handling a Smooth stream.
-    SmoothNetwork = 331,
-    /// A Smooth stream is missing media data.
-    SmoothNoMediaData = 332,
-    /// A source buffer cannot be added to the MediaSource.
-    SourceBufferFailure = 110,
-    /// An unknown error occurred with a text stream.
-    TextUnknown = 600,
-}
-
-impl TryFrom<i32> for MediaDetailedErrorCode {
-    type Error = Error;
-
-    fn try_from(value: i32) -> Result<Self, Self::Error> {
-        match value {
-            900 => Ok(MediaDetailedErrorCode::App),
-            901 => Ok(MediaDetailedErrorCode::BreakClipLoadingError),
-            902 => Ok(MediaDetailedErrorCode::BreakSeekInterceptorError),
-            423 => Ok(MediaDetailedErrorCode::DashInvalidSegmentInfo),
-            422 => Ok(MediaDetailedErrorCode::DashManifestNoMimeType),
-            421 => Ok(MediaDetailedErrorCode::DashManifestNoPeriods),
-            420 => Ok(MediaDetailedErrorCode::DashManifestUnknown),
-            321 => Ok(MediaDetailedErrorCode::DashNetwork),
-            322 => Ok(MediaDetailedErrorCode::DashNoInit),
-            999 => Ok(MediaDetailedErrorCode::Generic),
-            411 => Ok(MediaDetailedErrorCode::HlsManifestMaster),
-            412 => Ok(MediaDetailedErrorCode::HlsManifestPlaylist),
-            315 => Ok(MediaDetailedErrorCode::HlsNetworkInvalidSegment),
-            314 => Ok(MediaDetailedErrorCode::HlsNetworkKeyLoad),
-            311 => Ok(MediaDetailedErrorCode::HlsNetworkMasterPlaylist),
-            313 => Ok(MediaDetailedErrorCode::HlsNetworkNoKeyResponse),
-            312 => Ok(MediaDetailedErrorCode::HlsNetworkPlaylist),
-            316 => Ok(MediaDetailedErrorCode::HlsSegmentParsing),
-            903 => Ok(MediaDetailedErrorCode::ImageError),
-            905 => Ok(MediaDetailedErrorCode::LoadFailed),
-            904 => Ok(MediaDetailedErrorCode::LoadInterrupted),
-            400 => Ok(MediaDetailedErrorCode::ManifestUnknown),
-            201 => Ok(MediaDetailedErrorCode::MediakeysNetwork),
-            200 => Ok(MediaDetailedErrorCode::MediakeysUnknown),
-            202 => Ok(MediaDetailedErrorCode::MediakeysUnsupported),
-            203 => Ok(MediaDetailedErrorCode::MediakeysWebcrypto),
-            101 => Ok(MediaDetailedErrorCode::MediaAborted),
-            102 => Ok(MediaDetailedErrorCode::MediaDecode),
-            906 => Ok(MediaDetailedErrorCode::MediaErrorMessage),
-            103 => Ok(MediaDetailedErrorCode::MediaNetwork),
-            104 => Ok(MediaDetailedErrorCode::MediaSrcNotSupported),
-            100 => Ok(MediaDetailedErrorCode::MediaUnknown),
-            300 => Ok(MediaDetailedErrorCode::NetworkUnknown),
-            301 => Ok(MediaDetailedErrorCode::SegmentNetwork),
-            500 => Ok(MediaDetailedErrorCode::SegmentUnknown),
-            431 => Ok(MediaDetailedErrorCode::SmoothManifest),
-            331 => Ok(MediaDetailedErrorCode::SmoothNetwork),
-            332 => Ok(MediaDetailedErrorCode::SmoothNoMediaData),
-            110 => Ok(MediaDetailedErrorCode::SourceBufferFailure),
-            600 => Ok(MediaDetailedErrorCode::TextUnknown),
-            _ => Err(Error::Parsing(format!(
-                "media error code {} is not supported",
-                value
-            ))),
-        }
-    }
-}
-
-/// Represents all currently supported incoming messages that media channel can handle.
-#[derive(Clone, Debug)]
-pub enum MediaResponse {
-    /// Statuses of the currently active media.
-    Status(Status),
-    /// Sent when the load request was cancelled (a second load request was received).
-    LoadCancelled(LoadCancelled),
-    /// Sent when the load request failed. The player state will be IDLE.
-    LoadFailed(LoadFailed),
-    /// Sent when the request by the sender can not be fulfilled because the player is not in a
-    /// valid state. For example, if the application has not created a media element yet.
-    InvalidPlayerState(InvalidPlayerState),
-    /// Error indicating that request is not valid.
-    InvalidRequest(InvalidRequest),
-    /// The media error that occurred while executing a media operation on the media channel.
-    Error(MediaError),
-    /// Used every time when channel can't parse the message. Associated data contains `type` string
-    /// field and raw JSON data returned from cast device.
-    NotImplemented(String, serde_json::Value),
-}
-
-pub struct MediaChannel<'a, W>
-where
-    W: Read + Write,
-{
-    sender: Cow<'a, str>,
-    message_manager: Lrc<MessageManager<W>>,
-}
-
-impl<'a, W> MediaChannel<'a, W>
-where
-    W: Read + Write,
-{
-    pub fn new<S>(sender: S, message_manager: Lrc<MessageManager<W>>) -> MediaChannel<'a, W>
-    where
-        S: Into<Cow<'a, str>>,
-    {
-        MediaChannel {
-            sender: sender.into(),
-            message_manager,
-        }
-    }
-
-    /// Retrieves status of the cast device media session.
-    ///
-    /// # Arguments
-    ///
-    /// * `destination` - `protocol` identifier of specific app media session;
-    /// * `media_session_id` - Media session ID of the media for which the media status should be
-    /// returned. If none is provided, then the status for all media session IDs will be provided.
-    ///
-    /// # Return value
-    ///
-    /// Returned `Result` should consist of either `Status` instance or an `Error`.
-    pub fn get_status<S>(
-        &self,
-        destination: S,
-        media_session_id: Option<i32>,
-    ) -> Result<Status, Error>
-    where
-        S: Into<Cow<'a, str>>,
-    {
-        let request_id = self.message_manager.generate_request_id().get();
-
-        let payload = serde_json::to_string(&proxies::media::GetStatusRequest {
-            typ: MESSAGE_TYPE_GET_STATUS.to_string(),
-            request_id,
-            media_session_id,
-        })?;
-
-        self.message_manager.send(CastMessage {
-            namespace: CHANNEL_NAMESPACE.to_string(),
-            source: self.sender.to_string(),
-            destination: destination.into().to_string(),
-            payload: CastMessagePayload::String(payload),
-        })?;
-
-        self.message_manager.receive_find_map(|message| {
-            if !self.can_handle(message) {
-                return Ok(None);
-            }
-
-            match self.parse(message)? {
-                MediaResponse::Status(status) => {
-                    if status.request_id == request_id {
-                        return Ok(Some(status));
-                    }
-                }
-                MediaResponse::InvalidRequest(error) => {
-                    if error.request_id == request_id {
-                        return Err(Error::Internal(format!(
-                            "Invalid request ({}).",
-                            error.reason.unwrap_or_else(|| "Unknown".to_string())
-                        )));
-                    }
-                }
-                _ => {}
-            }
-
-            Ok(None)
-        })
-    }
-
-    /// Loads provided media to the application.
-    ///
-    /// # Arguments
-    /// * `destination` - `protocol` of the application to load media with (e.g. `web-1`);
-    /// * `session_id` - Current session identifier of the player application;
-    /// * `media` - `Media` instance that describes the media we'd like to load.
-    ///
-    /// # Return value
-    ///
-    /// Returned `Result` should consist of either `Status` instance or an `Error`.
-    pub fn load<S>(&self, destination: S, session_id: S, media: &Media) -> Result<Status, Error>
-    where
-        S: Into<Cow<'a, str>>,
-    {
-        self.load_with_custom_data(destination, session_id, media, None)
-    }
-
-    /// Loads provided media to the application with associated custom data.
-    ///
-    /// # Arguments
-    /// * `destination` - `protocol` of the application to load media with (e.g. `web-1`);
-    /// * `session_id` - Current session identifier of the player application;
-    /// * `media` - `Media` instance that describes the media we'd like to load.
-    /// * `custom_data` - serializable object with custom data.
-    ///
-    /// # Return value
-    ///
-    /// Returned `Result` should consist of either `Status` instance or an `Error`.
-    pub fn load_with_custom_data<S>(&self, destination: S, session_id: S, media: &Media, custom_data: Option<CustomData>) -> Result<Status, Error>
-    where
-        S: Into<Cow<'a, str>>,
-    {
-        let request_id = self.message_manager.generate_request_id().get();
-
-        let metadata = media.metadata.as_ref().map(|m| match *m {
+    fn encode(&self, custom_data: Option<CustomData>) -> proxies::media::Media {
+        let metadata = self.metadata.as_ref().map(|m| match *m {
             Metadata::Generic(ref x) => proxies::media::Metadata {
                 title: x.title.clone(),
                 subtitle: x.subtitle.clone(),
@@ -792,519 +1034,4551 @@ where
                 location: x.location.clone(),
                 latitude: x.latitude_longitude.map(|coord| coord.0),
                 longitude: x.latitude_longitude.map(|coord| coord.1),
-                width: x.dimensions.map(|dims| dims.0),
-                height: x.dimensions.map(|dims| dims.1),
+                width: x.width,
+                height: x.height,
                 creation_date_time: x.creation_date_time.clone(),
                 ..proxies::media::Metadata::new(4)
             },
+            // Raw metadata has been produced by `Metadata::decode`, so it's a valid proxy.
+            Metadata::Unknown(ref x) => serde_json::from_value(x.clone())
+                .unwrap_or_else(|_| proxies::media::Metadata::new(0)),
         });
 
-        let payload = serde_json::to_string(&proxies::media::MediaRequest {
-            request_id,
-            session_id: session_id.into().to_string(),
-            typ: MESSAGE_TYPE_LOAD.to_string(),
-
-            media: proxies::media::Media {
-                content_id: media.content_id.clone(),
-                stream_type: media.stream_type.to_string(),
-                content_type: media.content_type.clone(),
-                metadata,
-                duration: media.duration,
-                tracks: vec![],
-                custom_data: custom_data,
-            },
+        proxies::media::Media {
+            content_id: self.content_id.clone(),
+            content_url: self.content_url.clone(),
+            stream_type: self.stream_type.to_string(),
+            content_type: self.content_type.clone(),
+            metadata,
+            duration: self.duration,
+            tracks: self.tracks.iter().map(|track| track.encode()).collect(),
+            text_track_style: self.text_track_style.as_ref().map(|style| style.encode()),
+            hls_segment_format: self.hls_segment_format.map(|format| format.to_string()),
+            hls_video_segment_format: self
+                .hls_video_segment_format
+                .map(|format| format.to_string()),
+            breaks: self.breaks.iter().map(|b| b.encode()).collect(),
+            break_clips: self.break_clips.iter().map(|clip| clip.encode()).collect(),
+            custom_data,
+        }
+    }
 
-            current_time: None,
-            autoplay: true,
-            custom_data: proxies::media::CustomData::new(),
-        })?;
+    fn decode(media: &proxies::media::Media) -> Media {
+        Media {
+            content_id: media.content_id.clone(),
+            content_url: media.content_url.clone(),
+            stream_type: StreamType::from_str(media.stream_type.as_ref())
+                .unwrap_or(StreamType::None),
+            content_type: media.content_type.to_string(),
+            metadata: media.metadata.as_ref().map(Metadata::decode),
+            // Tracks of unknown type are skipped rather than failing the whole media status.
+            tracks: media
+                .tracks
+                .iter()
+                .filter_map(|track| Track::decode(track).ok())
+                .collect(),
+            text_track_style: media
+                .text_track_style
+                .as_ref()
+                .and_then(|style| TextTrackStyle::decode(style).ok()),
+            hls_segment_format: media
+                .hls_segment_format
+                .as_ref()
+                .and_then(|format| HlsSegmentFormat::from_str(format).ok()),
+            hls_video_segment_format: media
+                .hls_video_segment_format
+                .as_ref()
+                .and_then(|format| HlsVideoSegmentFormat::from_str(format).ok()),
+            duration: media.duration,
+            breaks: media.breaks.iter().map(Break::decode).collect(),
+            break_clips: media.break_clips.iter().map(BreakClip::decode).collect(),
+        }
+    }
+}
 
-        self.message_manager.send(CastMessage {
-            namespace: CHANNEL_NAMESPACE.to_string(),
-            source: self.sender.to_string(),
-            destination: destination.into().to_string(),
-            payload: CastMessagePayload::String(payload),
-        })?;
+/// Describes an ad break (cue point) of the media.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Break {
+    /// Unique identifier of the break.
+    pub id: String,
+    /// Identifiers of the break clips played during the break, in playback order.
+    pub break_clip_ids: Vec<String>,
+    /// Position of the break in the media, in seconds. `-1` means the end of the media.
+    pub position: f32,
+    /// Duration of the break in seconds, if known.
+    pub duration: Option<f32>,
+    /// Whether the break has already been watched.
+    pub is_watched: bool,
+    /// Whether the break is embedded into the main stream (e.g. server-side ad insertion).
+    pub is_embedded: Option<bool>,
+}
 
-        // Once media is loaded cast receiver device should emit status update event, or load failed
-        // event if something went wrong.
-        self.message_manager.receive_find_map(|message| {
-            if !self.can_handle(message) {
-                return Ok(None);
-            }
+impl Break {
+    fn encode(&self) -> proxies::media::Break {
+        proxies::media::Break {
+            id: self.id.clone(),
+            break_clip_ids: self.break_clip_ids.clone(),
+            position: self.position,
+            duration: self.duration,
+            is_watched: self.is_watched,
+            is_embedded: self.is_embedded,
+        }
+    }
 
-            match self.parse(message)? {
-                MediaResponse::Status(status) => {
-                    if status.request_id == request_id {
-                        return Ok(Some(status));
-                    }
+    fn decode(media_break: &proxies::media::Break) -> Break {
+        Break {
+            id: media_break.id.clone(),
+            break_clip_ids: media_break.break_clip_ids.clone(),
+            position: media_break.position,
+            duration: media_break.duration,
+            is_watched: media_break.is_watched,
+            is_embedded: media_break.is_embedded,
+        }
+    }
+}
 
-                    // [WORKAROUND] In some cases we don't receive response (e.g. from YouTube app),
-                    // so let's just wait for the response with the media we're interested in and
-                    // return it.
-                    let has_media = {
-                        status.entries.iter().any(|entry| {
-                            if let Some(ref loaded_media) = entry.media {
-                                return loaded_media.content_id == media.content_id;
-                            }
+/// Describes an ad creative played during the break.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BreakClip {
+    /// Unique identifier of the break clip.
+    pub id: String,
+    /// Title of the break clip.
+    pub title: Option<String>,
+    /// Identifier of the break clip content.
+    pub content_id: Option<String>,
+    /// URL of the break clip content.
+    pub content_url: Option<String>,
+    /// MIME content type of the break clip.
+    pub content_type: Option<String>,
+    /// URL of the page the sender can open when user clicks on the ad.
+    pub click_through_url: Option<String>,
+    /// URL of the image shown while the break clip is loading.
+    pub poster_url: Option<String>,
+    /// Duration of the break clip in seconds.
+    pub duration: Option<f32>,
+    /// Position in the break clip (in seconds) after which it can be skipped, `None` means that the
+    /// clip can't be skipped.
+    pub when_skippable: Option<f32>,
+}
 
-                            false
-                        })
-                    };
+impl BreakClip {
+    fn encode(&self) -> proxies::media::BreakClip {
+        proxies::media::BreakClip {
+            id: self.id.clone(),
+            title: self.title.clone(),
+            content_id: self.content_id.clone(),
+            content_url: self.content_url.clone(),
+            content_type: self.content_type.clone(),
+            click_through_url: self.click_through_url.clone(),
+            poster_url: self.poster_url.clone(),
+            duration: self.duration,
+            when_skippable: self.when_skippable,
+        }
+    }
 
-                    if has_media {
-                        return Ok(Some(status));
-                    }
-                }
-                MediaResponse::LoadFailed(error) => {
-                    if error.request_id == request_id {
-                        return Err(Error::Internal("Failed to load media.".to_string()));
-                    }
-                }
-                MediaResponse::LoadCancelled(error) => {
-                    if error.request_id == request_id {
-                        return Err(Error::Internal(
-                            "Load cancelled by another request.".to_string(),
-                        ));
-                    }
-                }
-                MediaResponse::InvalidPlayerState(error) => {
-                    if error.request_id == request_id {
-                        return Err(Error::Internal(
-                            "Load failed because of invalid player state.".to_string(),
-                        ));
-                    }
-                }
-                MediaResponse::InvalidRequest(error) => {
-                    if error.request_id == request_id {
-                        return Err(Error::Internal(format!(
-                            "Load failed because of invalid media request (reason: {}).",
-                            error.reason.unwrap_or_else(|| "UNKNOWN".to_string())
-                        )));
-                    }
-                }
-                _ => {}
-            }
+    fn decode(clip: &proxies::media::BreakClip) -> BreakClip {
+        BreakClip {
+            id: clip.id.clone(),
+            title: clip.title.clone(),
+            content_id: clip.content_id.clone(),
+            content_url: clip.content_url.clone(),
+            content_type: clip.content_type.clone(),
+            click_through_url: clip.click_through_url.clone(),
+            poster_url: clip.poster_url.clone(),
+            duration: clip.duration,
+            when_skippable: clip.when_skippable,
+        }
+    }
+}
 
-            Ok(None)
+/// Describes the ad break that is currently playing.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BreakStatus {
+    /// Time elapsed since the beginning of the current break, in seconds.
+    pub current_break_time: f32,
+    /// Time elapsed since the beginning of the current break clip, in seconds.
+    pub current_break_clip_time: f32,
+    /// Identifier of the current break.
+    pub break_id: Option<String>,
+    /// Identifier of the current break clip.
+    pub break_clip_id: Option<String>,
+    /// Position in the current break clip (in seconds) after which it can be skipped, `None`
+    /// means that the clip can't be skipped.
+    pub when_skippable: Option<f32>,
+}
+
+impl BreakStatus {
+    /// Checks whether the current break clip can already be skipped.
+    pub fn is_skippable(&self) -> bool {
+        self.when_skippable
+            .is_some_and(|when_skippable| self.current_break_clip_time >= when_skippable)
+    }
+
+    fn decode(status: &proxies::media::BreakStatus) -> BreakStatus {
+        BreakStatus {
+            current_break_time: status.current_break_time,
+            current_break_clip_time: status.current_break_clip_time,
+            break_id: status.break_id.clone(),
+            break_clip_id: status.break_clip_id.clone(),
+            when_skippable: status.when_skippable,
+        }
+    }
+}
+
+/// This data structure describes an item of the media queue.
+///
+/// See also the [`QueueItem` Cast reference](https://developers.google.com/cast/docs/reference/messages#QueueItem).
+#[derive(Clone, Debug)]
+pub struct QueueItem {
+    /// Unique identifier of the item in the queue, it's assigned by the receiver and should be
+    /// `None` for the items that are not in the queue yet.
+    pub item_id: Option<i32>,
+    /// Media that this item represents.
+    pub media: Media,
+    /// Whether playback should start automatically once this item becomes current.
+    pub autoplay: bool,
+    /// Number of seconds before the end of the previous item when this item should be preloaded.
+    pub preload_time: Option<f64>,
+    /// Position in seconds to start playback of this item from.
+    pub start_time: Option<f64>,
+    /// Number of seconds of this item to play, the whole item is played if not provided.
+    pub playback_duration: Option<f64>,
+    /// Custom application-specific data of this item.
+    pub custom_data: Option<CustomData>,
+}
+
+impl QueueItem {
+    pub fn new(media: Media) -> QueueItem {
+        QueueItem {
+            item_id: None,
+            media,
+            autoplay: true,
+            preload_time: None,
+            start_time: None,
+            playback_duration: None,
+            custom_data: None,
+        }
+    }
+
+    fn encode(&self) -> proxies::media::QueueItem {
+        proxies::media::QueueItem {
+            item_id: self.item_id,
+            media: Some(self.media.encode(None)),
+            autoplay: self.autoplay,
+            preload_time: self.preload_time,
+            start_time: self.start_time,
+            playback_duration: self.playback_duration,
+            custom_data: self.custom_data.clone(),
+        }
+    }
+
+    fn decode(item: &proxies::media::QueueItem) -> Option<QueueItem> {
+        Some(QueueItem {
+            item_id: item.item_id,
+            media: Media::decode(item.media.as_ref()?),
+            autoplay: item.autoplay,
+            preload_time: item.preload_time,
+            start_time: item.start_time,
+            playback_duration: item.playback_duration,
+            custom_data: item.custom_data.clone(),
         })
     }
 
-    /// Pauses playback of the current content. Triggers a STATUS event notification to all sender
-    /// applications.
+    /// Returns number of seconds this item plays for: `playback_duration` if provided, otherwise
+    /// duration of the media past `start_time`.
+    pub fn duration(&self) -> Option<f64> {
+        self.playback_duration.or_else(|| {
+            self.media
+                .duration
+                .map(|duration| (f64::from(duration) - self.start_time.unwrap_or(0.0)).max(0.0))
+        })
+    }
+}
+
+/// Media queue as known to the sender: the items (e.g. received with `MediaChannel::queue_get_items`
+/// or included into the `StatusEntry`) and the item that is currently playing.
+///
+/// Note that receivers usually report only a subset of the queue in the status (e.g. current and
+/// the next item), so the computations are only as complete as `items` are.
+#[derive(Clone, Debug, Default)]
+pub struct QueueData {
+    /// Known queue items, in the queue order.
+    pub items: Vec<QueueItem>,
+    /// ID of the queue item that is currently playing, if any.
+    pub current_item_id: Option<i32>,
+}
+
+impl QueueData {
+    /// Calculates total play time of the known queue items in seconds (see `QueueItem::duration`).
     ///
-    /// # Arguments
+    /// # Return value
     ///
-    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
-    /// * `media_session_id` - ID of the media session to be paused.
+    /// `None` if duration of any item is unknown.
+    pub fn total_duration(&self) -> Option<f64> {
+        self.items.iter().map(QueueItem::duration).sum()
+    }
+
+    /// Finds position of the currently playing item among the known queue items.
     ///
     /// # Return value
     ///
-    /// Returned `Result` should consist of either `Status` instance or an `Error`.
-    pub fn pause<S>(&self, destination: S, media_session_id: i32) -> Result<StatusEntry, Error>
-    where
-        S: Into<Cow<'a, str>>,
-    {
-        let request_id = self.message_manager.generate_request_id().get();
+    /// `None` if there is no current item or it's not among `items`.
+    pub fn current_index(&self) -> Option<usize> {
+        let current_item_id = self.current_item_id?;
+        self.items
+            .iter()
+            .position(|item| item.item_id == Some(current_item_id))
+    }
+}
 
-        let payload = serde_json::to_string(&proxies::media::PlaybackGenericRequest {
-            request_id,
-            media_session_id,
-            typ: MESSAGE_TYPE_PAUSE.to_string(),
-            custom_data: proxies::media::CustomData::new(),
-        })?;
+/// Describes the update of the media queue (see `MediaChannel::queue_update`).
+#[derive(Clone, Debug, Default)]
+pub struct QueueUpdateOptions {
+    /// Number of items to jump relatively to the current item, e.g. `1` plays the next item and
+    /// `-1` plays the previous one.
+    pub jump: Option<i32>,
+    /// ID of the item to play, takes precedence over `jump`.
+    pub current_item_id: Option<i32>,
+    /// Queue items to update properties of, matched by `item_id`.
+    pub items: Vec<QueueItem>,
+    /// New repeat mode of the queue.
+    pub repeat_mode: Option<RepeatMode>,
+    /// Whether queue items should be shuffled.
+    pub shuffle: Option<bool>,
+}
 
-        self.message_manager.send(CastMessage {
-            namespace: CHANNEL_NAMESPACE.to_string(),
-            source: self.sender.to_string(),
-            destination: destination.into().to_string(),
-            payload: CastMessagePayload::String(payload),
-        })?;
+/// Additional parameters of the media LOAD request.
+#[derive(Clone, Debug)]
+pub struct LoadOptions {
+    /// Whether media should start playing as soon as it's loaded (`true` by default).
+    pub autoplay: bool,
+    /// Position in seconds to start playback from. If not provided, playback starts from the
+    /// beginning for the buffered content and from the live edge for live content.
+    pub current_time: Option<f64>,
+    /// IDs of the `Media::tracks` that should be active right away (e.g. subtitles), so that
+    /// they don't need to be enabled with a separate `edit_tracks_info` call after loading.
+    pub active_track_ids: Option<Vec<i32>>,
+    /// User credentials receiver application uses to authorize the request (e.g. OAuth token).
+    pub credentials: Option<String>,
+    /// Type of the `credentials`, e.g. `cloud`, `android` or custom application-specific one.
+    pub credentials_type: Option<String>,
+    /// Behavior of the receiver when loaded media ends, e.g. `RepeatMode::Single` makes receiver
+    /// loop it. If not provided, receiver uses `RepeatMode::Off`.
+    pub repeat_mode: Option<RepeatMode>,
+    /// Custom application-specific data sent along with LOAD request (e.g. DRM tokens).
+    pub custom_data: Option<CustomData>,
+}
 
-        self.receive_status_entry(request_id, media_session_id)
+impl Default for LoadOptions {
+    fn default() -> Self {
+        LoadOptions {
+            autoplay: true,
+            current_time: None,
+            active_track_ids: None,
+            credentials: None,
+            credentials_type: None,
+            repeat_mode: None,
+            custom_data: None,
+        }
     }
+}
 
-    /// Begins playback of the content that was loaded with the load call, playback is continued
-    /// from the current time position.
+/// Seekable range of the live or event stream, in seconds of the relative media time.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct LiveSeekableRange {
+    /// Start of the range, if known.
+    pub start: Option<f32>,
+    /// End of the range, if known.
+    pub end: Option<f32>,
+    /// Whether the range is a moving window (start moves forward along with the end), `false`
+    /// means that range only expands.
+    pub is_moving_window: bool,
+    /// Whether the live stream is done, and the range doesn't change anymore.
+    pub is_live_done: bool,
+}
+
+impl LiveSeekableRange {
+    /// Checks whether `time` is within the range, missing bounds are considered unlimited.
+    pub fn contains(&self, time: f32) -> bool {
+        self.start.is_none_or(|start| time >= start) && self.end.is_none_or(|end| time <= end)
+    }
+
+    fn decode(range: &proxies::media::LiveSeekableRange) -> LiveSeekableRange {
+        LiveSeekableRange {
+            start: range.start,
+            end: range.end,
+            is_moving_window: range.is_moving_window,
+            is_live_done: range.is_live_done,
+        }
+    }
+}
+
+/// Describes the current status of the media artifact with respect to the session.
+#[derive(Clone, Debug)]
+pub struct Status {
+    /// Unique id of the request that requested the status.
+    pub request_id: u32,
+    /// Detailed status of every media status entry.
+    pub entries: Vec<StatusEntry>,
+}
+
+/// Detailed status of the media artifact with respect to the session.
+#[derive(Clone, Debug)]
+pub struct StatusEntry {
+    /// List of IDs corresponding to the active Tracks.
+    pub active_track_ids: Vec<i32>,
+    /// Unique ID for the playback of this specific session. This ID is set by the receiver at LOAD
+    /// and can be used to identify a specific instance of a playback. For example, two playbacks of
+    /// "Wish you were here" within the same session would each have a unique mediaSessionId.
+    pub media_session_id: i32,
+    /// Full description of the content that is being played back. Only be returned in a status
+    /// messages if the Media has changed.
+    pub media: Option<Media>,
+    /// Seekable range of a live or event stream. It uses relative media time in seconds.
+    /// It will be undefined for VOD streams.
+    pub live_seekable_range: Option<LiveSeekableRange>,
+    /// Indicates whether the media time is progressing, and at what rate. This is independent of
+    /// the player state since the media time can stop in any state. 1.0 is regular time, 0.5 is
+    /// slow motion.
+    pub playback_rate: f32,
+    /// Describes the state of the player.
+    pub player_state: PlayerState,
+    /// If the player_state is IDLE and the reason it became IDLE is known, this property is
+    /// provided. If the player is IDLE because it just started, this property will not be provided.
+    /// If the player is in any other state this property should not be provided.
+    pub idle_reason: Option<IdleReason>,
+    /// The current position of the media player since the beginning of the content, in seconds.
+    /// If this a live stream content, then this field represents the time in seconds from the
+    /// beginning of the event that should be known to the player.
+    pub current_time: Option<f32>,
+    /// Flags describing which media commands the media player supports.
+    pub supported_media_commands: SupportedMediaCommands,
+    /// Stream volume, if reported by the receiver.
+    pub volume: Option<Volume>,
+    /// ID of the queue item that is currently playing, if media queue is used.
+    pub current_item_id: Option<i32>,
+    /// ID of the queue item that is currently being loaded, if any.
+    pub loading_item_id: Option<i32>,
+    /// ID of the queue item that is being preloaded to play next without a gap, if any. It's not
+    /// provided e.g. near the end of the non-repeating queue.
+    pub preloaded_item_id: Option<i32>,
+    /// Repeat mode of the media queue, if media queue is used.
+    pub repeat_mode: Option<RepeatMode>,
+    /// Media queue items with the media information included into the status. Note that receiver
+    /// may include only a subset of the queue items (e.g. current and the next one).
+    pub items: Vec<QueueItem>,
+    /// Ad break that is currently playing, if any.
+    pub break_status: Option<BreakStatus>,
+}
+
+impl StatusEntry {
+    /// Checks whether the media is a live stream, either according to its stream type or to the
+    /// presence of the live seekable range.
+    pub fn is_live(&self) -> bool {
+        self.live_seekable_range.is_some()
+            || self
+                .media
+                .as_ref()
+                .is_some_and(|media| media.stream_type == StreamType::Live)
+    }
+
+    /// Returns the media queue as included into the status, see `QueueData` for what it's good for.
+    pub fn queue_data(&self) -> QueueData {
+        QueueData {
+            items: self.items.clone(),
+            current_item_id: self.current_item_id,
+        }
+    }
+
+    /// Returns the queue item that is being preloaded (see `preloaded_item_id`), if status includes
+    /// it, e.g. to prepare its metadata before it starts playing.
+    pub fn preloaded_item(&self) -> Option<&QueueItem> {
+        let preloaded_item_id = self.preloaded_item_id?;
+        self.items
+            .iter()
+            .find(|item| item.item_id == Some(preloaded_item_id))
+    }
+
+    /// Checks whether skipping or seeking is currently allowed: it's always allowed outside of ad
+    /// breaks, while ad is playing it's allowed only once the ad becomes skippable.
+    pub fn is_skip_allowed(&self) -> bool {
+        self.break_status
+            .as_ref()
+            .is_none_or(|break_status| break_status.is_skippable())
+    }
+}
+
+/// Projects the current playback position between `MEDIA_STATUS` updates from the last known
+/// position, the moment the status has been received and the playback rate, so that e.g. progress
+/// bar can move smoothly without polling the cast device.
+///
+/// # Examples
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// # use rust_cast::channels::media::{PlaybackClock, StatusEntry};
+/// # fn show(entry: &StatusEntry) {
+/// let mut clock = PlaybackClock::default();
+/// clock.update(entry, Instant::now());
+///
+/// let position = clock.estimated_current_time(Instant::now() + Duration::from_secs(1));
+/// # }
+/// ```
+#[derive(Copy, Clone, Debug, Default)]
+pub struct PlaybackClock {
+    current_time: f32,
+    playback_rate: f32,
+    duration: Option<f32>,
+    received_at: Option<Instant>,
+}
+
+impl PlaybackClock {
+    /// Updates the clock with the `entry` received from the cast device at `received_at`. Media
+    /// duration is remembered across updates since receivers include media description only when
+    /// media changes. Entries without the current position are ignored.
     ///
     /// # Arguments
     ///
-    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
-    /// * `media_session_id` - ID of the media session to be played.
+    /// * `entry` - status entry of the media session;
+    /// * `received_at` - moment the status has been received.
+    pub fn update(&mut self, entry: &StatusEntry, received_at: Instant) {
+        if let Some(ref media) = entry.media {
+            self.duration = media.duration;
+        }
+
+        let Some(current_time) = entry.current_time else {
+            return;
+        };
+
+        self.current_time = current_time;
+        self.playback_rate = match entry.player_state {
+            PlayerState::Playing => entry.playback_rate,
+            _ => 0.0,
+        };
+        self.received_at = Some(received_at);
+    }
+
+    /// Estimates playback position (in seconds) at the moment `at`. Position doesn't move unless
+    /// player is playing and never exceeds media duration (if known).
     ///
-    /// # Return value
+    /// # Arguments
     ///
-    /// Returned `Result` should consist of either `Status` instance or an `Error`.
-    pub fn play<S>(&self, destination: S, media_session_id: i32) -> Result<StatusEntry, Error>
-    where
-        S: Into<Cow<'a, str>>,
-    {
-        let request_id = self.message_manager.generate_request_id().get();
+    /// * `at` - moment to estimate the position at, usually `Instant::now()`.
+    pub fn estimated_current_time(&self, at: Instant) -> f32 {
+        let Some(received_at) = self.received_at else {
+            return self.current_time;
+        };
 
-        let payload = serde_json::to_string(&proxies::media::PlaybackGenericRequest {
-            request_id,
-            media_session_id,
-            typ: MESSAGE_TYPE_PLAY.to_string(),
-            custom_data: proxies::media::CustomData::new(),
-        })?;
+        let elapsed = at.saturating_duration_since(received_at).as_secs_f32();
+        let current_time = self.current_time + elapsed * self.playback_rate;
 
-        self.message_manager.send(CastMessage {
-            namespace: CHANNEL_NAMESPACE.to_string(),
-            source: self.sender.to_string(),
-            destination: destination.into().to_string(),
-            payload: CastMessagePayload::String(payload),
-        })?;
+        match self.duration {
+            Some(duration) => current_time.clamp(0.0, duration.max(0.0)),
+            None => current_time.max(0.0),
+        }
+    }
+}
+
+bitflags::bitflags! {
+    /// Flags describing which media commands the media player supports. Bits that are not known
+    /// to this library are preserved.
+    ///
+    /// See also the [`Media Command` Cast reference](https://developers.google.com/cast/docs/reference/web_receiver/cast.framework.messages#.Command).
+    #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+    pub struct SupportedMediaCommands: u32 {
+        /// Pause.
+        const PAUSE = 1;
+        /// Seek.
+        const SEEK = 1 << 1;
+        /// Stream volume.
+        const STREAM_VOLUME = 1 << 2;
+        /// Stream mute.
+        const STREAM_MUTE = 1 << 3;
+        /// Skip forward.
+        const SKIP_FORWARD = 1 << 4;
+        /// Skip backward.
+        const SKIP_BACKWARD = 1 << 5;
+        /// Next item in the queue.
+        const QUEUE_NEXT = 1 << 6;
+        /// Previous item in the queue.
+        const QUEUE_PREV = 1 << 7;
+        /// Shuffle queue.
+        const QUEUE_SHUFFLE = 1 << 8;
+        /// Skip ad.
+        const SKIP_AD = 1 << 9;
+        /// Repeat all items in the queue.
+        const QUEUE_REPEAT_ALL = 1 << 10;
+        /// Repeat single item in the queue.
+        const QUEUE_REPEAT_ONE = 1 << 11;
+        /// Edit tracks.
+        const EDIT_TRACKS = 1 << 12;
+        /// Playback rate.
+        const PLAYBACK_RATE = 1 << 13;
+        /// Like.
+        const LIKE = 1 << 14;
+        /// Dislike.
+        const DISLIKE = 1 << 15;
+        /// Follow.
+        const FOLLOW = 1 << 16;
+        /// Unfollow.
+        const UNFOLLOW = 1 << 17;
+        /// Stream transfer.
+        const STREAM_TRANSFER = 1 << 18;
+
+        // Keep bits that may be introduced by future receivers.
+        const _ = !0;
+    }
+}
+
+/// Describes the load cancelled error.
+#[derive(Copy, Clone, Debug)]
+pub struct LoadCancelled {
+    /// Unique id of the request that caused this error.
+    pub request_id: u32,
+}
+
+/// Describes the IDs of all items in the media queue.
+#[derive(Clone, Debug)]
+pub struct QueueItemIds {
+    /// Unique id of the request that requested the item IDs.
+    pub request_id: u32,
+    /// IDs of all queue items in the queue order.
+    pub item_ids: Vec<i32>,
+}
+
+/// Describes the media queue items requested by their IDs.
+#[derive(Clone, Debug)]
+pub struct QueueItems {
+    /// Unique id of the request that requested the items.
+    pub request_id: u32,
+    /// Requested queue items that still exist in the queue.
+    pub items: Vec<QueueItem>,
+}
+
+/// Describes the load failed error.
+#[derive(Copy, Clone, Debug)]
+pub struct LoadFailed {
+    /// Unique id of the request that caused this error.
+    pub request_id: u32,
+}
+
+/// Describes the invalid player state error.
+#[derive(Copy, Clone, Debug)]
+pub struct InvalidPlayerState {
+    /// Unique id of the request that caused this error.
+    pub request_id: u32,
+}
+
+/// Describes the invalid request error.
+#[derive(Clone, Debug)]
+pub struct InvalidRequest {
+    /// Unique id of the invalid request.
+    pub request_id: u32,
+    /// Description of the invalid request reason if available.
+    pub reason: Option<String>,
+}
+
+/// The media error encountered during media operations.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MediaError {
+    /// The detailed error code associated with the media error.
+    pub detailed_error_code: MediaDetailedErrorCode,
+    /// The type of the error message.
+    pub message_type: String,
+}
+
+/// The detailed media error code.
+/// https://developers.google.com/android/reference/com/google/android/gms/cast/MediaError.DetailedErrorCode#constants
+#[derive(Clone, Debug, PartialEq)]
+pub enum MediaDetailedErrorCode {
+    /// An error occurs outside of the framework (e.g., if an event handler throws an error).
+    App = 900,
+    /// Break clip load interceptor fails.
+    BreakClipLoadingError = 901,
+    /// Break seek interceptor fails.
+    BreakSeekInterceptorError = 902,
+    /// A DASH manifest contains invalid segment info.
+    DashInvalidSegmentInfo = 423,
+    /// A DASH manifest is missing a MimeType.
+    DashManifestNoMimeType = 422,
+    /// A DASH manifest is missing periods.
+    DashManifestNoPeriods = 421,
+    /// An unknown error occurs while parsing a DASH manifest.
+    DashManifestUnknown = 420,
+    /// An unknown network error occurs while handling a DASH stream.
+    DashNetwork = 321,
+    /// A DASH stream is missing an init.
+    DashNoInit = 322,
+    /// Returned when an unknown error occurs.
+    Generic = 999,
+    /// An error occurs while parsing an HLS master manifest.
+    HlsManifestMaster = 411,
+    /// An error occurs while parsing an HLS playlist.
+    HlsManifestPlaylist = 412,
+    /// An HLS segment is invalid.
+    HlsNetworkInvalidSegment = 315,
+    /// A request for an HLS key fails before it is sent.
+    HlsNetworkKeyLoad = 314,
+    /// An HLS master playlist fails to download.
+    HlsNetworkMasterPlaylist = 311,
+    /// An HLS key fails to download.
+    HlsNetworkNoKeyResponse = 313,
+    /// An HLS playlist fails to download.
+    HlsNetworkPlaylist = 312,
+    /// An HLS segment fails to parse.
+    HlsSegmentParsing = 316,
+    /// When an image fails to load.
+    ImageError = 903,
+    /// A load command failed.
+    LoadFailed = 905,
+    /// A load was interrupted by an unload, or by another load.
+    LoadInterrupted = 904,
+    /// An unknown error occurs while parsing a manifest.
+    ManifestUnknown = 400,
+    /// There is a media keys failure due to a network issue.
+    MediakeysNetwork = 201,
+    /// There is an unknown error with media keys.
+    MediakeysUnknown = 200,
+    /// A MediaKeySession object cannot be created.
+    MediakeysUnsupported = 202,
+    /// Crypto failed.
+    MediakeysWebcrypto = 203,
+    /// The fetching process for the media resource was aborted by the user agent at the user's request.
+    MediaAborted = 101,
+    /// An error occurred while decoding the media resource, after the resource was established to be usable.
+    MediaDecode = 102,
+    /// An error message was sent to the sender.
+    MediaErrorMessage = 906,
+    /// A network error caused the user agent to stop fetching the media resource, after the resource was established to be usable.
+    MediaNetwork = 103,
+    /// The media resource indicated by the src attribute was not suitable.
+    MediaSrcNotSupported = 104,
+    /// The HTMLMediaElement throws an error, but CAF does not recognize the specific error.
+    MediaUnknown = 100,
+    /// There was an unknown network issue.
+    NetworkUnknown = 300,
+    /// A segment fails to download.
+    SegmentNetwork = 301,
+    /// An unknown segment error occurs.
+    SegmentUnknown = 500,
+    /// An error occurs while parsing a Smooth manifest.
+    SmoothManifest = 431,
+    /// An unknown network error occurs while handling a Smooth stream.
+    SmoothNetwork = 331,
+    /// A Smooth stream is missing media data.
+    SmoothNoMediaData = 332,
+    /// A source buffer cannot be added to the MediaSource.
+    SourceBufferFailure = 110,
+    /// An unknown error occurred with a text stream.
+    TextUnknown = 600,
+}
+
+impl TryFrom<i32> for MediaDetailedErrorCode {
+    type Error = Error;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            900 => Ok(MediaDetailedErrorCode::App),
+            901 => Ok(MediaDetailedErrorCode::BreakClipLoadingError),
+            902 => Ok(MediaDetailedErrorCode::BreakSeekInterceptorError),
+            423 => Ok(MediaDetailedErrorCode::DashInvalidSegmentInfo),
+            422 => Ok(MediaDetailedErrorCode::DashManifestNoMimeType),
+            421 => Ok(MediaDetailedErrorCode::DashManifestNoPeriods),
+            420 => Ok(MediaDetailedErrorCode::DashManifestUnknown),
+            321 => Ok(MediaDetailedErrorCode::DashNetwork),
+            322 => Ok(MediaDetailedErrorCode::DashNoInit),
+            999 => Ok(MediaDetailedErrorCode::Generic),
+            411 => Ok(MediaDetailedErrorCode::HlsManifestMaster),
+            412 => Ok(MediaDetailedErrorCode::HlsManifestPlaylist),
+            315 => Ok(MediaDetailedErrorCode::HlsNetworkInvalidSegment),
+            314 => Ok(MediaDetailedErrorCode::HlsNetworkKeyLoad),
+            311 => Ok(MediaDetailedErrorCode::HlsNetworkMasterPlaylist),
+            313 => Ok(MediaDetailedErrorCode::HlsNetworkNoKeyResponse),
+            312 => Ok(MediaDetailedErrorCode::HlsNetworkPlaylist),
+            316 => Ok(MediaDetailedErrorCode::HlsSegmentParsing),
+            903 => Ok(MediaDetailedErrorCode::ImageError),
+            905 => Ok(MediaDetailedErrorCode::LoadFailed),
+            904 => Ok(MediaDetailedErrorCode::LoadInterrupted),
+            400 => Ok(MediaDetailedErrorCode::ManifestUnknown),
+            201 => Ok(MediaDetailedErrorCode::MediakeysNetwork),
+            200 => Ok(MediaDetailedErrorCode::MediakeysUnknown),
+            202 => Ok(MediaDetailedErrorCode::MediakeysUnsupported),
+            203 => Ok(MediaDetailedErrorCode::MediakeysWebcrypto),
+            101 => Ok(MediaDetailedErrorCode::MediaAborted),
+            102 => Ok(MediaDetailedErrorCode::MediaDecode),
+            906 => Ok(MediaDetailedErrorCode::MediaErrorMessage),
+            103 => Ok(MediaDetailedErrorCode::MediaNetwork),
+            104 => Ok(MediaDetailedErrorCode::MediaSrcNotSupported),
+            100 => Ok(MediaDetailedErrorCode::MediaUnknown),
+            300 => Ok(MediaDetailedErrorCode::NetworkUnknown),
+            301 => Ok(MediaDetailedErrorCode::SegmentNetwork),
+            500 => Ok(MediaDetailedErrorCode::SegmentUnknown),
+            431 => Ok(MediaDetailedErrorCode::SmoothManifest),
+            331 => Ok(MediaDetailedErrorCode::SmoothNetwork),
+            332 => Ok(MediaDetailedErrorCode::SmoothNoMediaData),
+            110 => Ok(MediaDetailedErrorCode::SourceBufferFailure),
+            600 => Ok(MediaDetailedErrorCode::TextUnknown),
+            _ => Err(Error::Parsing(format!(
+                "media error code {} is not supported",
+                value
+            ))),
+        }
+    }
+}
+
+/// Represents all currently supported incoming messages that media channel can handle.
+#[derive(Clone, Debug)]
+pub enum MediaResponse {
+    /// Statuses of the currently active media.
+    Status(Status),
+    /// IDs of all media queue items.
+    QueueItemIds(QueueItemIds),
+    /// Media queue items requested by their IDs.
+    QueueItems(QueueItems),
+    /// Sent when the load request was cancelled (a second load request was received).
+    LoadCancelled(LoadCancelled),
+    /// Sent when the load request failed. The player state will be IDLE.
+    LoadFailed(LoadFailed),
+    /// Sent when the request by the sender can not be fulfilled because the player is not in a
+    /// valid state. For example, if the application has not created a media element yet.
+    InvalidPlayerState(InvalidPlayerState),
+    /// Error indicating that request is not valid.
+    InvalidRequest(InvalidRequest),
+    /// The media error that occurred while executing a media operation on the media channel.
+    Error(MediaError),
+    /// Used every time when channel can't parse the message. Associated data contains `type` string
+    /// field and raw JSON data returned from cast device.
+    NotImplemented(String, serde_json::Value),
+}
+
+impl MediaResponse {
+    /// Converts error reply to the request with specified `request_id` into `Error::CastError`.
+    /// Returns `None` for any other response.
+    pub(crate) fn to_cast_error(&self, request_id: u32) -> Option<Error> {
+        let (type_, reason) = match *self {
+            MediaResponse::LoadCancelled(ref error) if error.request_id == request_id => {
+                (MESSAGE_TYPE_LOAD_CANCELLED, None)
+            }
+            MediaResponse::LoadFailed(ref error) if error.request_id == request_id => {
+                (MESSAGE_TYPE_LOAD_FAILED, None)
+            }
+            MediaResponse::InvalidPlayerState(ref error) if error.request_id == request_id => {
+                (MESSAGE_TYPE_INVALID_PLAYER_STATE, None)
+            }
+            MediaResponse::InvalidRequest(ref error) if error.request_id == request_id => {
+                (MESSAGE_TYPE_INVALID_REQUEST, error.reason.clone())
+            }
+            _ => return None,
+        };
+
+        Some(Error::CastError {
+            type_: type_.to_string(),
+            reason,
+            request_id: Some(i64::from(request_id)),
+        })
+    }
+}
+
+/// Blocking iterator over the `MEDIA_STATUS` updates, see `MediaChannel::status_updates`.
+pub struct StatusUpdates<'c, 'a, W>
+where
+    W: Read + Write,
+{
+    channel: &'c MediaChannel<'a, W>,
+    finished: bool,
+}
+
+impl<W> Iterator for StatusUpdates<'_, '_, W>
+where
+    W: Read + Write,
+{
+    type Item = Result<Status, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while !self.finished {
+            let message = match self.channel.message_manager.receive() {
+                Ok(message) => message,
+                Err(err @ Error::Timeout(_)) => return Some(Err(err)),
+                Err(Error::Io(ref err)) if err.kind() == ErrorKind::UnexpectedEof => {
+                    self.finished = true;
+                    return None;
+                }
+                Err(err) => {
+                    self.finished = true;
+                    return Some(Err(err));
+                }
+            };
+
+            if !self.channel.can_handle(&message) {
+                continue;
+            }
+
+            match self.channel.parse(&message) {
+                Ok(MediaResponse::Status(status)) => return Some(Ok(status)),
+                Ok(_) => {}
+                Err(err) => return Some(Err(err)),
+            }
+        }
+
+        None
+    }
+}
+
+pub struct MediaChannel<'a, W>
+where
+    W: Read + Write,
+{
+    sender: Cow<'a, str>,
+    namespace: Cow<'a, str>,
+    message_manager: Lrc<MessageManager<W>>,
+}
+
+impl<'a, W> MediaChannel<'a, W>
+where
+    W: Read + Write,
+{
+    pub fn new<S>(sender: S, message_manager: Lrc<MessageManager<W>>) -> MediaChannel<'a, W>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        MediaChannel {
+            sender: sender.into(),
+            namespace: Cow::Borrowed(namespaces::MEDIA),
+            message_manager,
+        }
+    }
+
+    /// Overrides the namespace media messages are exchanged in (`urn:x-cast:com.google.cast.media`
+    /// by default), so that standard media commands can be used with the custom receivers that
+    /// implement media protocol under their own namespace. The receiver application itself is
+    /// addressed by its `transport_id`, which every method accepts as `destination`.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - namespace of the media protocol implemented by the receiver application.
+    pub fn with_namespace<S>(mut self, namespace: S) -> Self
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.namespace = namespace.into();
+        self
+    }
+
+    /// Retrieves status of the cast device media session.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - `protocol` identifier of specific app media session;
+    /// * `media_session_id` - Media session ID of the media for which the media status should be
+    ///   returned. If none is provided, then the status for all media session IDs will be provided.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `Status` instance or an `Error`.
+    pub fn get_status<S>(
+        &self,
+        destination: S,
+        media_session_id: Option<i32>,
+    ) -> Result<Status, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let request_id = self.message_manager.generate_request_id().get();
+
+        let payload = serde_json::to_string(&proxies::media::GetStatusRequest {
+            typ: MESSAGE_TYPE_GET_STATUS.to_string(),
+            request_id,
+            media_session_id,
+        })?;
+
+        let destination = destination.into();
+
+        self.message_manager.send(CastMessage {
+            namespace: self.namespace.to_string(),
+            source: self.sender.to_string(),
+            destination: destination.to_string(),
+            payload: CastMessagePayload::String(payload),
+        })?;
+
+        self.receive_status(&destination, request_id)
+    }
+
+    /// Periodically polls status of the media session with `GET_STATUS` requests and passes every
+    /// received `Status` to `handler`, which is useful for receivers that don't reliably push
+    /// status updates. Polling stops once `stop` handle is triggered, `handler` fails, or media
+    /// session ends: the status that reports session as idle (or doesn't list it at all) is still
+    /// passed to `handler` as the final one.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - `protocol` identifier of specific app media session;
+    /// * `media_session_id` - ID of the media session to poll;
+    /// * `interval` - delay between two consecutive requests;
+    /// * `stop` - handle that stops polling once triggered;
+    /// * `handler` - function that is called for every received status.
+    pub fn poll_status<S, F>(
+        &self,
+        destination: S,
+        media_session_id: i32,
+        interval: Duration,
+        stop: &StopHandle,
+        mut handler: F,
+    ) -> Result<(), Error>
+    where
+        S: Into<Cow<'a, str>>,
+        F: FnMut(&Status) -> Result<(), Error>,
+    {
+        let destination = destination.into();
+
+        while !stop.is_stopped() {
+            let status = self.get_status(destination.clone(), Some(media_session_id))?;
+
+            let ended = status
+                .entries
+                .iter()
+                .find(|entry| entry.media_session_id == media_session_id)
+                .is_none_or(|entry| entry.player_state == PlayerState::Idle);
+
+            handler(&status)?;
+
+            if ended {
+                break;
+            }
+
+            thread::sleep(interval);
+        }
+
+        Ok(())
+    }
+
+    /// Loads provided media to the application.
+    ///
+    /// # Arguments
+    /// * `destination` - `protocol` of the application to load media with (e.g. `web-1`);
+    /// * `session_id` - Current session identifier of the player application;
+    /// * `media` - `Media` instance that describes the media we'd like to load.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `Status` instance or an `Error`.
+    pub fn load<S>(&self, destination: S, session_id: S, media: &Media) -> Result<Status, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.load_with_custom_data(destination, session_id, media, None)
+    }
+
+    /// Loads provided media to the application with associated custom data.
+    ///
+    /// # Arguments
+    /// * `destination` - `protocol` of the application to load media with (e.g. `web-1`);
+    /// * `session_id` - Current session identifier of the player application;
+    /// * `media` - `Media` instance that describes the media we'd like to load.
+    /// * `custom_data` - serializable object with custom data.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `Status` instance or an `Error`.
+    pub fn load_with_custom_data<S>(
+        &self,
+        destination: S,
+        session_id: S,
+        media: &Media,
+        custom_data: Option<CustomData>,
+    ) -> Result<Status, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.load_media(
+            destination,
+            session_id,
+            media,
+            custom_data,
+            LoadOptions::default(),
+        )
+    }
+
+    /// Loads provided media to the application using additional LOAD request parameters.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rust_cast::channels::media::{LoadOptions, Media, StreamType};
+    ///
+    /// # use rust_cast::CastDevice;
+    /// # let cast_device = CastDevice::connect_without_host_verification("host", 1234)?;
+    /// let media = Media {
+    ///     content_id: Some("http://example.com/video.mp4".to_string()),
+    ///     content_url: None,
+    ///     content_type: "video/mp4".to_string(),
+    ///     stream_type: StreamType::Buffered,
+    ///     duration: None,
+    ///     metadata: None,
+    ///     tracks: vec![],
+    ///     text_track_style: None,
+    ///     hls_segment_format: None,
+    ///     hls_video_segment_format: None,
+    ///     breaks: vec![],
+    ///     break_clips: vec![],
+    /// };
+    ///
+    /// cast_device.media.load_with_options(
+    ///     "web-1",
+    ///     "session-id",
+    ///     &media,
+    ///     LoadOptions {
+    ///         autoplay: false,
+    ///         current_time: Some(30.0),
+    ///         ..LoadOptions::default()
+    ///     },
+    /// )?;
+    /// # Ok::<(), rust_cast::errors::Error>(())
+    /// ```
+    ///
+    /// # Arguments
+    /// * `destination` - `protocol` of the application to load media with (e.g. `web-1`);
+    /// * `session_id` - Current session identifier of the player application;
+    /// * `media` - `Media` instance that describes the media we'd like to load;
+    /// * `options` - `LoadOptions` instance with additional LOAD request parameters.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `Status` instance or an `Error`. Media without
+    /// content id and URL, with a content URL that lacks a scheme or with a malformed content type
+    /// is rejected with `Error::InvalidRequest` before anything is sent.
+    pub fn load_with_options<S>(
+        &self,
+        destination: S,
+        session_id: S,
+        media: &Media,
+        options: LoadOptions,
+    ) -> Result<Status, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.load_media(destination, session_id, media, None, options)
+    }
+
+    /// Loads provided media to the application and makes receiver repeat it according to
+    /// `repeat_mode` once it ends (e.g. `RepeatMode::Single` loops it), without building the queue.
+    ///
+    /// # Arguments
+    /// * `destination` - `protocol` of the application to load media with (e.g. `web-1`);
+    /// * `session_id` - Current session identifier of the player application;
+    /// * `media` - `Media` instance that describes the media we'd like to load;
+    /// * `repeat_mode` - Behavior of the receiver when media ends.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `Status` instance or an `Error`.
+    pub fn load_looping<S>(
+        &self,
+        destination: S,
+        session_id: S,
+        media: &Media,
+        repeat_mode: RepeatMode,
+    ) -> Result<Status, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.load_with_options(
+            destination,
+            session_id,
+            media,
+            LoadOptions {
+                repeat_mode: Some(repeat_mode),
+                ..LoadOptions::default()
+            },
+        )
+    }
+
+    fn load_media<S>(
+        &self,
+        destination: S,
+        session_id: S,
+        media: &Media,
+        custom_data: Option<CustomData>,
+        options: LoadOptions,
+    ) -> Result<Status, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        media.validate()?;
+
+        let request_id = self.message_manager.generate_request_id().get();
+
+        let payload = serde_json::to_string(&load_request(
+            request_id,
+            session_id.into().to_string(),
+            media,
+            custom_data,
+            options,
+        ))?;
+
+        let destination = destination.into();
+
+        self.message_manager.send(CastMessage {
+            namespace: self.namespace.to_string(),
+            source: self.sender.to_string(),
+            destination: destination.to_string(),
+            payload: CastMessagePayload::String(payload),
+        })?;
+
+        self.receive_load_status(&destination, request_id, media.content_key())
+    }
+
+    /// Loads provided items into the media queue of the application and starts playback.
+    ///
+    /// # Arguments
+    /// * `destination` - `protocol` of the application to load media with (e.g. `web-1`);
+    /// * `session_id` - Current session identifier of the player application;
+    /// * `items` - `QueueItem` instances that describe media queue, there should be at least one;
+    /// * `start_index` - Index of the item in `items` to start playback with;
+    /// * `repeat_mode` - Behavior of the queue when the current item ends.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `Status` instance or an `Error`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidRequest` if `start_index` is out of `items` bounds or media of any
+    /// item can't be loaded (e.g. neither content id nor content URL is provided).
+    pub fn queue_load<S>(
+        &self,
+        destination: S,
+        session_id: S,
+        items: &[QueueItem],
+        start_index: u32,
+        repeat_mode: RepeatMode,
+    ) -> Result<Status, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let start_item = items.get(start_index as usize).ok_or_else(|| {
+            Error::InvalidRequest(format!(
+                "Start index {} is out of the queue bounds ({} items).",
+                start_index,
+                items.len()
+            ))
+        })?;
+
+        for item in items {
+            item.media.validate()?;
+        }
+
+        let request_id = self.message_manager.generate_request_id().get();
+
+        let payload = serde_json::to_string(&proxies::media::QueueLoadRequest {
+            request_id,
+            session_id: session_id.into().to_string(),
+            typ: MESSAGE_TYPE_QUEUE_LOAD.to_string(),
+            queue_data: proxies::media::QueueData {
+                items: items.iter().map(|item| item.encode()).collect(),
+                start_index,
+                repeat_mode,
+            },
+        })?;
+
+        let destination = destination.into();
+
+        self.message_manager.send(CastMessage {
+            namespace: self.namespace.to_string(),
+            source: self.sender.to_string(),
+            destination: destination.to_string(),
+            payload: CastMessagePayload::String(payload),
+        })?;
+
+        self.receive_load_status(&destination, request_id, start_item.media.content_key())
+    }
+
+    /// Inserts provided items into the media queue of the active media session.
+    ///
+    /// # Arguments
+    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
+    /// * `media_session_id` - ID of the media session which queue should be modified;
+    /// * `items` - `QueueItem` instances to insert;
+    /// * `insert_before` - ID of the queue item to insert new items before, new items are
+    ///   appended to the end of the queue if not provided.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `StatusEntry` instance or an `Error`.
+    pub fn queue_insert<S>(
+        &self,
+        destination: S,
+        media_session_id: i32,
+        items: &[QueueItem],
+        insert_before: Option<i32>,
+    ) -> Result<StatusEntry, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let request_id = self.message_manager.generate_request_id().get();
+
+        let payload = serde_json::to_string(&proxies::media::QueueInsertRequest {
+            request_id,
+            media_session_id,
+            typ: MESSAGE_TYPE_QUEUE_INSERT.to_string(),
+            items: items.iter().map(|item| item.encode()).collect(),
+            insert_before,
+        })?;
+
+        let destination = destination.into();
+
+        self.message_manager.send(CastMessage {
+            namespace: self.namespace.to_string(),
+            source: self.sender.to_string(),
+            destination: destination.to_string(),
+            payload: CastMessagePayload::String(payload),
+        })?;
+
+        self.receive_status_entry(&destination, request_id, media_session_id)
+    }
+
+    /// Updates the media queue of the active media session: jumps to another item, changes repeat
+    /// mode or properties of the existing items.
+    ///
+    /// # Arguments
+    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
+    /// * `media_session_id` - ID of the media session which queue should be updated;
+    /// * `options` - `QueueUpdateOptions` describing the update.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `Status` instance or an `Error`. Status doesn't
+    /// contain any entries if the update has finished the media session, e.g. if it jumped past
+    /// the end of the non-repeating queue.
+    pub fn queue_update<S>(
+        &self,
+        destination: S,
+        media_session_id: i32,
+        options: QueueUpdateOptions,
+    ) -> Result<Status, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let request_id = self.message_manager.generate_request_id().get();
+
+        let payload = serde_json::to_string(&proxies::media::QueueUpdateRequest {
+            request_id,
+            media_session_id,
+            typ: MESSAGE_TYPE_QUEUE_UPDATE.to_string(),
+            jump: options.jump,
+            current_item_id: options.current_item_id,
+            items: options.items.iter().map(|item| item.encode()).collect(),
+            repeat_mode: options.repeat_mode,
+            shuffle: options.shuffle,
+        })?;
+
+        let destination = destination.into();
+
+        self.message_manager.send(CastMessage {
+            namespace: self.namespace.to_string(),
+            source: self.sender.to_string(),
+            destination: destination.to_string(),
+            payload: CastMessagePayload::String(payload),
+        })?;
+
+        self.receive_status(&destination, request_id)
+    }
+
+    /// Skips to the next item in the media queue with the dedicated `QUEUE_NEXT` request. Falls
+    /// back to `QUEUE_UPDATE` with `jump` of `1` if receiver rejects the request with
+    /// `INVALID_REQUEST`, since not all receivers support it.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
+    /// * `media_session_id` - ID of the media session whose queue should be advanced.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `Status` instance or an `Error`.
+    pub fn queue_next<S>(&self, destination: S, media_session_id: i32) -> Result<Status, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.queue_jump(destination, media_session_id, MESSAGE_TYPE_QUEUE_NEXT, 1)
+    }
+
+    /// Skips to the previous item in the media queue with the dedicated `QUEUE_PREV` request. Falls
+    /// back to `QUEUE_UPDATE` with `jump` of `-1` if receiver rejects the request with
+    /// `INVALID_REQUEST`, since not all receivers support it.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
+    /// * `media_session_id` - ID of the media session whose queue should be rewound.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `Status` instance or an `Error`.
+    pub fn queue_prev<S>(&self, destination: S, media_session_id: i32) -> Result<Status, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.queue_jump(destination, media_session_id, MESSAGE_TYPE_QUEUE_PREV, -1)
+    }
+
+    fn queue_jump<S>(
+        &self,
+        destination: S,
+        media_session_id: i32,
+        message_type: &str,
+        jump: i32,
+    ) -> Result<Status, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let request_id = self.message_manager.generate_request_id().get();
+
+        let payload = serde_json::to_string(&proxies::media::PlaybackGenericRequest {
+            request_id,
+            media_session_id,
+            typ: message_type.to_string(),
+            custom_data: proxies::media::CustomData::new(),
+        })?;
+
+        let destination = destination.into();
+
+        self.message_manager.send(CastMessage {
+            namespace: self.namespace.to_string(),
+            source: self.sender.to_string(),
+            destination: destination.to_string(),
+            payload: CastMessagePayload::String(payload),
+        })?;
+
+        match self.receive_status(&destination, request_id) {
+            Err(Error::CastError { ref type_, .. }) if type_ == MESSAGE_TYPE_INVALID_REQUEST => {
+                log::debug!(
+                    "{} is not supported by {}, falling back to {}.",
+                    message_type,
+                    destination,
+                    MESSAGE_TYPE_QUEUE_UPDATE
+                );
+
+                self.queue_update(
+                    destination,
+                    media_session_id,
+                    QueueUpdateOptions {
+                        jump: Some(jump),
+                        ..QueueUpdateOptions::default()
+                    },
+                )
+            }
+            result => result,
+        }
+    }
+
+    /// Removes items from the media queue of the active media session. If the currently playing
+    /// item is removed, receiver moves on to the next item.
+    ///
+    /// # Arguments
+    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
+    /// * `media_session_id` - ID of the media session which queue should be modified;
+    /// * `item_ids` - IDs of the queue items to remove.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `Status` instance or an `Error`.
+    pub fn queue_remove<S>(
+        &self,
+        destination: S,
+        media_session_id: i32,
+        item_ids: &[i32],
+    ) -> Result<Status, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let request_id = self.message_manager.generate_request_id().get();
+
+        let payload = serde_json::to_string(&proxies::media::QueueRemoveRequest {
+            request_id,
+            media_session_id,
+            typ: MESSAGE_TYPE_QUEUE_REMOVE.to_string(),
+            item_ids: item_ids.to_vec(),
+        })?;
+
+        let destination = destination.into();
+
+        self.message_manager.send(CastMessage {
+            namespace: self.namespace.to_string(),
+            source: self.sender.to_string(),
+            destination: destination.to_string(),
+            payload: CastMessagePayload::String(payload),
+        })?;
+
+        self.receive_status(&destination, request_id)
+    }
+
+    /// Moves items of the media queue of the active media session.
+    ///
+    /// # Arguments
+    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
+    /// * `media_session_id` - ID of the media session which queue should be modified;
+    /// * `item_ids` - IDs of the queue items to move, in the order they should end up in;
+    /// * `insert_before` - ID of the queue item to move items before, items are moved to the end
+    ///   of the queue if not provided.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `Status` instance or an `Error`.
+    pub fn queue_reorder<S>(
+        &self,
+        destination: S,
+        media_session_id: i32,
+        item_ids: &[i32],
+        insert_before: Option<i32>,
+    ) -> Result<Status, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let request_id = self.message_manager.generate_request_id().get();
+
+        let payload = serde_json::to_string(&proxies::media::QueueReorderRequest {
+            request_id,
+            media_session_id,
+            typ: MESSAGE_TYPE_QUEUE_REORDER.to_string(),
+            item_ids: item_ids.to_vec(),
+            insert_before,
+        })?;
+
+        let destination = destination.into();
+
+        self.message_manager.send(CastMessage {
+            namespace: self.namespace.to_string(),
+            source: self.sender.to_string(),
+            destination: destination.to_string(),
+            payload: CastMessagePayload::String(payload),
+        })?;
+
+        self.receive_status(&destination, request_id)
+    }
+
+    /// Retrieves IDs of all items in the media queue of the active media session. Unlike media
+    /// status, that includes only a few items around the current one, this covers the whole queue.
+    ///
+    /// # Arguments
+    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
+    /// * `media_session_id` - ID of the media session which queue should be inspected.
+    ///
+    /// # Return value
+    ///
+    /// IDs of the queue items in the queue order.
+    pub fn queue_get_item_ids<S>(
+        &self,
+        destination: S,
+        media_session_id: i32,
+    ) -> Result<Vec<i32>, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let request_id = self.message_manager.generate_request_id().get();
+
+        let payload = serde_json::to_string(&proxies::media::QueueGetItemIdsRequest {
+            request_id,
+            media_session_id,
+            typ: MESSAGE_TYPE_QUEUE_GET_ITEM_IDS.to_string(),
+        })?;
+
+        let destination = destination.into();
+
+        self.message_manager.send(CastMessage {
+            namespace: self.namespace.to_string(),
+            source: self.sender.to_string(),
+            destination: destination.to_string(),
+            payload: CastMessagePayload::String(payload),
+        })?;
+
+        self.message_manager.receive_find_map(|message| {
+            if !self.can_handle(message) {
+                self.ensure_app_running(message, &destination, request_id)?;
+                return Ok(None);
+            }
+
+            let response = self.parse(message)?;
+            if let Some(error) = response.to_cast_error(request_id) {
+                return Err(error);
+            }
+
+            match response {
+                MediaResponse::QueueItemIds(reply) if reply.request_id == request_id => {
+                    Ok(Some(reply.item_ids))
+                }
+                _ => Ok(None),
+            }
+        })
+    }
+
+    /// Retrieves full information about the media queue items with specified IDs.
+    ///
+    /// # Arguments
+    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
+    /// * `media_session_id` - ID of the media session which queue should be inspected;
+    /// * `item_ids` - IDs of the queue items to retrieve.
+    ///
+    /// # Return value
+    ///
+    /// Requested queue items, items that no longer exist in the queue are omitted.
+    pub fn queue_get_items<S>(
+        &self,
+        destination: S,
+        media_session_id: i32,
+        item_ids: &[i32],
+    ) -> Result<Vec<QueueItem>, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let request_id = self.message_manager.generate_request_id().get();
+
+        let payload = serde_json::to_string(&proxies::media::QueueGetItemsRequest {
+            request_id,
+            media_session_id,
+            typ: MESSAGE_TYPE_QUEUE_GET_ITEMS.to_string(),
+            item_ids: item_ids.to_vec(),
+        })?;
+
+        let destination = destination.into();
+
+        self.message_manager.send(CastMessage {
+            namespace: self.namespace.to_string(),
+            source: self.sender.to_string(),
+            destination: destination.to_string(),
+            payload: CastMessagePayload::String(payload),
+        })?;
+
+        self.message_manager.receive_find_map(|message| {
+            if !self.can_handle(message) {
+                self.ensure_app_running(message, &destination, request_id)?;
+                return Ok(None);
+            }
+
+            let response = self.parse(message)?;
+            if let Some(error) = response.to_cast_error(request_id) {
+                return Err(error);
+            }
+
+            match response {
+                MediaResponse::QueueItems(reply) if reply.request_id == request_id => {
+                    Ok(Some(reply.items))
+                }
+                _ => Ok(None),
+            }
+        })
+    }
+
+    /// Pauses playback of the current content. Triggers a STATUS event notification to all sender
+    /// applications.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
+    /// * `media_session_id` - ID of the media session to be paused.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `Status` instance or an `Error`.
+    pub fn pause<S>(&self, destination: S, media_session_id: i32) -> Result<StatusEntry, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let request_id = self.message_manager.generate_request_id().get();
+
+        let payload = serde_json::to_string(&proxies::media::PlaybackGenericRequest {
+            request_id,
+            media_session_id,
+            typ: MESSAGE_TYPE_PAUSE.to_string(),
+            custom_data: proxies::media::CustomData::new(),
+        })?;
+
+        let destination = destination.into();
+
+        self.message_manager.send(CastMessage {
+            namespace: self.namespace.to_string(),
+            source: self.sender.to_string(),
+            destination: destination.to_string(),
+            payload: CastMessagePayload::String(payload),
+        })?;
+
+        self.receive_status_entry(&destination, request_id, media_session_id)
+    }
+
+    /// Begins playback of the content that was loaded with the load call, playback is continued
+    /// from the current time position.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
+    /// * `media_session_id` - ID of the media session to be played.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `Status` instance or an `Error`.
+    pub fn play<S>(&self, destination: S, media_session_id: i32) -> Result<StatusEntry, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let request_id = self.message_manager.generate_request_id().get();
+
+        let payload = serde_json::to_string(&proxies::media::PlaybackGenericRequest {
+            request_id,
+            media_session_id,
+            typ: MESSAGE_TYPE_PLAY.to_string(),
+            custom_data: proxies::media::CustomData::new(),
+        })?;
+
+        let destination = destination.into();
+
+        self.message_manager.send(CastMessage {
+            namespace: self.namespace.to_string(),
+            source: self.sender.to_string(),
+            destination: destination.to_string(),
+            payload: CastMessagePayload::String(payload),
+        })?;
+
+        self.receive_status_entry(&destination, request_id, media_session_id)
+    }
+
+    /// Retrieves status of the media session and makes sure that media is loaded into it, i.e. that
+    /// player isn't idle. Receivers reply with `INVALID_PLAYER_STATE` to transport commands (e.g.
+    /// `pause` or `seek`) sent before media is loaded, so this check can be used to avoid such
+    /// requests, e.g. while application is still starting up.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
+    /// * `media_session_id` - ID of the media session to check.
+    ///
+    /// # Return value
+    ///
+    /// `StatusEntry` of the media session.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::CastError` with `INVALID_PLAYER_STATE` type (and no request id) if media
+    /// session doesn't exist or its player is idle.
+    pub fn ensure_media_loaded<S>(
+        &self,
+        destination: S,
+        media_session_id: i32,
+    ) -> Result<StatusEntry, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let entry = self
+            .get_status(destination, Some(media_session_id))?
+            .entries
+            .into_iter()
+            .find(|entry| entry.media_session_id == media_session_id);
+
+        match entry {
+            Some(entry) if entry.player_state != PlayerState::Idle => Ok(entry),
+            Some(_) => Err(Error::CastError {
+                type_: MESSAGE_TYPE_INVALID_PLAYER_STATE.to_string(),
+                reason: Some(format!("Media session {} is idle.", media_session_id)),
+                request_id: None,
+            }),
+            None => Err(media_session_not_found_error(media_session_id)),
+        }
+    }
+
+    /// Toggles playback of the media session based on its current player state: playing media
+    /// (including the one that is buffering or loading and thus is about to play) is paused, while
+    /// paused or idle media is played.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
+    /// * `media_session_id` - ID of the media session to toggle playback of.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `StatusEntry` instance reported after the
+    /// command or an `Error`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::CastError` with `INVALID_PLAYER_STATE` type (and no request id) if media
+    /// session doesn't exist, same as `ensure_media_loaded`.
+    pub fn toggle_playback<S>(
+        &self,
+        destination: S,
+        media_session_id: i32,
+    ) -> Result<StatusEntry, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let destination = destination.into();
+
+        let status = self.get_status(destination.clone(), Some(media_session_id))?;
+        let entry = status
+            .entries
+            .iter()
+            .find(|entry| entry.media_session_id == media_session_id)
+            .ok_or_else(|| media_session_not_found_error(media_session_id))?;
+
+        match entry.player_state {
+            PlayerState::Playing | PlayerState::Buffering | PlayerState::Loading => {
+                self.pause(destination, media_session_id)
+            }
+            PlayerState::Paused | PlayerState::Idle => self.play(destination, media_session_id),
+        }
+    }
+
+    /// Stops playback of the current content. Triggers a STATUS event notification to all sender
+    /// applications. After this command the content will no longer be loaded and the
+    /// media_session_id is invalidated.
+    ///
+    /// This only unloads the media, the media application itself keeps running and stays on
+    /// screen. Use `ReceiverChannel::stop_app` to stop the whole application instead.
+    ///
+    /// Method waits until cast device reports the media session as `PlayerState::Idle` (normally
+    /// with `IdleReason::Cancelled`), either in the reply to this request or in a subsequent status
+    /// broadcast, since some receivers reply before the media has actually been unloaded. Many
+    /// receivers drop the session right away instead, so the reply that doesn't list the media
+    /// session anymore (e.g. `status: []`) means success too.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
+    /// * `media_session_id` - ID of the media session to be stopped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if media hasn't been stopped within `receiver::DEFAULT_TIMEOUT`.
+    ///
+    /// # Return value
+    ///
+    /// Status entry of the stopped media session, `None` if cast device has dropped the session
+    /// right away.
+    pub fn stop<S>(
+        &self,
+        destination: S,
+        media_session_id: i32,
+    ) -> Result<Option<StatusEntry>, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.stop_with_timeout(destination, media_session_id, receiver::DEFAULT_TIMEOUT)
+    }
+
+    /// Same as `stop`, but waits for the media to stop for up to `timeout`.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
+    /// * `media_session_id` - ID of the media session to be stopped;
+    /// * `timeout` - how long to wait for the cast device to confirm that media is stopped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if media hasn't been stopped within `timeout`.
+    pub fn stop_with_timeout<S>(
+        &self,
+        destination: S,
+        media_session_id: i32,
+        timeout: Duration,
+    ) -> Result<Option<StatusEntry>, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let request_id = self.message_manager.generate_request_id().get();
+
+        let payload = serde_json::to_string(&proxies::media::PlaybackGenericRequest {
+            request_id,
+            media_session_id,
+            typ: MESSAGE_TYPE_STOP.to_string(),
+            custom_data: proxies::media::CustomData::new(),
+        })?;
+
+        let destination = destination.into();
+
+        self.message_manager.send(CastMessage {
+            namespace: self.namespace.to_string(),
+            source: self.sender.to_string(),
+            destination: destination.to_string(),
+            payload: CastMessagePayload::String(payload),
+        })?;
+
+        self.message_manager
+            .receive_find_map_with_timeout(timeout, |message| {
+                if !self.can_handle(message) {
+                    self.ensure_app_running(message, &destination, request_id)?;
+                    return Ok(None);
+                }
+
+                let response = self.parse(message)?;
+                if let Some(error) = response.to_cast_error(request_id) {
+                    return Err(error);
+                }
+
+                if let MediaResponse::Status(mut status) = response {
+                    if status.request_id == request_id || status.request_id == 0 {
+                        let position = status
+                            .entries
+                            .iter()
+                            .position(|entry| entry.media_session_id == media_session_id);
+
+                        return Ok(match position {
+                            Some(position)
+                                if status.entries[position].player_state == PlayerState::Idle =>
+                            {
+                                Some(Some(status.entries.remove(position)))
+                            }
+                            None if status.request_id == request_id => Some(None),
+                            _ => None,
+                        });
+                    }
+                }
+
+                Ok(None)
+            })
+    }
+
+    /// Sets the playback rate of the current content (e.g. `1.5` to play 50% faster). Triggers a
+    /// STATUS event notification to all sender applications. The rate is sent as is, receivers
+    /// usually clamp it to the range they support (typically `0.5` - `2.0`), so the rate that has
+    /// actually been applied should be taken from the returned `StatusEntry`.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
+    /// * `media_session_id` - ID of the media session to change playback rate of;
+    /// * `playback_rate` - Desired playback rate, `1.0` is the normal speed.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `StatusEntry` instance or an `Error`.
+    pub fn set_playback_rate<S>(
+        &self,
+        destination: S,
+        media_session_id: i32,
+        playback_rate: f32,
+    ) -> Result<StatusEntry, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let request_id = self.message_manager.generate_request_id().get();
+
+        let payload = serde_json::to_string(&proxies::media::PlaybackRateRequest {
+            request_id,
+            media_session_id,
+            typ: MESSAGE_TYPE_SET_PLAYBACK_RATE.to_string(),
+            playback_rate,
+        })?;
+
+        let destination = destination.into();
+
+        self.message_manager.send(CastMessage {
+            namespace: self.namespace.to_string(),
+            source: self.sender.to_string(),
+            destination: destination.to_string(),
+            payload: CastMessagePayload::String(payload),
+        })?;
+
+        self.receive_status_entry(&destination, request_id, media_session_id)
+    }
+
+    /// Sets the volume level of the media stream, this is different from the cast device volume
+    /// that is controlled via `ReceiverChannel::set_volume`. Triggers a STATUS event notification
+    /// to all sender applications.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
+    /// * `media_session_id` - ID of the media session to change stream volume of;
+    /// * `level` - Stream volume level in the `0.0` - `1.0` range.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `StatusEntry` instance or an `Error`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnsupportedOperation` if media stream doesn't support `STREAM_VOLUME`
+    /// command.
+    pub fn set_stream_volume<S>(
+        &self,
+        destination: S,
+        media_session_id: i32,
+        level: f32,
+    ) -> Result<StatusEntry, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.send_stream_volume(
+            destination,
+            media_session_id,
+            proxies::media::StreamVolume {
+                level: Some(level),
+                muted: None,
+            },
+            SupportedMediaCommands::STREAM_VOLUME,
+        )
+    }
+
+    /// Mutes or unmutes the media stream, this is different from muting the cast device that is
+    /// done via `ReceiverChannel::set_mute`. Triggers a STATUS event notification to all sender
+    /// applications.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
+    /// * `media_session_id` - ID of the media session to mute or unmute;
+    /// * `muted` - Whether media stream should be muted or not.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `StatusEntry` instance or an `Error`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnsupportedOperation` if media stream doesn't support `STREAM_MUTE`
+    /// command.
+    pub fn set_stream_mute<S>(
+        &self,
+        destination: S,
+        media_session_id: i32,
+        muted: bool,
+    ) -> Result<StatusEntry, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.send_stream_volume(
+            destination,
+            media_session_id,
+            proxies::media::StreamVolume {
+                level: None,
+                muted: Some(muted),
+            },
+            SupportedMediaCommands::STREAM_MUTE,
+        )
+    }
+
+    /// Sets the current position in the stream. Triggers a STATUS event notification to all sender
+    /// applications. If the position provided is outside the range of valid positions for the
+    /// current content, then the player should pick a valid position as close to the requested
+    /// position as possible.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
+    /// * `media_session_id` - ID of the media session to seek in;
+    /// * `current_time` - Time in seconds to seek to.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `Status` instance or an `Error`.
+    pub fn seek<S>(
+        &self,
+        destination: S,
+        media_session_id: i32,
+        current_time: Option<f32>,
+        resume_state: Option<ResumeState>,
+    ) -> Result<StatusEntry, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let request_id = self.message_manager.generate_request_id().get();
+
+        let payload = serde_json::to_string(&proxies::media::PlaybackSeekRequest {
+            request_id,
+            media_session_id,
+            typ: MESSAGE_TYPE_SEEK.to_string(),
+            current_time,
+            relative_time: None,
+            resume_state: resume_state.map(|s| s.to_string()),
+            custom_data: proxies::media::CustomData::new(),
+        })?;
+
+        let destination = destination.into();
+
+        self.message_manager.send(CastMessage {
+            namespace: self.namespace.to_string(),
+            source: self.sender.to_string(),
+            destination: destination.to_string(),
+            payload: CastMessagePayload::String(payload),
+        })?;
+
+        self.receive_status_entry(&destination, request_id, media_session_id)
+    }
+
+    /// Sets the current position in the stream either to the absolute position or relatively to the
+    /// current one. Unlike `seek`, this method requests media status first to verify that the
+    /// position can be changed at all (ad break that isn't skippable yet can't be seeked through,
+    /// see `StatusEntry::is_skip_allowed`) and, for the live streams, that the target position is
+    /// within the seekable range reported by the receiver.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
+    /// * `media_session_id` - ID of the media session to seek in;
+    /// * `options` - `SeekOptions` describing target position and playback state.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `StatusEntry` instance or an `Error`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidRequest` if ad break can't be skipped yet or target position is
+    /// outside of the live seekable range.
+    pub fn seek_with_options<S>(
+        &self,
+        destination: S,
+        media_session_id: i32,
+        options: SeekOptions,
+    ) -> Result<StatusEntry, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let destination = destination.into();
+
+        if let Some(position) = options.position {
+            let status = self.get_status(destination.clone(), Some(media_session_id))?;
+            let entry = status
+                .entries
+                .iter()
+                .find(|entry| entry.media_session_id == media_session_id);
+
+            if entry.is_some_and(|entry| !entry.is_skip_allowed()) {
+                return Err(Error::InvalidRequest(
+                    "Ad break can't be skipped yet, seek is not allowed.".to_string(),
+                ));
+            }
+
+            if let Some(range) = entry.and_then(|entry| entry.live_seekable_range) {
+                let target = match position {
+                    SeekPosition::Absolute(time) => time,
+                    SeekPosition::Relative(delta) => {
+                        entry.and_then(|entry| entry.current_time).unwrap_or(0.0) + delta
+                    }
+                };
+
+                if !range.contains(target) {
+                    return Err(Error::InvalidRequest(format!(
+                        "Seek position {} is outside of the live seekable range {:?}.",
+                        target, range
+                    )));
+                }
+            }
+        }
+
+        let (current_time, relative_time) = match options.position {
+            Some(SeekPosition::Absolute(time)) => (Some(time), None),
+            Some(SeekPosition::Relative(delta)) => (None, Some(delta)),
+            None => (None, None),
+        };
+
+        let request_id = self.message_manager.generate_request_id().get();
+
+        let payload = serde_json::to_string(&proxies::media::PlaybackSeekRequest {
+            request_id,
+            media_session_id,
+            typ: MESSAGE_TYPE_SEEK.to_string(),
+            current_time,
+            relative_time,
+            resume_state: options.resume_state.map(|s| s.to_string()),
+            custom_data: proxies::media::CustomData::new(),
+        })?;
+
+        self.message_manager.send(CastMessage {
+            namespace: self.namespace.to_string(),
+            source: self.sender.to_string(),
+            destination: destination.to_string(),
+            payload: CastMessagePayload::String(payload),
+        })?;
+
+        self.receive_status_entry(&destination, request_id, media_session_id)
+    }
+
+    /// Modifies the text tracks style or change the tracks status. If a trackId does not match
+    /// the existing trackIds the whole request will fail and no status will change.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
+    /// * `media_session_id` - ID of the media session to modify tracks for;
+    /// * `track_selection` - Track selection data.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `Status` instance or an `Error`.
+    pub fn edit_tracks<S>(
+        &self,
+        destination: S,
+        media_session_id: i32,
+        track_selection: TrackSelection,
+    ) -> Result<StatusEntry, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let request_id = self.message_manager.generate_request_id().get();
+
+        let payload = serde_json::to_string(&proxies::media::EditTracksInfoRequest {
+            request_id,
+            media_session_id,
+            typ: MESSAGE_TYPE_EDIT_TRACKS_INFO.to_string(),
+            active_track_ids: track_selection.active_track_ids,
+            enable_text_tracks: track_selection.enable_text_tracks,
+            language: track_selection.language,
+            text_track_style: track_selection
+                .text_track_style
+                .as_ref()
+                .map(|style| style.encode()),
+        })?;
+
+        let destination = destination.into();
+
+        self.message_manager.send(CastMessage {
+            namespace: self.namespace.to_string(),
+            source: self.sender.to_string(),
+            destination: destination.to_string(),
+            payload: CastMessagePayload::String(payload),
+        })?;
+
+        self.receive_status_entry(&destination, request_id, media_session_id)
+    }
+
+    /// Activates specified tracks (e.g. subtitles or audio track in another language) and
+    /// optionally changes the style of the text tracks.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
+    /// * `media_session_id` - ID of the media session to modify tracks for;
+    /// * `active_track_ids` - IDs of the tracks that should be active, empty slice disables all
+    ///   tracks (e.g. turns subtitles off);
+    /// * `text_track_style` - Style of the text tracks, the current style is kept if not provided.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `Status` instance or an `Error`.
+    pub fn edit_tracks_info<S>(
+        &self,
+        destination: S,
+        media_session_id: i32,
+        active_track_ids: &[i32],
+        text_track_style: Option<TextTrackStyle>,
+    ) -> Result<StatusEntry, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.edit_tracks(
+            destination,
+            media_session_id,
+            TrackSelection {
+                active_track_ids: Some(active_track_ids.to_vec()),
+                text_track_style,
+                ..TrackSelection::default()
+            },
+        )
+    }
+
+    /// Waits until the player of the specified media session reaches `target` state, based on the
+    /// `MEDIA_STATUS` messages pushed by the receiver (e.g. to wait for `PlayerState::Playing`
+    /// after `load`). Messages that are read from the stream but aren't relevant are kept in the
+    /// buffer and can be later retrieved with `MessageManager::receive`.
+    ///
+    /// Timeout is checked the same way `MessageManager::receive_find_map_with_timeout` checks it.
+    ///
+    /// # Arguments
+    ///
+    /// * `media_session_id` - ID of the media session to watch;
+    /// * `target` - Player state to wait for;
+    /// * `timeout` - How long to wait for the player to reach `target` state.
+    ///
+    /// # Return value
+    ///
+    /// `StatusEntry` that reported `target` state.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::CastError` as soon as player becomes idle because of an error (unless
+    /// `PlayerState::Idle` is the `target`), and `Error::Timeout` if player hasn't reached `target`
+    /// state in time.
+    pub fn wait_for_state(
+        &self,
+        media_session_id: i32,
+        target: PlayerState,
+        timeout: Duration,
+    ) -> Result<StatusEntry, Error> {
+        self.wait_for_state_cancellable(media_session_id, target, timeout, &StopHandle::new())
+    }
+
+    /// Same as `wait_for_state`, but gives up waiting once `cancel` handle is triggered (e.g. when
+    /// user navigates away), without affecting the connection. Handle is checked every time a
+    /// message arrives or stream read times out.
+    ///
+    /// # Arguments
+    ///
+    /// * `media_session_id` - ID of the media session to watch;
+    /// * `target` - Player state to wait for;
+    /// * `timeout` - How long to wait for the player to reach `target` state;
+    /// * `cancel` - Handle that cancels waiting once triggered.
+    ///
+    /// # Errors
+    ///
+    /// Same as `wait_for_state`, plus `Error::Cancelled` if `cancel` handle has been triggered
+    /// before player reached `target` state.
+    pub fn wait_for_state_cancellable(
+        &self,
+        media_session_id: i32,
+        target: PlayerState,
+        timeout: Duration,
+        cancel: &StopHandle,
+    ) -> Result<StatusEntry, Error> {
+        let deadline = Instant::now() + timeout;
+        let timeout_error = || {
+            Error::Timeout(format!(
+                "Media session {} hasn't reached {} state within {:?}.",
+                media_session_id, target, timeout
+            ))
+        };
+
+        loop {
+            let result = self.message_manager.receive_find_map(|message| {
+                if self.can_handle(message) {
+                    if let MediaResponse::Status(status) = self.parse(message)? {
+                        let entry = status
+                            .entries
+                            .into_iter()
+                            .find(|entry| entry.media_session_id == media_session_id);
+
+                        if let Some(entry) = entry {
+                            if entry.player_state == target {
+                                return Ok(Some(entry));
+                            }
+
+                            if entry.player_state == PlayerState::Idle
+                                && entry.idle_reason == Some(IdleReason::Error)
+                            {
+                                return Err(Error::CastError {
+                                    type_: MESSAGE_TYPE_MEDIA_STATUS.to_string(),
+                                    reason: Some(IdleReason::Error.to_string()),
+                                    request_id: Some(i64::from(status.request_id))
+                                        .filter(|request_id| *request_id != 0),
+                                });
+                            }
+                        }
+                    }
+                }
+
+                if cancel.is_stopped() {
+                    // Message has been read already, so it shouldn't be lost along with the wait.
+                    self.message_manager.keep_out_of_band(message.clone());
+                    return Err(Error::Cancelled);
+                }
+
+                if Instant::now() >= deadline {
+                    self.message_manager.keep_out_of_band(message.clone());
+                    return Err(timeout_error());
+                }
+
+                Ok(None)
+            });
+
+            match result {
+                Err(Error::Timeout(_)) if cancel.is_stopped() => return Err(Error::Cancelled),
+                Err(Error::Timeout(_)) if Instant::now() < deadline => {}
+                Err(Error::Timeout(_)) => return Err(timeout_error()),
+                result => return result,
+            }
+        }
+    }
+
+    /// Returns blocking iterator over the `MEDIA_STATUS` updates pushed by the receiver (including
+    /// the buffered ones), e.g. to follow playback changes made by other senders.
+    ///
+    /// Messages of other namespaces are skipped, so if heartbeat has to be kept going while
+    /// iterating, consider `MessageManager::run_event_loop` with the dispatcher instead.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rust_cast::CastDevice;
+    /// # let cast_device = CastDevice::connect_without_host_verification("host", 1234)?;
+    /// for status in cast_device.media.status_updates() {
+    ///     for entry in status?.entries {
+    ///         println!("Media session {} is {}.", entry.media_session_id, entry.player_state);
+    ///     }
+    /// }
+    /// # Ok::<(), rust_cast::errors::Error>(())
+    /// ```
+    ///
+    /// # Return value
+    ///
+    /// Iterator that ends once connection is closed by the cast device. Read timeout (see
+    /// `CastDevice::set_read_timeout`) is yielded as `Error::Timeout`, iteration can go on after
+    /// that. Any other error is yielded once and ends the iteration.
+    pub fn status_updates(&self) -> StatusUpdates<'_, 'a, W> {
+        StatusUpdates {
+            channel: self,
+            finished: false,
+        }
+    }
+
+    pub fn can_handle(&self, message: &CastMessage) -> bool {
+        message.namespace == self.namespace
+    }
+
+    pub fn parse(&self, message: &CastMessage) -> Result<MediaResponse, Error> {
+        self.parse_envelope(message)
+            .map(|(_, _, response)| response)
+    }
+
+    /// Same as `parse`, but also returns the raw JSON payload the response has been parsed from,
+    /// e.g. to log or archive exactly what the cast device has sent.
+    ///
+    /// # Return value
+    ///
+    /// Tuple of the parsed response and raw payload.
+    pub fn parse_with_raw(
+        &self,
+        message: &CastMessage,
+    ) -> Result<(MediaResponse, serde_json::Value), Error> {
+        channels::parse_with_raw(message, |message| self.parse(message))
+    }
+
+    /// Same as `parse`, but also returns `requestId` (if any) and message type (empty for binary
+    /// payload) of `message`, e.g. for custom dispatching and logging.
+    ///
+    /// # Return value
+    ///
+    /// Tuple of the request id, message type and parsed response.
+    pub fn parse_envelope(
+        &self,
+        message: &CastMessage,
+    ) -> Result<(Option<i64>, String, MediaResponse), Error> {
+        channels::parse_envelope(message, &self.namespace, parse_response)
+    }
+
+    /// Waits for the status caused by the request with specified `request_id`.
+    ///
+    /// # Arguments
+    ///
+    /// * `destination` - `protocol` of the media application request has been sent to;
+    /// * `request_id` - ID of the request that caused status to be broadcasted.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `Status` instance or an `Error`.
+    fn receive_status(&self, destination: &str, request_id: u32) -> Result<Status, Error> {
+        self.message_manager.receive_find_map(|message| {
+            if !self.can_handle(message) {
+                self.ensure_app_running(message, destination, request_id)?;
+                return Ok(None);
+            }
+
+            let response = self.parse(message)?;
+            if let Some(error) = response.to_cast_error(request_id) {
+                return Err(error);
+            }
+
+            if let MediaResponse::Status(status) = response {
+                if status.request_id == request_id {
+                    return Ok(Some(status));
+                }
+            }
+
+            Ok(None)
+        })
+    }
+
+    /// Sends stream `SET_VOLUME` request once media status confirms that stream supports the
+    /// `required` command.
+    fn send_stream_volume<S>(
+        &self,
+        destination: S,
+        media_session_id: i32,
+        volume: proxies::media::StreamVolume,
+        required: SupportedMediaCommands,
+    ) -> Result<StatusEntry, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let destination = destination.into();
+
+        let status = self.get_status(destination.clone(), Some(media_session_id))?;
+        let supported = status
+            .entries
+            .iter()
+            .find(|entry| entry.media_session_id == media_session_id)
+            .is_some_and(|entry| entry.supported_media_commands.contains(required));
+
+        if !supported {
+            return Err(Error::UnsupportedOperation(format!(
+                "Media session {} doesn't support {:?} command.",
+                media_session_id, required
+            )));
+        }
+
+        let request_id = self.message_manager.generate_request_id().get();
+
+        let payload = serde_json::to_string(&proxies::media::StreamVolumeRequest {
+            request_id,
+            media_session_id,
+            typ: MESSAGE_TYPE_SET_VOLUME.to_string(),
+            volume,
+        })?;
+
+        self.message_manager.send(CastMessage {
+            namespace: self.namespace.to_string(),
+            source: self.sender.to_string(),
+            destination: destination.to_string(),
+            payload: CastMessagePayload::String(payload),
+        })?;
+
+        self.receive_status_entry(&destination, request_id, media_session_id)
+    }
+
+    /// Fails with `ERROR_TYPE_APP_STOPPED` error if `message` signals that the application behind
+    /// `destination` has stopped (it's not listed in the receiver status anymore or it has closed
+    /// virtual connection), so that request doesn't wait for the reply that never comes. Message
+    /// is kept in the buffer, so that it can be still processed with `MessageManager::receive`.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - message that is not handled by the channel;
+    /// * `destination` - `protocol` of the media application request has been sent to;
+    /// * `request_id` - ID of the pending request.
+    fn ensure_app_running(
+        &self,
+        message: &CastMessage,
+        destination: &str,
+        request_id: u32,
+    ) -> Result<(), Error> {
+        let payload = match message.payload.as_str() {
+            Some(payload) => payload,
+            None => return Ok(()),
+        };
+
+        let stopped = if message.namespace == namespaces::CONNECTION {
+            message.source == destination
+                && serde_json::from_str::<serde_json::Value>(payload).is_ok_and(|reply| {
+                    reply.get("type").and_then(|typ| typ.as_str())
+                        == Some(connection::MESSAGE_TYPE_CLOSE)
+                })
+        } else if message.namespace == namespaces::RECEIVER {
+            serde_json::from_str::<proxies::receiver::StatusReply>(payload).is_ok_and(|reply| {
+                reply.typ == receiver::MESSAGE_TYPE_RECEIVER_STATUS
+                    && !reply
+                        .status
+                        .applications
+                        .iter()
+                        .any(|app| app.transport_id == destination)
+            })
+        } else {
+            false
+        };
+
+        if !stopped {
+            return Ok(());
+        }
+
+        self.message_manager.keep_out_of_band(message.clone());
+
+        Err(Error::CastError {
+            type_: ERROR_TYPE_APP_STOPPED.to_string(),
+            reason: None,
+            request_id: Some(i64::from(request_id)),
+        })
+    }
+
+    /// Waits for the status caused by the LOAD-like request with specified `request_id`.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_id` - ID of the request that caused status to be broadcasted;
+    /// * `content_id` - ID of the content that should be loaded by the request.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `Status` instance or an `Error`.
+    fn receive_load_status(
+        &self,
+        destination: &str,
+        request_id: u32,
+        content_id: &str,
+    ) -> Result<Status, Error> {
+        // Once media is loaded cast receiver device should emit status update event, or load failed
+        // event if something went wrong.
+        self.message_manager.receive_find_map(|message| {
+            if !self.can_handle(message) {
+                self.ensure_app_running(message, destination, request_id)?;
+                return Ok(None);
+            }
+
+            let response = self.parse(message)?;
+            if let Some(error) = response.to_cast_error(request_id) {
+                return Err(error);
+            }
+
+            if let MediaResponse::Status(status) = response {
+                if status.request_id == request_id {
+                    return Ok(Some(status));
+                }
+
+                // [WORKAROUND] In some cases we don't receive response (e.g. from YouTube app),
+                // so let's just wait for the response with the media we're interested in and
+                // return it.
+                let has_media = {
+                    status.entries.iter().any(|entry| {
+                        if let Some(ref loaded_media) = entry.media {
+                            return loaded_media.content_key() == content_id;
+                        }
+
+                        false
+                    })
+                };
+
+                if has_media {
+                    return Ok(Some(status));
+                }
+            }
+
+            Ok(None)
+        })
+    }
+
+    /// Waits for the status entry with specified `request_id` and `media_session_id`. This method
+    /// is very handy for the media playback methods where particular `StatusEntry` is required.
+    ///
+    /// # Arguments
+    ///
+    /// * `request_id` - ID of the request that caused status entry to be broadcasted.
+    /// * `media_session_id` - ID of the media session to receive.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `Status` instance or an `Error`.
+    fn receive_status_entry(
+        &self,
+        destination: &str,
+        request_id: u32,
+        media_session_id: i32,
+    ) -> Result<StatusEntry, Error> {
+        self.message_manager.receive_find_map(|message| {
+            if !self.can_handle(message) {
+                self.ensure_app_running(message, destination, request_id)?;
+                return Ok(None);
+            }
+
+            let response = self.parse(message)?;
+            if let Some(error) = response.to_cast_error(request_id) {
+                return Err(error);
+            }
+
+            if let MediaResponse::Status(mut status) = response {
+                if status.request_id == request_id {
+                    let position = status
+                        .entries
+                        .iter()
+                        .position(|e| e.media_session_id == media_session_id);
+
+                    return Ok(position.map(|position| status.entries.remove(position)));
+                }
+            }
+
+            Ok(None)
+        })
+    }
+}
+
+/// Error reported when requested media session doesn't exist, same as cast device reports
+/// transport commands sent to it.
+pub(crate) fn media_session_not_found_error(media_session_id: i32) -> Error {
+    Error::CastError {
+        type_: MESSAGE_TYPE_INVALID_PLAYER_STATE.to_string(),
+        reason: Some(format!("Media session {} is not found.", media_session_id)),
+        request_id: None,
+    }
+}
+
+/// Builds LOAD request for `media` with the specified `options`.
+pub(crate) fn load_request(
+    request_id: u32,
+    session_id: String,
+    media: &Media,
+    custom_data: Option<CustomData>,
+    options: LoadOptions,
+) -> proxies::media::MediaRequest {
+    proxies::media::MediaRequest {
+        request_id,
+        session_id,
+        typ: MESSAGE_TYPE_LOAD.to_string(),
+
+        media: media.encode(custom_data),
+
+        current_time: options.current_time,
+        active_track_ids: options.active_track_ids,
+        credentials: options.credentials,
+        credentials_type: options.credentials_type,
+        queue_data: options
+            .repeat_mode
+            .map(|repeat_mode| proxies::media::LoadQueueData { repeat_mode }),
+        autoplay: options.autoplay,
+        custom_data: options
+            .custom_data
+            .unwrap_or_else(|| serde_json::Value::Object(Default::default())),
+    }
+}
+
+/// Parses JSON payload of the media message with `message_type` into `MediaResponse`.
+pub(crate) fn parse_response(
+    message_type: &str,
+    reply: serde_json::Value,
+) -> Result<MediaResponse, Error> {
+    Ok(match message_type {
+        MESSAGE_TYPE_MEDIA_STATUS => {
+            let reply: proxies::media::StatusReply = serde_json::value::from_value(reply)?;
+
+            let statuses_entries = reply
+                .status
+                .iter()
+                .map(|x| {
+                    Ok(StatusEntry {
+                        active_track_ids: x.active_track_ids.clone(),
+                        media_session_id: x.media_session_id,
+                        media: x.media.as_ref().map(Media::decode),
+                        live_seekable_range: x
+                            .live_seekable_range
+                            .as_ref()
+                            .map(LiveSeekableRange::decode),
+                        playback_rate: x.playback_rate,
+                        player_state: x
+                            .player_state
+                            .as_ref()
+                            .map_or(Ok(PlayerState::Idle), |state| PlayerState::from_str(state))?,
+                        idle_reason: x
+                            .idle_reason
+                            .as_ref()
+                            .map(|reason| IdleReason::from_str(reason))
+                            .transpose()?,
+                        current_time: x.current_time,
+                        supported_media_commands: SupportedMediaCommands::from_bits_retain(
+                            x.supported_media_commands,
+                        ),
+                        volume: x.volume.as_ref().map(Volume::decode),
+                        current_item_id: x.current_item_id,
+                        loading_item_id: x.loading_item_id,
+                        preloaded_item_id: x.preloaded_item_id,
+                        repeat_mode: x.repeat_mode.clone(),
+                        items: x.items.iter().filter_map(QueueItem::decode).collect(),
+                        break_status: x.break_status.as_ref().map(BreakStatus::decode),
+                    })
+                })
+                .collect::<Result<Vec<StatusEntry>, Error>>()?;
+
+            MediaResponse::Status(Status {
+                request_id: reply.request_id,
+                entries: statuses_entries,
+            })
+        }
+        MESSAGE_TYPE_LOAD_CANCELLED => {
+            let reply: proxies::media::LoadCancelledReply = serde_json::value::from_value(reply)?;
+
+            MediaResponse::LoadCancelled(LoadCancelled {
+                request_id: reply.request_id,
+            })
+        }
+        MESSAGE_TYPE_LOAD_FAILED => {
+            let reply: proxies::media::LoadFailedReply = serde_json::value::from_value(reply)?;
+
+            MediaResponse::LoadFailed(LoadFailed {
+                request_id: reply.request_id,
+            })
+        }
+        MESSAGE_TYPE_INVALID_PLAYER_STATE => {
+            let reply: proxies::media::InvalidPlayerStateReply =
+                serde_json::value::from_value(reply)?;
+
+            MediaResponse::InvalidPlayerState(InvalidPlayerState {
+                request_id: reply.request_id,
+            })
+        }
+        MESSAGE_TYPE_INVALID_REQUEST => {
+            let reply: proxies::media::InvalidRequestReply = serde_json::value::from_value(reply)?;
+
+            MediaResponse::InvalidRequest(InvalidRequest {
+                request_id: reply.request_id,
+                reason: reply.reason,
+            })
+        }
+        MESSAGE_TYPE_QUEUE_ITEM_IDS => {
+            let reply: proxies::media::QueueItemIdsReply = serde_json::value::from_value(reply)?;
+
+            MediaResponse::QueueItemIds(QueueItemIds {
+                request_id: reply.request_id,
+                item_ids: reply.item_ids,
+            })
+        }
+        MESSAGE_TYPE_QUEUE_ITEMS => {
+            let reply: proxies::media::QueueItemsReply = serde_json::value::from_value(reply)?;
+
+            MediaResponse::QueueItems(QueueItems {
+                request_id: reply.request_id,
+                items: reply.items.iter().filter_map(QueueItem::decode).collect(),
+            })
+        }
+        MESSAGE_TYPE_ERROR => {
+            let reply: proxies::media::MediaErrorReply = serde_json::value::from_value(reply)?;
+            let detailed_error_code = MediaDetailedErrorCode::try_from(reply.detailed_error_code)?;
+
+            MediaResponse::Error(MediaError {
+                detailed_error_code,
+                message_type: reply.message_type,
+            })
+        }
+        _ => MediaResponse::NotImplemented(message_type.to_string(), reply),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{channels::tests::MockTcpStream, DEFAULT_RECEIVER_ID, DEFAULT_SENDER_ID};
+
+    use super::*;
+
+    /// Builds `MEDIA_STATUS` message sent by the media application `web-1` in reply to the request
+    /// with `request_id` (`0` for broadcasts), `status` is the array of status entries.
+    fn media_status(request_id: u32, status: serde_json::Value) -> CastMessage {
+        CastMessage {
+            namespace: namespaces::MEDIA.to_string(),
+            source: "web-1".to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String(
+                serde_json::json!({
+                    "type": "MEDIA_STATUS",
+                    "requestId": request_id,
+                    "status": status
+                })
+                .to_string(),
+            ),
+        }
+    }
+
+    #[test]
+    fn test_parse_media_error() {
+        let message = CastMessage {
+            namespace: namespaces::MEDIA.to_string(),
+            source: DEFAULT_RECEIVER_ID.to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String(
+                "{\"type\":\"ERROR\",\"detailedErrorCode\":104,\"itemId\":1}".to_string(),
+            ),
+        };
+        let channel = MediaChannel {
+            sender: Cow::from(DEFAULT_SENDER_ID),
+            namespace: Cow::from(namespaces::MEDIA),
+            message_manager: Lrc::new(MessageManager::new(MockTcpStream::new())),
+        };
+        let expected_result = MediaError {
+            detailed_error_code: MediaDetailedErrorCode::MediaSrcNotSupported,
+            message_type: MESSAGE_TYPE_ERROR.to_string(),
+        };
+
+        let response = channel.parse(&message).unwrap();
+
+        if let MediaResponse::Error(result) = response {
+            assert_eq!(expected_result, result);
+        } else {
+            panic!("expected MediaResponse::Error, but got {:?}", response);
+        }
+    }
+
+    #[test]
+    fn test_parse_unknown_message_type() {
+        let message_type = "FOO_BAR";
+        let payload = format!("{{\"type\":\"{}\",\"itemId\":666}}", message_type);
+        let expected_payload = serde_json::from_str::<serde_json::Value>(payload.as_str()).unwrap();
+        let message = CastMessage {
+            namespace: namespaces::MEDIA.to_string(),
+            source: DEFAULT_RECEIVER_ID.to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String(payload),
+        };
+        let channel = MediaChannel {
+            sender: Cow::from(DEFAULT_SENDER_ID),
+            namespace: Cow::from(namespaces::MEDIA),
+            message_manager: Lrc::new(MessageManager::new(MockTcpStream::new())),
+        };
+
+        let response = channel.parse(&message).unwrap();
+
+        if let MediaResponse::NotImplemented(result_code, result_payload) = response {
+            assert_eq!(message_type, result_code);
+            assert_eq!(expected_payload, result_payload);
+        } else {
+            panic!("expected MediaResponse::Error, but got {:?}", response);
+        }
+    }
+
+    #[test]
+    fn test_parse_preloaded_item() {
+        let channel = MediaChannel::new(
+            DEFAULT_SENDER_ID,
+            Lrc::new(MessageManager::new(MockTcpStream::new())),
+        );
+        let parse_entry = |status: serde_json::Value| {
+            let message = media_status(0, serde_json::json!([status]));
+
+            match channel.parse(&message).unwrap() {
+                MediaResponse::Status(mut status) => status.entries.remove(0),
+                response => panic!("expected MediaResponse::Status, but got {:?}", response),
+            }
+        };
+
+        let entry = parse_entry(serde_json::json!({
+            "mediaSessionId": 1,
+            "playerState": "PLAYING",
+            "currentItemId": 1,
+            "loadingItemId": 2,
+            "preloadedItemId": 2,
+            "items": [{
+                "itemId": 2,
+                "media": {
+                    "contentId": "http://example.com/2.mp3",
+                    "contentType": "audio/mpeg",
+                    "streamType": "BUFFERED"
+                }
+            }]
+        }));
+        assert_eq!(entry.loading_item_id, Some(2));
+        assert_eq!(entry.preloaded_item_id, Some(2));
+        assert_eq!(
+            entry
+                .preloaded_item()
+                .and_then(|item| item.media.content_id.as_deref()),
+            Some("http://example.com/2.mp3")
+        );
+
+        // Near the end of the non-repeating queue there is nothing to preload.
+        let entry = parse_entry(serde_json::json!({
+            "mediaSessionId": 1,
+            "playerState": "PLAYING",
+            "currentItemId": 2,
+            "preloadedItemId": null
+        }));
+        assert_eq!(entry.loading_item_id, None);
+        assert_eq!(entry.preloaded_item_id, None);
+        assert!(entry.preloaded_item().is_none());
+    }
+
+    #[test]
+    fn test_queue_data() {
+        let item = |item_id: i32, duration: Option<f32>, playback_duration: Option<f64>| {
+            let mut item = QueueItem::new(Media {
+                content_id: Some(format!("http://example.com/{}.mp3", item_id)),
+                content_url: None,
+                content_type: "audio/mpeg".to_string(),
+                stream_type: StreamType::Buffered,
+                duration,
+                metadata: None,
+                tracks: vec![],
+                text_track_style: None,
+                hls_segment_format: None,
+                hls_video_segment_format: None,
+                breaks: vec![],
+                break_clips: vec![],
+            });
+            item.item_id = Some(item_id);
+            item.start_time = Some(10.0);
+            item.playback_duration = playback_duration;
+            item
+        };
+
+        let mut queue = QueueData {
+            items: vec![item(1, Some(100.0), None), item(2, None, Some(30.0))],
+            current_item_id: Some(2),
+        };
+
+        assert_eq!(queue.total_duration(), Some(120.0));
+        assert_eq!(queue.current_index(), Some(1));
+
+        queue.items.push(item(3, None, None));
+        queue.current_item_id = Some(4);
+
+        assert_eq!(queue.total_duration(), None);
+        assert_eq!(queue.current_index(), None);
+        assert_eq!(QueueData::default().total_duration(), Some(0.0));
+    }
+
+    #[test]
+    fn test_queue_item_custom_data() {
+        let mut item = QueueItem::new(Media {
+            content_id: Some("http://example.com/1.mp3".to_string()),
+            content_url: None,
+            content_type: "audio/mpeg".to_string(),
+            stream_type: StreamType::Buffered,
+            duration: None,
+            metadata: None,
+            tracks: vec![],
+            text_track_style: None,
+            hls_segment_format: None,
+            hls_video_segment_format: None,
+            breaks: vec![],
+            break_clips: vec![],
+        });
+
+        let json = serde_json::to_value(item.encode()).unwrap();
+        assert!(json.get("customData").is_none());
+
+        item.custom_data = Some(serde_json::json!({ "trackId": 42 }));
+
+        let json = serde_json::to_value(item.encode()).unwrap();
+        assert_eq!(json["customData"], serde_json::json!({ "trackId": 42 }));
+        assert!(json["media"].get("customData").is_none());
+
+        let item: proxies::media::QueueItem = serde_json::from_value(json).unwrap();
+        let item = QueueItem::decode(&item).unwrap();
+        assert_eq!(item.custom_data, Some(serde_json::json!({ "trackId": 42 })));
+    }
+
+    #[test]
+    fn test_parse_media_status_with_queue_items() {
+        let payload = serde_json::json!({
+            "type": "MEDIA_STATUS",
+            "requestId": 7,
+            "status": [{
+                "mediaSessionId": 1,
+                "playbackRate": 1,
+                "playerState": "PLAYING",
+                "supportedMediaCommands": (1 << 30) | 15,
+                "currentItemId": 2,
+                "repeatMode": "REPEAT_ALL",
+                "items": [
+                    {
+                        "itemId": 1,
+                        "autoplay": true,
+                        "media": {
+                            "contentId": "http://example.com/1.mp3",
+                            "contentType": "audio/mpeg",
+                            "streamType": "BUFFERED"
+                        }
+                    },
+                    {
+                        "itemId": 2,
+                        "preloadTime": 10.0,
+                        "media": {
+                            "contentId": "http://example.com/2.mp3",
+                            "contentType": "audio/mpeg",
+                            "streamType": "BUFFERED"
+                        }
+                    },
+                    { "itemId": 3 }
+                ]
+            }]
+        });
+        let message = CastMessage {
+            namespace: namespaces::MEDIA.to_string(),
+            source: DEFAULT_RECEIVER_ID.to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String(payload.to_string()),
+        };
+        let channel = MediaChannel {
+            sender: Cow::from(DEFAULT_SENDER_ID),
+            namespace: Cow::from(namespaces::MEDIA),
+            message_manager: Lrc::new(MessageManager::new(MockTcpStream::new())),
+        };
+
+        let response = channel.parse(&message).unwrap();
+
+        if let MediaResponse::Status(mut status) = response {
+            let entry = status.entries.remove(0);
+
+            assert_eq!(entry.current_item_id, Some(2));
+            assert!(entry
+                .supported_media_commands
+                .contains(SupportedMediaCommands::PAUSE | SupportedMediaCommands::SEEK));
+            assert_eq!(entry.supported_media_commands.bits(), (1 << 30) | 15);
+            assert_eq!(entry.repeat_mode, Some(RepeatMode::All));
+            assert_eq!(entry.items.len(), 2);
+            assert_eq!(entry.items[0].item_id, Some(1));
+            assert_eq!(entry.items[1].item_id, Some(2));
+            assert_eq!(entry.items[1].preload_time, Some(10.0));
+            assert_eq!(
+                entry.items[1].media.content_id.as_deref(),
+                Some("http://example.com/2.mp3")
+            );
+        } else {
+            panic!("expected MediaResponse::Status, but got {:?}", response);
+        }
+    }
+
+    #[test]
+    fn test_seek_outside_live_seekable_range() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&media_status(
+            1,
+            serde_json::json!([{
+                "mediaSessionId": 1,
+                "playbackRate": 1,
+                "playerState": "PLAYING",
+                "supportedMediaCommands": 15,
+                "currentTime": 90.0,
+                "liveSeekableRange": { "start": 0.0, "end": 100.0 }
+            }]),
+        ));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::new(MessageManager::new(stream)));
+
+        let result = channel.seek_with_options(
+            "web-1",
+            1,
+            SeekOptions {
+                position: Some(SeekPosition::Relative(20.0)),
+                resume_state: None,
+            },
+        );
+
+        assert!(matches!(result, Err(Error::InvalidRequest(_))));
+    }
+
+    #[test]
+    fn test_seek_during_unskippable_break() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&media_status(
+            1,
+            serde_json::json!([{
+                "mediaSessionId": 1,
+                "playbackRate": 1,
+                "playerState": "PLAYING",
+                "supportedMediaCommands": 15,
+                "currentTime": 3.0,
+                "breakStatus": {
+                    "currentBreakTime": 3.0,
+                    "currentBreakClipTime": 3.0,
+                    "breakId": "preroll",
+                    "breakClipId": "ad-1",
+                    "whenSkippable": 5.0
+                }
+            }]),
+        ));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::clone(&message_manager));
+
+        let result = channel.seek_with_options(
+            "web-1",
+            1,
+            SeekOptions {
+                position: Some(SeekPosition::Absolute(60.0)),
+                resume_state: None,
+            },
+        );
+
+        assert!(matches!(result, Err(Error::InvalidRequest(_))));
+
+        let written = String::from_utf8_lossy(&message_manager.inner().write_buffer).to_string();
+        assert!(written.contains("\"type\":\"GET_STATUS\""));
+        assert!(!written.contains("\"type\":\"SEEK\""));
+    }
+
+    #[test]
+    fn test_play_invalid_player_state() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&CastMessage {
+            namespace: namespaces::MEDIA.to_string(),
+            source: DEFAULT_RECEIVER_ID.to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String(
+                serde_json::json!({ "type": "INVALID_PLAYER_STATE", "requestId": 1 }).to_string(),
+            ),
+        });
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::new(MessageManager::new(stream)));
+
+        match channel.play("web-1", 1) {
+            Err(Error::CastError {
+                type_, request_id, ..
+            }) => {
+                assert_eq!(type_, MESSAGE_TYPE_INVALID_PLAYER_STATE);
+                assert_eq!(request_id, Some(1));
+            }
+            result => panic!("expected Error::CastError, but got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_pause_before_media_is_loaded() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&media_status(
+            1,
+            serde_json::json!([{
+                "mediaSessionId": 1,
+                "playerState": "IDLE",
+                "idleReason": "FINISHED"
+            }]),
+        ));
+        stream.add_message(&CastMessage {
+            namespace: namespaces::MEDIA.to_string(),
+            source: "web-1".to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String(
+                r#"{"type":"INVALID_PLAYER_STATE","requestId":2,"customData":{}}"#.to_string(),
+            ),
+        });
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::new(MessageManager::new(stream)));
+
+        match channel.ensure_media_loaded("web-1", 1) {
+            Err(Error::CastError {
+                type_, request_id, ..
+            }) => {
+                assert_eq!(type_, MESSAGE_TYPE_INVALID_PLAYER_STATE);
+                assert_eq!(request_id, None);
+            }
+            result => panic!("expected Error::CastError, but got {:?}", result),
+        }
+
+        match channel.pause("web-1", 1) {
+            Err(Error::CastError {
+                type_, request_id, ..
+            }) => {
+                assert_eq!(type_, MESSAGE_TYPE_INVALID_PLAYER_STATE);
+                assert_eq!(request_id, Some(2));
+            }
+            result => panic!("expected Error::CastError, but got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_edit_tracks_info_disables_all_tracks() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&media_status(
+            1,
+            serde_json::json!([{ "mediaSessionId": 1, "playerState": "PLAYING" }]),
+        ));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::clone(&message_manager));
+
+        let entry = channel.edit_tracks_info("web-1", 1, &[], None).unwrap();
+
+        assert_eq!(entry.media_session_id, 1);
+
+        let written = String::from_utf8_lossy(&message_manager.inner().write_buffer).to_string();
+        assert!(written.contains("\"activeTrackIds\":[]"));
+        assert!(!written.contains("textTrackStyle"));
+    }
+
+    #[test]
+    fn test_encode_decode_media_tracks() {
+        let media = Media {
+            content_id: Some("http://example.com/video.mp4".to_string()),
+            content_url: None,
+            stream_type: StreamType::Buffered,
+            content_type: "video/mp4".to_string(),
+            metadata: None,
+            tracks: vec![Track {
+                id: 1,
+                track_type: TrackType::Text,
+                name: Some("English".to_string()),
+                language: Some("en-US".to_string()),
+                track_content_id: Some("http://example.com/en.vtt".to_string()),
+                track_content_type: Some("text/vtt".to_string()),
+                subtype: Some(TextTrackType::Subtitles),
+            }],
+            text_track_style: None,
+            hls_segment_format: None,
+            hls_video_segment_format: None,
+            duration: None,
+            breaks: vec![],
+            break_clips: vec![],
+        };
+
+        let encoded = serde_json::to_value(media.encode(None)).unwrap();
+        assert_eq!(
+            encoded["tracks"][0],
+            serde_json::json!({
+                "trackId": 1,
+                "type": "TEXT",
+                "name": "English",
+                "language": "en-US",
+                "trackContentId": "http://example.com/en.vtt",
+                "trackContentType": "text/vtt",
+                "subtype": "SUBTITLES"
+            })
+        );
+
+        let decoded = Media::decode(&serde_json::from_value(encoded).unwrap());
+        assert_eq!(decoded.tracks.len(), 1);
+        assert_eq!(decoded.tracks[0].subtype, Some(TextTrackType::Subtitles));
+        assert_eq!(decoded.tracks[0].track_type, TrackType::Text);
+    }
+
+    #[test]
+    fn test_encode_media_hls_hints() {
+        let mut media = Media {
+            content_id: Some("http://example.com/stream.m3u8".to_string()),
+            content_url: None,
+            stream_type: StreamType::Buffered,
+            content_type: CONTENT_TYPE_HLS.to_string(),
+            metadata: None,
+            tracks: vec![],
+            text_track_style: None,
+            hls_segment_format: None,
+            hls_video_segment_format: None,
+            duration: None,
+            breaks: vec![],
+            break_clips: vec![],
+        };
+
+        assert_eq!(
+            serde_json::to_value(media.encode(None)).unwrap(),
+            serde_json::json!({
+                "contentId": "http://example.com/stream.m3u8",
+                "streamType": "BUFFERED",
+                "contentType": "application/vnd.apple.mpegurl",
+                "tracks": []
+            })
+        );
+
+        media.hls_segment_format = Some(HlsSegmentFormat::Fmp4);
+        media.hls_video_segment_format = Some(HlsVideoSegmentFormat::Mpeg2Ts);
+
+        let encoded = serde_json::to_value(media.encode(None)).unwrap();
+        assert_eq!(encoded["hlsSegmentFormat"], "fmp4");
+        assert_eq!(encoded["hlsVideoSegmentFormat"], "mpeg2_ts");
+
+        let decoded = Media::decode(&serde_json::from_value(encoded).unwrap());
+        assert_eq!(decoded.hls_segment_format, Some(HlsSegmentFormat::Fmp4));
+        assert_eq!(
+            decoded.hls_video_segment_format,
+            Some(HlsVideoSegmentFormat::Mpeg2Ts)
+        );
+    }
+
+    #[test]
+    fn test_queue_update_jump_past_end() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&media_status(1, serde_json::json!([])));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::clone(&message_manager));
+
+        let status = channel
+            .queue_update(
+                "web-1",
+                1,
+                QueueUpdateOptions {
+                    jump: Some(1),
+                    ..QueueUpdateOptions::default()
+                },
+            )
+            .unwrap();
+
+        assert!(status.entries.is_empty());
+
+        let written = String::from_utf8_lossy(&message_manager.inner().write_buffer).to_string();
+        assert!(written.contains("\"type\":\"QUEUE_UPDATE\""));
+        assert!(written.contains("\"jump\":1"));
+        assert!(!written.contains("\"items\""));
+    }
+
+    #[test]
+    fn test_queue_next_falls_back_to_queue_update() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&media_status(1, serde_json::json!([])));
+        stream.add_message(&CastMessage {
+            namespace: namespaces::MEDIA.to_string(),
+            source: "web-1".to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String(
+                serde_json::json!({
+                    "type": "INVALID_REQUEST",
+                    "requestId": 2,
+                    "reason": "INVALID_COMMAND"
+                })
+                .to_string(),
+            ),
+        });
+        stream.add_message(&media_status(3, serde_json::json!([])));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::clone(&message_manager));
+
+        channel.queue_next("web-1", 1).unwrap();
+        let written = String::from_utf8_lossy(&message_manager.inner().write_buffer).to_string();
+        assert!(written.contains("\"type\":\"QUEUE_NEXT\""));
+        assert!(!written.contains("QUEUE_UPDATE"));
+
+        message_manager.inner().write_buffer.clear();
+
+        channel.queue_prev("web-1", 1).unwrap();
+        let written = String::from_utf8_lossy(&message_manager.inner().write_buffer).to_string();
+        assert!(written.contains("\"type\":\"QUEUE_PREV\""));
+        assert!(written.contains("\"type\":\"QUEUE_UPDATE\""));
+        assert!(written.contains("\"jump\":-1"));
+    }
+
+    #[test]
+    fn test_queue_reorder_to_the_end() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&media_status(
+            1,
+            serde_json::json!([{
+                "mediaSessionId": 1,
+                "playerState": "PLAYING",
+                "currentItemId": 2,
+                "items": [
+                    { "itemId": 2, "media": { "contentId": "b", "contentType": "audio/mpeg" } },
+                    { "itemId": 1, "media": { "contentId": "a", "contentType": "audio/mpeg" } }
+                ]
+            }]),
+        ));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::clone(&message_manager));
+
+        let status = channel.queue_reorder("web-1", 1, &[1], None).unwrap();
+
+        let item_ids = status.entries[0]
+            .items
+            .iter()
+            .map(|item| item.item_id)
+            .collect::<Vec<_>>();
+        assert_eq!(item_ids, vec![Some(2), Some(1)]);
+
+        let written = String::from_utf8_lossy(&message_manager.inner().write_buffer).to_string();
+        assert!(written.contains("\"type\":\"QUEUE_REORDER\""));
+        assert!(written.contains("\"itemIds\":[1]"));
+        assert!(!written.contains("insertBefore"));
+    }
+
+    #[test]
+    fn test_queue_get_items_returns_existing_subset() {
+        let mut stream = MockTcpStream::new();
+        // Status with the same request id must not be mistaken for the reply.
+        stream.add_message(&media_status(1, serde_json::json!([])));
+        stream.add_message(&CastMessage {
+            namespace: namespaces::MEDIA.to_string(),
+            source: "web-1".to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String(
+                serde_json::json!({
+                    "type": "QUEUE_ITEMS",
+                    "requestId": 1,
+                    "items": [
+                        { "itemId": 3, "media": { "contentId": "c", "contentType": "audio/mpeg" } }
+                    ]
+                })
+                .to_string(),
+            ),
+        });
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::new(MessageManager::new(stream)));
+
+        let items = channel.queue_get_items("web-1", 1, &[3, 4]).unwrap();
+
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].item_id, Some(3));
+        assert_eq!(items[0].media.content_id.as_deref(), Some("c"));
+    }
+
+    #[test]
+    fn test_parse_color() {
+        assert_eq!(
+            Color::from_str("#FF000080").unwrap(),
+            Color::new(0xFF, 0, 0, 0x80)
+        );
+        assert_eq!(
+            Color::from_str("#00ff00").unwrap(),
+            Color::new(0, 0xFF, 0, 0xFF)
+        );
+        assert_eq!(Color::new(1, 2, 3, 4).to_string(), "#01020304");
+
+        for color in [
+            "FF000080",
+            "#FF0000F",
+            "#FF00008000",
+            "#GG000080",
+            "#+F000080",
+        ] {
+            assert!(matches!(Color::from_str(color), Err(Error::Parsing(_))));
+        }
+    }
+
+    #[test]
+    fn test_text_track_style_round_trip() {
+        let style = TextTrackStyle {
+            foreground_color: Some(Color::new(0xFF, 0xFF, 0xFF, 0xFF)),
+            background_color: Some(Color::new(0, 0, 0, 0x80)),
+            edge_type: Some(TextTrackEdgeType::DropShadow),
+            edge_color: None,
+            font_scale: Some(1.5),
+            font_family: Some("Droid Sans".to_string()),
+            font_style: Some(TextTrackFontStyle::BoldItalic),
+        };
+
+        let encoded = serde_json::to_value(style.encode()).unwrap();
+        assert_eq!(
+            encoded,
+            serde_json::json!({
+                "foregroundColor": "#FFFFFFFF",
+                "backgroundColor": "#00000080",
+                "edgeType": "DROP_SHADOW",
+                "fontScale": 1.5,
+                "fontFamily": "Droid Sans",
+                "fontStyle": "BOLD_ITALIC"
+            })
+        );
+
+        let decoded = TextTrackStyle::decode(&serde_json::from_value(encoded).unwrap()).unwrap();
+        assert_eq!(decoded, style);
+
+        let malformed = serde_json::from_value(serde_json::json!({ "edgeColor": "red" })).unwrap();
+        assert!(matches!(
+            TextTrackStyle::decode(&malformed),
+            Err(Error::Parsing(_))
+        ));
+    }
+
+    #[test]
+    fn test_best_image() {
+        let image = |url: &str, width: Option<u32>| Image {
+            url: url.to_string(),
+            width,
+            height: width,
+        };
+        let metadata = |images: Vec<Image>| {
+            Metadata::Generic(GenericMediaMetadata {
+                title: None,
+                subtitle: None,
+                images,
+                release_date: None,
+            })
+        };
+        let best_url = |metadata: &Metadata, target_width: u32| {
+            metadata
+                .best_image(target_width)
+                .map(|image| image.url.clone())
+        };
+
+        let sized = metadata(vec![
+            image("unknown", None),
+            image("large", Some(1024)),
+            image("small", Some(128)),
+            image("medium", Some(512)),
+        ]);
+        assert_eq!(best_url(&sized, 100).as_deref(), Some("small"));
+        assert_eq!(best_url(&sized, 300).as_deref(), Some("medium"));
+        assert_eq!(best_url(&sized, 512).as_deref(), Some("medium"));
+        assert_eq!(best_url(&sized, 2048).as_deref(), Some("large"));
+
+        let without_sizes = metadata(vec![image("first", None), image("second", None)]);
+        assert_eq!(best_url(&without_sizes, 300).as_deref(), Some("first"));
+
+        // Receiver may report only one of the dimensions, width alone is enough for ranking.
+        let width_only = metadata(vec![
+            image("unknown", None),
+            Image::decode(&proxies::media::Image {
+                url: "wide".to_string(),
+                width: Some(800),
+                height: None,
+            }),
+        ]);
+        assert_eq!(best_url(&width_only, 300).as_deref(), Some("wide"));
+
+        assert!(metadata(vec![]).best_image(300).is_none());
+    }
+
+    #[test]
+    fn test_decode_media_metadata() {
+        let media: proxies::media::Media = serde_json::from_value(serde_json::json!({
+            "contentId": "http://example.com/song.mp3",
+            "streamType": "BUFFERED",
+            "contentType": "audio/mpeg",
+            "metadata": {
+                "metadataType": 3,
+                "albumName": "Album",
+                "artist": "Artist",
+                "trackNumber": 5,
+                "images": [{ "url": "http://example.com/cover.jpg", "width": 64, "height": 64 }]
+            }
+        }))
+        .unwrap();
+
+        match Media::decode(&media).metadata {
+            Some(Metadata::MusicTrack(ref metadata)) => {
+                assert_eq!(metadata.album_name.as_deref(), Some("Album"));
+                assert_eq!(metadata.artist.as_deref(), Some("Artist"));
+                assert_eq!(metadata.track_number, Some(5));
+                assert_eq!(metadata.images[0].width, Some(64));
+                assert_eq!(metadata.images[0].height, Some(64));
+            }
+            metadata => panic!("expected music track metadata, but got {:?}", metadata),
+        }
+
+        let metadata: proxies::media::Metadata = serde_json::from_value(serde_json::json!({
+            "metadataType": 42,
+            "title": "Title",
+            "customField": [1, 2]
+        }))
+        .unwrap();
+
+        match Metadata::decode(&metadata) {
+            Metadata::Unknown(raw) => {
+                assert_eq!(raw["metadataType"], 42);
+                assert_eq!(raw["title"], "Title");
+                assert_eq!(raw["customField"], serde_json::json!([1, 2]));
+            }
+            metadata => panic!("expected unknown metadata, but got {:?}", metadata),
+        }
+    }
+
+    #[test]
+    fn test_parse_live_media_status() {
+        let message = media_status(
+            0,
+            serde_json::json!([{
+                "mediaSessionId": 1,
+                "playerState": "PLAYING",
+                "media": {
+                    "contentId": "http://example.com/live.m3u8",
+                    "streamType": "LIVE",
+                    "contentType": "application/vnd.apple.mpegurl"
+                },
+                "liveSeekableRange": { "start": 10.0, "isMovingWindow": true }
+            }]),
+        );
+        let channel = MediaChannel::new(
+            DEFAULT_SENDER_ID,
+            Lrc::new(MessageManager::new(MockTcpStream::new())),
+        );
+
+        match channel.parse(&message).unwrap() {
+            MediaResponse::Status(status) => {
+                let entry = &status.entries[0];
+                let range = entry.live_seekable_range.unwrap();
+
+                assert!(entry.is_live());
+                assert_eq!(range.start, Some(10.0));
+                assert_eq!(range.end, None);
+                assert!(range.is_moving_window);
+                assert!(!range.is_live_done);
+                assert!(!range.contains(5.0));
+                assert!(range.contains(1000.0));
+            }
+            response => panic!("expected Status, but got {:?}", response),
+        }
+    }
+
+    #[test]
+    fn test_parse_idle_media_status() {
+        let message = media_status(
+            0,
+            serde_json::json!([{
+                "mediaSessionId": 1,
+                "playerState": "IDLE",
+                "idleReason": "FINISHED",
+                "volume": { "level": 0.5, "muted": false }
+            }]),
+        );
+        let channel = MediaChannel::new(
+            DEFAULT_SENDER_ID,
+            Lrc::new(MessageManager::new(MockTcpStream::new())),
+        );
+
+        let response = channel.parse(&message).unwrap();
+
+        if let MediaResponse::Status(status) = response {
+            let entry = &status.entries[0];
+
+            assert_eq!(entry.player_state, PlayerState::Idle);
+            assert_eq!(entry.idle_reason, Some(IdleReason::Finished));
+            assert_eq!(entry.playback_rate, 1.0);
+            assert_eq!(entry.volume.and_then(|volume| volume.level), Some(0.5));
+        } else {
+            panic!("expected MediaResponse::Status, but got {:?}", response);
+        }
+    }
+
+    #[test]
+    fn test_set_playback_rate_reports_applied_rate() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&media_status(
+            1,
+            serde_json::json!([{
+                "mediaSessionId": 1,
+                "playerState": "PLAYING",
+                "playbackRate": 2
+            }]),
+        ));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::new(MessageManager::new(stream)));
+
+        let entry = channel.set_playback_rate("web-1", 1, 3.0).unwrap();
+
+        assert_eq!(entry.playback_rate, 2.0);
+
+        let written = channel.message_manager.inner().write_buffer.clone();
+        let written = String::from_utf8_lossy(&written);
+        assert!(written.contains("\"type\":\"SET_PLAYBACK_RATE\""));
+        assert!(written.contains("\"playbackRate\":3.0"));
+    }
+
+    #[test]
+    fn test_set_stream_volume() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&media_status(
+            1,
+            serde_json::json!([{
+                "mediaSessionId": 1,
+                "playerState": "PLAYING",
+                "supportedMediaCommands": 4,
+                "volume": { "level": 0.25, "muted": false }
+            }]),
+        ));
+        stream.add_message(&media_status(
+            2,
+            serde_json::json!([{
+                "mediaSessionId": 1,
+                "playerState": "PLAYING",
+                "supportedMediaCommands": 4,
+                "volume": { "level": 0.25, "muted": false }
+            }]),
+        ));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::new(MessageManager::new(stream)));
+
+        let entry = channel.set_stream_volume("web-1", 1, 0.25).unwrap();
+
+        assert_eq!(entry.volume.and_then(|volume| volume.level), Some(0.25));
+
+        let written = channel.message_manager.inner().write_buffer.clone();
+        let written = String::from_utf8_lossy(&written);
+        assert!(written.contains("\"type\":\"SET_VOLUME\""));
+        assert!(written.contains("\"volume\":{\"level\":0.25}"));
+    }
+
+    #[test]
+    fn test_set_stream_mute_unsupported() {
+        let mut stream = MockTcpStream::new();
+        // Only STREAM_VOLUME is supported.
+        stream.add_message(&media_status(
+            1,
+            serde_json::json!([{
+                "mediaSessionId": 1,
+                "playerState": "PLAYING",
+                "supportedMediaCommands": 4,
+                "volume": { "level": 0.25, "muted": false }
+            }]),
+        ));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::new(MessageManager::new(stream)));
+
+        let result = channel.set_stream_mute("web-1", 1, true);
+
+        assert!(matches!(result, Err(Error::UnsupportedOperation(_))));
+    }
+
+    #[test]
+    fn test_wait_for_state() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&media_status(
+            0,
+            serde_json::json!([{ "mediaSessionId": 1, "playerState": "LOADING" }]),
+        ));
+        stream.add_message(&media_status(
+            0,
+            serde_json::json!([{ "mediaSessionId": 1, "playerState": "BUFFERING" }]),
+        ));
+        stream.add_message(&media_status(
+            0,
+            serde_json::json!([{ "mediaSessionId": 1, "playerState": "PLAYING" }]),
+        ));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::new(MessageManager::new(stream)));
+
+        let entry = channel
+            .wait_for_state(1, PlayerState::Playing, Duration::from_secs(5))
+            .unwrap();
+
+        assert_eq!(entry.player_state, PlayerState::Playing);
+    }
+
+    #[test]
+    fn test_wait_for_state_idle_error() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&media_status(
+            0,
+            serde_json::json!([{ "mediaSessionId": 1, "playerState": "BUFFERING" }]),
+        ));
+        stream.add_message(&media_status(
+            0,
+            serde_json::json!([{
+                "mediaSessionId": 1,
+                "playerState": "IDLE",
+                "idleReason": "ERROR"
+            }]),
+        ));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::new(MessageManager::new(stream)));
+
+        match channel.wait_for_state(1, PlayerState::Playing, Duration::from_secs(5)) {
+            Err(Error::CastError {
+                type_, request_id, ..
+            }) => {
+                assert_eq!(type_, "MEDIA_STATUS");
+                assert_eq!(request_id, None);
+            }
+            result => panic!("expected Error::CastError, but got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_wait_for_state_timeout() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&media_status(
+            0,
+            serde_json::json!([{ "mediaSessionId": 1, "playerState": "BUFFERING" }]),
+        ));
+        stream.timeout_when_empty = true;
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::new(MessageManager::new(stream)));
+
+        let result = channel.wait_for_state(1, PlayerState::Playing, Duration::ZERO);
+
+        assert!(matches!(result, Err(Error::Timeout(_))));
+    }
+
+    #[test]
+    fn test_wait_for_state_cancelled() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&media_status(
+            0,
+            serde_json::json!([{ "mediaSessionId": 1, "playerState": "BUFFERING" }]),
+        ));
+        stream.timeout_when_empty = true;
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::clone(&message_manager));
+
+        let cancel = StopHandle::new();
+        cancel.stop();
+
+        let result = channel.wait_for_state_cancellable(
+            1,
+            PlayerState::Playing,
+            Duration::from_secs(60),
+            &cancel,
+        );
+
+        assert!(matches!(result, Err(Error::Cancelled)));
+        assert_eq!(
+            message_manager.receive().unwrap().namespace,
+            namespaces::MEDIA
+        );
+    }
+
+    #[test]
+    fn test_status_updates() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&media_status(
+            0,
+            serde_json::json!([{ "mediaSessionId": 1, "playerState": "BUFFERING" }]),
+        ));
+        stream.add_message(&CastMessage {
+            namespace: namespaces::HEARTBEAT.to_string(),
+            source: DEFAULT_RECEIVER_ID.to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String(r#"{"type":"PING"}"#.to_string()),
+        });
+        stream.add_message(&media_status(
+            0,
+            serde_json::json!([{ "mediaSessionId": 1, "playerState": "PLAYING" }]),
+        ));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::new(MessageManager::new(stream)));
+
+        let states = channel
+            .status_updates()
+            .map(|status| status.unwrap().entries[0].player_state)
+            .collect::<Vec<_>>();
+
+        assert_eq!(states, vec![PlayerState::Buffering, PlayerState::Playing]);
+    }
+
+    #[test]
+    fn test_stop_waits_for_idle() {
+        let mut stream = MockTcpStream::new();
+        // Reply to the STOP request that still reports media as playing.
+        stream.add_message(&media_status(
+            1,
+            serde_json::json!([{ "mediaSessionId": 1, "playerState": "PLAYING" }]),
+        ));
+        stream.add_message(&media_status(
+            0,
+            serde_json::json!([{
+                "mediaSessionId": 1,
+                "playerState": "IDLE",
+                "idleReason": "CANCELLED"
+            }]),
+        ));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::clone(&message_manager));
+
+        let entry = channel.stop("web-1", 1).unwrap().unwrap();
+
+        assert_eq!(entry.player_state, PlayerState::Idle);
+        assert_eq!(entry.idle_reason, Some(IdleReason::Cancelled));
+
+        let written = message_manager.inner().write_buffer.clone();
+        assert!(String::from_utf8_lossy(&written).contains("\"type\":\"STOP\""));
+    }
+
+    #[test]
+    fn test_stop_accepts_dropped_session() {
+        let mut stream = MockTcpStream::new();
+        // Unrelated broadcast that doesn't list the session shouldn't end the wait.
+        stream.add_message(&media_status(0, serde_json::json!([])));
+        stream.add_message(&media_status(1, serde_json::json!([])));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::new(MessageManager::new(stream)));
+
+        assert!(channel.stop("web-1", 1).unwrap().is_none());
+
+        let mut stream = MockTcpStream::new();
+        stream.timeout_when_empty = true;
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::new(MessageManager::new(stream)));
+
+        assert!(matches!(
+            channel.stop_with_timeout("web-1", 1, Duration::ZERO),
+            Err(Error::Timeout(_))
+        ));
+    }
+
+    #[test]
+    fn test_poll_status_stops_when_session_ends() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&media_status(
+            1,
+            serde_json::json!([{ "mediaSessionId": 1, "playerState": "PLAYING" }]),
+        ));
+        stream.add_message(&media_status(
+            2,
+            serde_json::json!([{ "mediaSessionId": 1, "playerState": "BUFFERING" }]),
+        ));
+        stream.add_message(&media_status(3, serde_json::json!([])));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::clone(&message_manager));
+
+        let mut polled = vec![];
+        channel
+            .poll_status("web-1", 1, Duration::ZERO, &StopHandle::new(), |status| {
+                polled.push(status.entries.len());
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(polled, vec![1, 1, 0]);
+
+        let written = message_manager.inner().write_buffer.clone();
+        assert_eq!(
+            String::from_utf8_lossy(&written)
+                .matches("\"type\":\"GET_STATUS\"")
+                .count(),
+            3
+        );
+    }
+
+    #[test]
+    fn test_toggle_playback() {
+        for (state, command, new_state) in [
+            ("PLAYING", "PAUSE", PlayerState::Paused),
+            ("BUFFERING", "PAUSE", PlayerState::Paused),
+            ("PAUSED", "PLAY", PlayerState::Playing),
+            ("IDLE", "PLAY", PlayerState::Playing),
+        ] {
+            let mut stream = MockTcpStream::new();
+            stream.add_message(&media_status(
+                1,
+                serde_json::json!([{ "mediaSessionId": 1, "playerState": state }]),
+            ));
+            let new_state_name = if command == "PAUSE" {
+                "PAUSED"
+            } else {
+                "PLAYING"
+            };
+            stream.add_message(&media_status(
+                2,
+                serde_json::json!([{ "mediaSessionId": 1, "playerState": new_state_name }]),
+            ));
+            let message_manager = Lrc::new(MessageManager::new(stream));
+            let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::clone(&message_manager));
+
+            let entry = channel.toggle_playback("web-1", 1).unwrap();
+            assert_eq!(entry.player_state, new_state);
+
+            let written =
+                String::from_utf8_lossy(&message_manager.inner().write_buffer).to_string();
+            assert!(written.contains(&format!("\"type\":\"{}\"", command)));
+        }
+
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&media_status(1, serde_json::json!([])));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::new(MessageManager::new(stream)));
 
-        self.receive_status_entry(request_id, media_session_id)
+        match channel.toggle_playback("web-1", 1) {
+            Err(Error::CastError {
+                type_, request_id, ..
+            }) => {
+                assert_eq!(type_, MESSAGE_TYPE_INVALID_PLAYER_STATE);
+                assert_eq!(request_id, None);
+            }
+            result => panic!("expected Error::CastError, but got {:?}", result),
+        }
     }
 
-    /// Stops playback of the current content. Triggers a STATUS event notification to all sender
-    /// applications. After this command the content will no longer be loaded and the
-    /// media_session_id is invalidated.
-    ///
-    /// # Arguments
-    ///
-    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
-    /// * `media_session_id` - ID of the media session to be stopped.
-    ///
-    /// # Return value
-    ///
-    /// Returned `Result` should consist of either `Status` instance or an `Error`.
-    pub fn stop<S>(&self, destination: S, media_session_id: i32) -> Result<StatusEntry, Error>
-    where
-        S: Into<Cow<'a, str>>,
-    {
-        let request_id = self.message_manager.generate_request_id().get();
+    #[test]
+    fn test_queue_load_start_index_out_of_bounds() {
+        let message_manager = Lrc::new(MessageManager::new(MockTcpStream::new()));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::clone(&message_manager));
 
-        let payload = serde_json::to_string(&proxies::media::PlaybackGenericRequest {
-            request_id,
-            media_session_id,
-            typ: MESSAGE_TYPE_STOP.to_string(),
-            custom_data: proxies::media::CustomData::new(),
-        })?;
+        let media = Media {
+            content_id: Some("http://example.com/video.mp4".to_string()),
+            content_url: None,
+            content_type: "video/mp4".to_string(),
+            stream_type: StreamType::Buffered,
+            duration: None,
+            metadata: None,
+            tracks: vec![],
+            text_track_style: None,
+            hls_segment_format: None,
+            hls_video_segment_format: None,
+            breaks: vec![],
+            break_clips: vec![],
+        };
 
-        self.message_manager.send(CastMessage {
-            namespace: CHANNEL_NAMESPACE.to_string(),
-            source: self.sender.to_string(),
-            destination: destination.into().to_string(),
-            payload: CastMessagePayload::String(payload),
-        })?;
+        let result = channel.queue_load(
+            "web-1",
+            "session-id",
+            &[QueueItem::new(media)],
+            1,
+            RepeatMode::Off,
+        );
 
-        self.receive_status_entry(request_id, media_session_id)
+        assert!(matches!(result, Err(Error::InvalidRequest(_))));
+        assert!(message_manager.inner().write_buffer.is_empty());
     }
 
-    /// Sets the current position in the stream. Triggers a STATUS event notification to all sender
-    /// applications. If the position provided is outside the range of valid positions for the
-    /// current content, then the player should pick a valid position as close to the requested
-    /// position as possible.
-    ///
-    /// # Arguments
-    ///
-    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
-    /// * `media_session_id` - ID of the media session to seek in;
-    /// * `current_time` - Time in seconds to seek to.
-    ///
-    /// # Return value
-    ///
-    /// Returned `Result` should consist of either `Status` instance or an `Error`.
-    pub fn seek<S>(
-        &self,
-        destination: S,
-        media_session_id: i32,
-        current_time: Option<f32>,
-        resume_state: Option<ResumeState>,
-    ) -> Result<StatusEntry, Error>
-    where
-        S: Into<Cow<'a, str>>,
-    {
-        let request_id = self.message_manager.generate_request_id().get();
+    #[test]
+    fn test_load_with_start_time_and_active_tracks() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&media_status(1, serde_json::json!([])));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::clone(&message_manager));
 
-        let payload = serde_json::to_string(&proxies::media::PlaybackSeekRequest {
-            request_id,
-            media_session_id,
-            typ: MESSAGE_TYPE_SEEK.to_string(),
-            current_time,
-            resume_state: resume_state.map(|s| s.to_string()),
-            custom_data: proxies::media::CustomData::new(),
-        })?;
+        let media = Media {
+            content_id: Some("http://example.com/video.mp4".to_string()),
+            content_url: None,
+            content_type: "video/mp4".to_string(),
+            stream_type: StreamType::Buffered,
+            duration: None,
+            metadata: None,
+            tracks: vec![Track {
+                id: 2,
+                track_type: TrackType::Text,
+                name: Some("Français".to_string()),
+                language: Some("fr".to_string()),
+                track_content_id: Some("http://example.com/fr.vtt".to_string()),
+                track_content_type: Some("text/vtt".to_string()),
+                subtype: Some(TextTrackType::Subtitles),
+            }],
+            text_track_style: None,
+            hls_segment_format: None,
+            hls_video_segment_format: None,
+            breaks: vec![],
+            break_clips: vec![],
+        };
 
-        self.message_manager.send(CastMessage {
-            namespace: CHANNEL_NAMESPACE.to_string(),
-            source: self.sender.to_string(),
-            destination: destination.into().to_string(),
-            payload: CastMessagePayload::String(payload),
-        })?;
+        channel
+            .load_with_options(
+                "web-1",
+                "session-id",
+                &media,
+                LoadOptions {
+                    current_time: Some(90.0),
+                    active_track_ids: Some(vec![2]),
+                    ..LoadOptions::default()
+                },
+            )
+            .unwrap();
+
+        // Decode the frame that has been sent to check the exact payload.
+        let mut sent = MockTcpStream::new();
+        sent.read_buffer = message_manager.inner().write_buffer.clone();
+        let payload = MessageManager::new(sent)
+            .receive()
+            .unwrap()
+            .payload
+            .to_json()
+            .unwrap();
 
-        self.receive_status_entry(request_id, media_session_id)
+        assert_eq!(
+            payload,
+            serde_json::json!({
+                "requestId": 1,
+                "sessionId": "session-id",
+                "type": "LOAD",
+                "media": {
+                    "contentId": "http://example.com/video.mp4",
+                    "streamType": "BUFFERED",
+                    "contentType": "video/mp4",
+                    "tracks": [{
+                        "trackId": 2,
+                        "type": "TEXT",
+                        "name": "Français",
+                        "language": "fr",
+                        "trackContentId": "http://example.com/fr.vtt",
+                        "trackContentType": "text/vtt",
+                        "subtype": "SUBTITLES"
+                    }]
+                },
+                "currentTime": 90.0,
+                "activeTrackIds": [2],
+                "customData": {},
+                "autoplay": true
+            })
+        );
     }
 
-    /// Modifies the text tracks style or change the tracks status. If a trackId does not match
-    /// the existing trackIds the whole request will fail and no status will change.
-    ///
-    /// # Arguments
-    ///
-    /// * `destination` - `protocol` of the media application (e.g. `web-1`);
-    /// * `media_session_id` - ID of the media session to modify tracks for;
-    /// * `track_selection` - Track selection data.
-    ///
-    /// # Return value
-    ///
-    /// Returned `Result` should consist of either `Status` instance or an `Error`.
-    pub fn edit_tracks<S>(
-        &self,
-        destination: S,
-        media_session_id: i32,
-        track_selection: TrackSelection,
-    ) -> Result<StatusEntry, Error>
-    where
-        S: Into<Cow<'a, str>>,
-    {
-        let request_id = self.message_manager.generate_request_id().get();
+    #[test]
+    fn test_load_with_credentials() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&media_status(1, serde_json::json!([])));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::clone(&message_manager));
 
-        let payload = serde_json::to_string(&proxies::media::EditTracksInfoRequest {
-            request_id,
-            media_session_id,
-            typ: MESSAGE_TYPE_EDIT_TRACKS_INFO.to_string(),
-            active_track_ids: track_selection.active_track_ids,
-            enable_text_tracks: track_selection.enable_text_tracks,
-            language: track_selection.language,
-        })?;
+        let media = Media {
+            content_id: Some("http://example.com/video.mp4".to_string()),
+            content_url: None,
+            content_type: "video/mp4".to_string(),
+            stream_type: StreamType::Buffered,
+            duration: None,
+            metadata: None,
+            tracks: vec![],
+            text_track_style: None,
+            hls_segment_format: None,
+            hls_video_segment_format: None,
+            breaks: vec![],
+            break_clips: vec![],
+        };
 
-        self.message_manager.send(CastMessage {
-            namespace: CHANNEL_NAMESPACE.to_string(),
-            source: self.sender.to_string(),
-            destination: destination.into().to_string(),
-            payload: CastMessagePayload::String(payload),
-        })?;
+        channel
+            .load_with_options(
+                "web-1",
+                "session-id",
+                &media,
+                LoadOptions {
+                    credentials: Some("token".to_string()),
+                    credentials_type: Some("cloud".to_string()),
+                    ..LoadOptions::default()
+                },
+            )
+            .unwrap();
 
-        self.receive_status_entry(request_id, media_session_id)
+        let written = String::from_utf8_lossy(&message_manager.inner().write_buffer).to_string();
+        assert!(written.contains("\"credentials\":\"token\""));
+        assert!(written.contains("\"credentialsType\":\"cloud\""));
     }
 
-    pub fn can_handle(&self, message: &CastMessage) -> bool {
-        message.namespace == CHANNEL_NAMESPACE
-    }
+    #[test]
+    fn test_load_looping() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&media_status(1, serde_json::json!([])));
+        stream.add_message(&media_status(2, serde_json::json!([])));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::clone(&message_manager));
 
-    pub fn parse(&self, message: &CastMessage) -> Result<MediaResponse, Error> {
-        let reply = match message.payload {
-            CastMessagePayload::String(ref payload) => {
-                serde_json::from_str::<serde_json::Value>(payload)?
-            }
-            _ => {
-                return Err(Error::Internal(
-                    "Binary payload is not supported!".to_string(),
-                ));
-            }
+        let media = Media {
+            content_id: Some("http://example.com/track.mp3".to_string()),
+            content_url: None,
+            content_type: "audio/mpeg".to_string(),
+            stream_type: StreamType::Buffered,
+            duration: None,
+            metadata: None,
+            tracks: vec![],
+            text_track_style: None,
+            hls_segment_format: None,
+            hls_video_segment_format: None,
+            breaks: vec![],
+            break_clips: vec![],
         };
 
-        let message_type = reply
-            .as_object()
-            .and_then(|object| object.get("type"))
-            .and_then(|property| property.as_str())
-            .unwrap_or("")
-            .to_string();
+        channel.load("web-1", "session-id", &media).unwrap();
+        let written = String::from_utf8_lossy(&message_manager.inner().write_buffer).to_string();
+        assert!(!written.contains("queueData"));
 
-        let response = match message_type.as_ref() {
-            MESSAGE_TYPE_MEDIA_STATUS => {
-                let reply: proxies::media::StatusReply = serde_json::value::from_value(reply)?;
+        message_manager.inner().write_buffer.clear();
 
-                let statuses_entries = reply.status.iter().map(|x| {
-                    StatusEntry {
-                        active_track_ids: x.active_track_ids.clone(),
-                        media_session_id: x.media_session_id,
-                        media: x.media.as_ref().map(|m| {
-                            Media {
-                                content_id: m.content_id.to_string(),
-                                stream_type: StreamType::from_str(m.stream_type.as_ref()).unwrap(),
-                                content_type: m.content_type.to_string(),
-                                metadata: None, // TODO
-                                tracks: m.tracks.iter().map(|t| {
-                                    Track {
-                                        id: t.id,
-                                        track_type: TrackType::from_str(t.typ.as_ref()).unwrap(),
-                                        name: t.name.clone(),
-                                        language: t.language.clone(),
-                                    }
-                                }).collect(),
-                                duration: m.duration,
-                            }
-                        }),
-                        live_seekable_range: x.live_seekable_range.clone(),
-                        playback_rate: x.playback_rate,
-                        player_state: PlayerState::from_str(x.player_state.as_ref()).unwrap(),
-                        idle_reason: x
-                            .idle_reason
-                            .as_ref()
-                            .map(|reason| IdleReason::from_str(reason).unwrap()),
-                        current_time: x.current_time,
-                        supported_media_commands: x.supported_media_commands,
-                    }
-                });
+        channel
+            .load_looping("web-1", "session-id", &media, RepeatMode::Single)
+            .unwrap();
+        let written = String::from_utf8_lossy(&message_manager.inner().write_buffer).to_string();
+        assert!(written.contains("\"queueData\":{\"repeatMode\":\"REPEAT_SINGLE\"}"));
+    }
 
-                MediaResponse::Status(Status {
-                    request_id: reply.request_id,
-                    entries: statuses_entries.collect::<Vec<StatusEntry>>(),
-                })
-            }
-            MESSAGE_TYPE_LOAD_CANCELLED => {
-                let reply: proxies::media::LoadCancelledReply =
-                    serde_json::value::from_value(reply)?;
+    #[test]
+    fn test_load_with_content_url() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&media_status(1, serde_json::json!([])));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::clone(&message_manager));
 
-                MediaResponse::LoadCancelled(LoadCancelled {
-                    request_id: reply.request_id,
-                })
-            }
-            MESSAGE_TYPE_LOAD_FAILED => {
-                let reply: proxies::media::LoadFailedReply = serde_json::value::from_value(reply)?;
+        let mut media = Media {
+            content_id: None,
+            content_url: None,
+            content_type: "video/mp4".to_string(),
+            stream_type: StreamType::Buffered,
+            duration: None,
+            metadata: None,
+            tracks: vec![],
+            text_track_style: None,
+            hls_segment_format: None,
+            hls_video_segment_format: None,
+            breaks: vec![],
+            break_clips: vec![],
+        };
 
-                MediaResponse::LoadFailed(LoadFailed {
-                    request_id: reply.request_id,
-                })
-            }
-            MESSAGE_TYPE_INVALID_PLAYER_STATE => {
-                let reply: proxies::media::InvalidPlayerStateReply =
-                    serde_json::value::from_value(reply)?;
+        match channel.load("web-1", "session-id", &media) {
+            Err(Error::InvalidRequest(_)) => {}
+            result => panic!("expected Error::InvalidRequest, but got {:?}", result),
+        }
+        assert!(message_manager.inner().write_buffer.is_empty());
 
-                MediaResponse::InvalidPlayerState(InvalidPlayerState {
-                    request_id: reply.request_id,
-                })
-            }
-            MESSAGE_TYPE_INVALID_REQUEST => {
-                let reply: proxies::media::InvalidRequestReply =
-                    serde_json::value::from_value(reply)?;
+        media.content_url = Some("http://example.com/video.mp4".to_string());
+        channel.load("web-1", "session-id", &media).unwrap();
 
-                MediaResponse::InvalidRequest(InvalidRequest {
-                    request_id: reply.request_id,
-                    reason: reply.reason,
-                })
-            }
-            MESSAGE_TYPE_ERROR => {
-                let reply: proxies::media::MediaErrorReply = serde_json::value::from_value(reply)?;
-                let detailed_error_code =
-                    MediaDetailedErrorCode::try_from(reply.detailed_error_code)?;
-
-                MediaResponse::Error(MediaError {
-                    detailed_error_code,
-                    message_type: reply.message_type,
-                })
-            }
-            _ => MediaResponse::NotImplemented(message_type.to_string(), reply),
+        let written = String::from_utf8_lossy(&message_manager.inner().write_buffer).to_string();
+        assert!(written.contains("\"contentUrl\":\"http://example.com/video.mp4\""));
+        assert!(!written.contains("contentId"));
+    }
+
+    #[test]
+    fn test_load_validation() {
+        let message_manager = Lrc::new(MessageManager::new(MockTcpStream::new()));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::clone(&message_manager));
+
+        let media = Media {
+            content_id: Some("catalog-42".to_string()),
+            content_url: Some("example.com/video.mp4".to_string()),
+            content_type: "video/mp4".to_string(),
+            stream_type: StreamType::Buffered,
+            duration: None,
+            metadata: None,
+            tracks: vec![],
+            text_track_style: None,
+            hls_segment_format: None,
+            hls_video_segment_format: None,
+            breaks: vec![],
+            break_clips: vec![],
         };
 
-        Ok(response)
+        for invalid in [
+            Media {
+                content_url: Some(String::new()),
+                ..media.clone()
+            },
+            media.clone(),
+            Media {
+                content_url: Some("1http://example.com/video.mp4".to_string()),
+                ..media.clone()
+            },
+            Media {
+                content_url: None,
+                content_type: "mp4".to_string(),
+                ..media.clone()
+            },
+            Media {
+                content_url: None,
+                content_type: "video/".to_string(),
+                ..media.clone()
+            },
+        ] {
+            match channel.load("web-1", "session-id", &invalid) {
+                Err(Error::InvalidRequest(_)) => {}
+                result => panic!("expected Error::InvalidRequest, but got {:?}", result),
+            }
+        }
+        assert!(message_manager.inner().write_buffer.is_empty());
+
+        for valid in [
+            "http://example.com/video.mp4",
+            "custom-scheme+x.1:catalog/42",
+            "data:video/mp4;base64,AAAA",
+        ] {
+            assert!(has_url_scheme(valid), "{} should be accepted", valid);
+        }
+        assert!(Media {
+            content_url: None,
+            content_type: String::new(),
+            ..media
+        }
+        .validate()
+        .is_ok());
     }
 
-    /// Waits for the status entry with specified `request_id` and `media_session_id`. This method
-    /// is very handy for the media playback methods where particular `StatusEntry` is required.
-    ///
-    /// # Arguments
-    ///
-    /// * `request_id` - ID of the request that caused status entry to be broadcasted.
-    /// * `media_session_id` - ID of the media session to receive.
-    ///
-    /// # Return value
-    ///
-    /// Returned `Result` should consist of either `Status` instance or an `Error`.
-    fn receive_status_entry(
-        &self,
-        request_id: u32,
-        media_session_id: i32,
-    ) -> Result<StatusEntry, Error> {
-        self.message_manager.receive_find_map(|message| {
-            if !self.can_handle(message) {
-                return Ok(None);
-            }
+    #[test]
+    fn test_pending_request_fails_when_app_stops() {
+        let app_stopped_messages = [
+            CastMessage {
+                namespace: namespaces::RECEIVER.to_string(),
+                source: DEFAULT_RECEIVER_ID.to_string(),
+                destination: DEFAULT_SENDER_ID.to_string(),
+                payload: CastMessagePayload::String(
+                    serde_json::json!({
+                        "type": "RECEIVER_STATUS",
+                        "requestId": 0,
+                        "status": { "applications": [], "volume": { "level": 0.5 } }
+                    })
+                    .to_string(),
+                ),
+            },
+            CastMessage {
+                namespace: namespaces::CONNECTION.to_string(),
+                source: "web-1".to_string(),
+                destination: DEFAULT_SENDER_ID.to_string(),
+                payload: CastMessagePayload::String("{\"type\":\"CLOSE\"}".to_string()),
+            },
+        ];
 
-            match self.parse(message)? {
-                MediaResponse::Status(mut status) => {
-                    if status.request_id == request_id {
-                        let position = status
-                            .entries
-                            .iter()
-                            .position(|e| e.media_session_id == media_session_id);
+        for app_stopped_message in app_stopped_messages {
+            let mut stream = MockTcpStream::new();
+            stream.add_message(&app_stopped_message);
+            let message_manager = Lrc::new(MessageManager::new(stream));
+            let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::clone(&message_manager));
 
-                        return Ok(position.map(|position| status.entries.remove(position)));
-                    }
-                }
-                MediaResponse::InvalidPlayerState(error) => {
-                    if error.request_id == request_id {
-                        return Err(Error::Internal(
-                            "Request failed because of invalid player state.".to_string(),
-                        ));
-                    }
-                }
-                MediaResponse::InvalidRequest(error) => {
-                    if error.request_id == request_id {
-                        return Err(Error::Internal(format!(
-                            "Invalid request ({}).",
-                            error.reason.unwrap_or_else(|| "Unknown".to_string())
-                        )));
-                    }
+            match channel.pause("web-1", 1) {
+                Err(Error::CastError {
+                    type_, request_id, ..
+                }) => {
+                    assert_eq!(type_, ERROR_TYPE_APP_STOPPED);
+                    assert_eq!(request_id, Some(1));
                 }
-                _ => {}
+                result => panic!("expected Error::CastError, but got {:?}", result),
             }
 
-            Ok(None)
-        })
+            // Message that signalled the stop is still available to the application.
+            assert_eq!(
+                message_manager.receive().unwrap().namespace,
+                app_stopped_message.namespace
+            );
+        }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::{channels::tests::MockTcpStream, DEFAULT_RECEIVER_ID, DEFAULT_SENDER_ID};
+    #[test]
+    fn test_custom_namespace() {
+        const NAMESPACE: &str = "urn:x-cast:com.example.media";
 
-    use super::*;
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&CastMessage {
+            namespace: NAMESPACE.to_string(),
+            ..media_status(
+                1,
+                serde_json::json!([{ "mediaSessionId": 1, "playerState": "PAUSED" }]),
+            )
+        });
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::clone(&message_manager))
+            .with_namespace(NAMESPACE);
+
+        let entry = channel.pause("web-1", 1).unwrap();
+        assert_eq!(entry.player_state, PlayerState::Paused);
+
+        let written = message_manager.inner().write_buffer.clone();
+        assert!(String::from_utf8_lossy(&written).contains(NAMESPACE));
+        assert!(!channel.can_handle(&media_status(
+            0,
+            serde_json::json!([{ "mediaSessionId": 1, "playerState": "IDLE" }]),
+        )));
+    }
 
     #[test]
-    fn test_parse_media_error() {
-        let message = CastMessage {
-            namespace: CHANNEL_NAMESPACE.to_string(),
-            source: DEFAULT_RECEIVER_ID.to_string(),
-            destination: DEFAULT_SENDER_ID.to_string(),
-            payload: CastMessagePayload::String(
-                "{\"type\":\"ERROR\",\"detailedErrorCode\":104,\"itemId\":1}".to_string(),
-            ),
-        };
-        let channel = MediaChannel {
-            sender: Cow::from(DEFAULT_SENDER_ID),
-            message_manager: Lrc::new(MessageManager::new(MockTcpStream::new())),
+    fn test_playback_clock() {
+        let channel = MediaChannel::new(
+            DEFAULT_SENDER_ID,
+            Lrc::new(MessageManager::new(MockTcpStream::new())),
+        );
+        let entry = |player_state: &str, media: Option<serde_json::Value>| {
+            let message = media_status(
+                0,
+                serde_json::json!([{
+                    "mediaSessionId": 1,
+                    "playerState": player_state,
+                    "playbackRate": 2.0,
+                    "currentTime": 10.0,
+                    "media": media
+                }]),
+            );
+
+            match channel.parse(&message).unwrap() {
+                MediaResponse::Status(mut status) => status.entries.remove(0),
+                response => panic!("expected Status, but got {:?}", response),
+            }
         };
-        let expected_result = MediaError {
-            detailed_error_code: MediaDetailedErrorCode::MediaSrcNotSupported,
-            message_type: MESSAGE_TYPE_ERROR.to_string(),
+
+        let received_at = Instant::now();
+        let mut clock = PlaybackClock::default();
+        clock.update(
+            &entry(
+                "PLAYING",
+                Some(serde_json::json!({
+                    "contentId": "video.mp4",
+                    "streamType": "BUFFERED",
+                    "contentType": "video/mp4",
+                    "duration": 15.0
+                })),
+            ),
+            received_at,
+        );
+
+        let estimate = clock.estimated_current_time(received_at + Duration::from_secs(1));
+        assert!((estimate - 12.0).abs() < 1e-3);
+        assert_eq!(
+            clock.estimated_current_time(received_at + Duration::from_secs(10)),
+            15.0
+        );
+
+        // Duration is kept when media is not included into the status.
+        clock.update(&entry("PAUSED", None), received_at);
+        assert_eq!(
+            clock.estimated_current_time(received_at + Duration::from_secs(10)),
+            10.0
+        );
+    }
+
+    #[test]
+    fn test_parse_breaks() {
+        let message = media_status(
+            0,
+            serde_json::json!([{
+                "mediaSessionId": 1,
+                "playerState": "PLAYING",
+                "currentTime": 0.0,
+                "media": {
+                    "contentId": "video.mp4",
+                    "streamType": "BUFFERED",
+                    "contentType": "video/mp4",
+                    "breaks": [{
+                        "id": "preroll",
+                        "breakClipIds": ["ad-1"],
+                        "position": 0.0,
+                        "isWatched": false
+                    }],
+                    "breakClips": [{
+                        "id": "ad-1",
+                        "contentUrl": "http://example.com/ad.mp4",
+                        "duration": 15.0,
+                        "whenSkippable": 5.0
+                    }]
+                },
+                "breakStatus": {
+                    "currentBreakTime": 3.0,
+                    "currentBreakClipTime": 3.0,
+                    "breakId": "preroll",
+                    "breakClipId": "ad-1",
+                    "whenSkippable": 5.0
+                }
+            }]),
+        );
+        let channel = MediaChannel::new(
+            DEFAULT_SENDER_ID,
+            Lrc::new(MessageManager::new(MockTcpStream::new())),
+        );
+
+        let mut entry = match channel.parse(&message).unwrap() {
+            MediaResponse::Status(mut status) => status.entries.remove(0),
+            response => panic!("expected Status, but got {:?}", response),
         };
 
-        let response = channel.parse(&message).unwrap();
+        let media = entry.media.as_ref().unwrap();
+        assert_eq!(media.breaks[0].id, "preroll");
+        assert_eq!(media.breaks[0].break_clip_ids, vec!["ad-1".to_string()]);
+        assert_eq!(media.break_clips[0].when_skippable, Some(5.0));
+        assert!(!entry.is_skip_allowed());
 
-        if let MediaResponse::Error(result) = response {
-            assert_eq!(expected_result, result);
-        } else {
-            panic!("expected MediaResponse::Error, but got {:?}", response);
+        entry.break_status.as_mut().unwrap().current_break_clip_time = 5.0;
+        assert!(entry.is_skip_allowed());
+
+        entry.break_status = None;
+        assert!(entry.is_skip_allowed());
+    }
+
+    #[test]
+    fn test_parse_media_status_with_unknown_fields() {
+        let message = media_status(
+            0,
+            serde_json::json!([{
+                "mediaSessionId": 1,
+                "playerState": "PLAYING",
+                "queueData": {
+                    "id": "queue-1",
+                    "items": [{ "itemId": 1 }],
+                    "shuffle": false
+                },
+                "extendedStatus": { "playerState": "LOADING" },
+                "media": {
+                    "contentUrl": "http://example.com/video.mp4",
+                    "streamType": "BUFFERED",
+                    "mediaCategory": "VIDEO"
+                }
+            }]),
+        );
+        let channel = MediaChannel::new(
+            DEFAULT_SENDER_ID,
+            Lrc::new(MessageManager::new(MockTcpStream::new())),
+        );
+
+        match channel.parse(&message).unwrap() {
+            MediaResponse::Status(status) => {
+                assert_eq!(status.request_id, 0);
+                assert_eq!(status.entries[0].player_state, PlayerState::Playing);
+                assert_eq!(
+                    status.entries[0].media.as_ref().unwrap().stream_type,
+                    StreamType::Buffered
+                );
+            }
+            response => panic!("expected Status, but got {:?}", response),
         }
     }
 
     #[test]
-    fn test_parse_unknown_message_type() {
-        let message_type = "FOO_BAR";
-        let payload = format!("{{\"type\":\"{}\",\"itemId\":666}}", message_type);
-        let expected_payload = serde_json::from_str::<serde_json::Value>(payload.as_str()).unwrap();
-        let message = CastMessage {
-            namespace: CHANNEL_NAMESPACE.to_string(),
-            source: DEFAULT_RECEIVER_ID.to_string(),
-            destination: DEFAULT_SENDER_ID.to_string(),
-            payload: CastMessagePayload::String(payload),
-        };
-        let channel = MediaChannel {
-            sender: Cow::from(DEFAULT_SENDER_ID),
-            message_manager: Lrc::new(MessageManager::new(MockTcpStream::new())),
-        };
+    fn test_repeat_mode() {
+        for (repeat_mode, raw) in [
+            (RepeatMode::Off, "\"REPEAT_OFF\""),
+            (RepeatMode::All, "\"REPEAT_ALL\""),
+            (RepeatMode::Single, "\"REPEAT_SINGLE\""),
+            (RepeatMode::AllAndShuffle, "\"REPEAT_ALL_AND_SHUFFLE\""),
+            (
+                RepeatMode::Unknown("REPEAT_RANDOM".to_string()),
+                "\"REPEAT_RANDOM\"",
+            ),
+        ] {
+            assert_eq!(serde_json::to_string(&repeat_mode).unwrap(), raw);
+            assert_eq!(repeat_mode.to_string().parse(), Ok(repeat_mode.clone()));
+            assert_eq!(
+                serde_json::from_str::<RepeatMode>(raw).unwrap(),
+                repeat_mode
+            );
+        }
 
-        let response = channel.parse(&message).unwrap();
+        let mut repeat_mode = RepeatMode::Off;
+        let mut cycle = vec![];
+        for _ in 0..4 {
+            repeat_mode = repeat_mode.next();
+            cycle.push(repeat_mode.clone());
+        }
+        assert_eq!(
+            cycle,
+            vec![
+                RepeatMode::All,
+                RepeatMode::Single,
+                RepeatMode::Off,
+                RepeatMode::All
+            ]
+        );
+        assert_eq!(RepeatMode::AllAndShuffle.next(), RepeatMode::Off);
 
-        if let MediaResponse::NotImplemented(result_code, result_payload) = response {
-            assert_eq!(message_type, result_code);
-            assert_eq!(expected_payload, result_payload);
-        } else {
-            panic!("expected MediaResponse::Error, but got {:?}", response);
+        let channel = MediaChannel::new(
+            DEFAULT_SENDER_ID,
+            Lrc::new(MessageManager::new(MockTcpStream::new())),
+        );
+        let message = media_status(
+            0,
+            serde_json::json!([{ "mediaSessionId": 1, "repeatMode": "REPEAT_RANDOM" }]),
+        );
+        match channel.parse(&message).unwrap() {
+            MediaResponse::Status(status) => assert_eq!(
+                status.entries[0].repeat_mode,
+                Some(RepeatMode::Unknown("REPEAT_RANDOM".to_string()))
+            ),
+            response => panic!("expected media status, but got {:?}", response),
         }
     }
 }
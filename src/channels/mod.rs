@@ -1,16 +1,97 @@
 pub mod connection;
+pub mod custom;
 pub mod heartbeat;
 pub mod media;
+pub mod multizone;
 pub mod receiver;
 
+use crate::{errors::Error, message_manager::CastMessage};
+
+/// Parses `message` that belongs to the `namespace` channel: extracts its message type and
+/// `requestId` (if any) and lets `parse` turn the message type and JSON payload into the channel
+/// response. Some replies (e.g. to `GET_APP_AVAILABILITY`) use `responseType` instead of `type`.
+///
+/// Channels don't know how to handle binary payload, so it's passed to `parse` as is (as an array
+/// of bytes, see `CastMessagePayload::to_json`) with empty message type, which is expected to end
+/// up as `NotImplemented` response.
+///
+/// Fails with `Error::UnexpectedNamespace` if `message` belongs to another namespace.
+///
+/// # Return value
+///
+/// Tuple of the request id, message type and parsed response.
+pub(crate) fn parse_envelope<R, F>(
+    message: &CastMessage,
+    namespace: &str,
+    parse: F,
+) -> Result<(Option<i64>, String, R), Error>
+where
+    F: FnOnce(&str, serde_json::Value) -> Result<R, Error>,
+{
+    if message.namespace != namespace {
+        return Err(Error::UnexpectedNamespace {
+            expected: namespace.to_string(),
+            actual: message.namespace.clone(),
+        });
+    }
+
+    let reply = message.payload.to_json()?;
+
+    let message_type = reply
+        .as_object()
+        .and_then(|object| object.get("type").or_else(|| object.get("responseType")))
+        .and_then(|property| property.as_str())
+        .unwrap_or("")
+        .to_string();
+
+    let request_id = reply
+        .get("requestId")
+        .and_then(|property| property.as_i64());
+
+    let response = parse(&message_type, reply)?;
+
+    Ok((request_id, message_type, response))
+}
+
+/// Parses `message` with `parse` and returns the response along with the raw JSON payload it has
+/// been parsed from (see `CastMessagePayload::to_json`).
+pub(crate) fn parse_with_raw<R, F>(
+    message: &CastMessage,
+    parse: F,
+) -> Result<(R, serde_json::Value), Error>
+where
+    F: FnOnce(&CastMessage) -> Result<R, Error>,
+{
+    Ok((parse(message)?, message.payload.to_json()?))
+}
+
 #[cfg(test)]
-mod tests {
-    use std::io::{Read, Write};
+pub(crate) mod tests {
+    use std::{
+        cell::RefCell,
+        io::{Read, Write},
+        time::Duration,
+    };
+
+    use crate::{
+        cast::{
+            cast_channel,
+            cast_channel::cast_message::{PayloadType, ProtocolVersion},
+        },
+        message_manager::{CastMessage, CastMessagePayload, ReadTimeout},
+        utils,
+    };
 
     pub struct MockTcpStream {
         pub read_buffer: Vec<u8>,
         pub read_pos: usize,
         pub write_buffer: Vec<u8>,
+        /// Whether reading from the exhausted stream should fail as if read timeout expired.
+        pub timeout_when_empty: bool,
+        /// Whether writing to the stream should fail as if connection is broken.
+        pub fail_writes: bool,
+        /// Every read timeout that has been set, the last one is the current one.
+        pub read_timeouts: RefCell<Vec<Option<Duration>>>,
     }
 
     impl MockTcpStream {
@@ -19,12 +100,57 @@ mod tests {
                 read_buffer: vec![],
                 read_pos: 0,
                 write_buffer: vec![],
+                timeout_when_empty: false,
+                fail_writes: false,
+                read_timeouts: RefCell::new(vec![]),
             }
         }
+
+        /// Appends framed `message` to the data that will be read from the stream.
+        pub fn add_message(&mut self, message: &CastMessage) {
+            let mut raw_message = cast_channel::CastMessage::new();
+
+            raw_message.set_protocol_version(ProtocolVersion::CASTV2_1_0);
+            raw_message.set_namespace(message.namespace.clone());
+            raw_message.set_source_id(message.source.clone());
+            raw_message.set_destination_id(message.destination.clone());
+
+            match message.payload {
+                CastMessagePayload::String(ref payload) => {
+                    raw_message.set_payload_type(PayloadType::STRING);
+                    raw_message.set_payload_utf8(payload.clone());
+                }
+                CastMessagePayload::Binary(ref payload) => {
+                    raw_message.set_payload_type(PayloadType::BINARY);
+                    raw_message.set_payload_binary(payload.clone());
+                }
+            }
+
+            let content = utils::to_vec(&raw_message).unwrap();
+
+            self.read_buffer
+                .extend(utils::write_u32_to_buffer(content.len() as u32).unwrap());
+            self.read_buffer.extend(content);
+        }
+    }
+
+    impl ReadTimeout for MockTcpStream {
+        fn read_timeout(&self) -> std::io::Result<Option<Duration>> {
+            Ok(self.read_timeouts.borrow().last().copied().flatten())
+        }
+
+        fn set_read_timeout(&self, timeout: Option<Duration>) -> std::io::Result<()> {
+            self.read_timeouts.borrow_mut().push(timeout);
+            Ok(())
+        }
     }
 
     impl Read for MockTcpStream {
         fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.timeout_when_empty && self.read_pos == self.read_buffer.len() {
+                return Err(std::io::ErrorKind::WouldBlock.into());
+            }
+
             let bytes_to_read = std::cmp::min(buf.len(), self.read_buffer.len() - self.read_pos);
             buf[..bytes_to_read]
                 .copy_from_slice(&self.read_buffer[self.read_pos..self.read_pos + bytes_to_read]);
@@ -35,6 +161,10 @@ mod tests {
 
     impl Write for MockTcpStream {
         fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if self.fail_writes {
+                return Err(std::io::ErrorKind::BrokenPipe.into());
+            }
+
             self.write_buffer.extend_from_slice(buf);
             Ok(buf.len())
         }
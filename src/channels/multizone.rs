@@ -0,0 +1,420 @@
+use std::{
+    borrow::Cow,
+    io::{Read, Write},
+};
+
+use crate::{
+    cast::proxies,
+    channels::{
+        self,
+        receiver::{InvalidRequest, Volume},
+    },
+    errors::Error,
+    message_manager::{CastMessage, CastMessagePayload, MessageManager},
+    namespaces, Lrc,
+};
+
+const MESSAGE_TYPE_GET_STATUS: &str = "GET_STATUS";
+const MESSAGE_TYPE_SET_DEVICE_VOLUME: &str = "SET_DEVICE_VOLUME";
+
+const MESSAGE_TYPE_MULTIZONE_STATUS: &str = "MULTIZONE_STATUS";
+const MESSAGE_TYPE_DEVICE_ADDED: &str = "DEVICE_ADDED";
+const MESSAGE_TYPE_DEVICE_UPDATED: &str = "DEVICE_UPDATED";
+const MESSAGE_TYPE_DEVICE_REMOVED: &str = "DEVICE_REMOVED";
+const MESSAGE_TYPE_INVALID_REQUEST: &str = "INVALID_REQUEST";
+
+/// Describes a single cast device (speaker) that is a member of the speaker group.
+#[derive(Clone, Debug)]
+pub struct Device {
+    /// Unique identifier of the device.
+    pub device_id: String,
+    /// Friendly name of the device.
+    pub name: String,
+    /// Own volume of the device, it may differ from the volume of the group as a whole.
+    pub volume: Volume,
+    /// Bit mask of the device capabilities (e.g. video or audio output).
+    pub capabilities: i32,
+}
+
+impl Device {
+    fn decode(device: &proxies::multizone::Device) -> Device {
+        Device {
+            device_id: device.device_id.clone(),
+            name: device.name.clone(),
+            volume: Volume::decode(&device.volume),
+            capabilities: device.capabilities,
+        }
+    }
+}
+
+/// Describes the current members of the speaker group.
+#[derive(Clone, Debug)]
+pub struct Status {
+    /// Unique id of the request that requested the status.
+    pub request_id: u32,
+    /// Devices that are members of the group.
+    pub devices: Vec<Device>,
+    /// Whether the group plays different channels (e.g. left and right) on different devices.
+    pub is_multichannel: bool,
+}
+
+#[derive(Clone, Debug)]
+pub enum MultizoneResponse {
+    /// Statuses of all group members.
+    Status(Status),
+    /// Device has joined the group.
+    DeviceAdded(Device),
+    /// Device status (e.g. volume) has changed.
+    DeviceUpdated(Device),
+    /// Device with the specified id has left the group.
+    DeviceRemoved(String),
+    /// Error indicating that request is not valid.
+    InvalidRequest(InvalidRequest),
+    /// Used every time when channel can't parse the message. Associated data contains `type` string
+    /// field and raw JSON data returned from cast device.
+    NotImplemented(String, serde_json::Value),
+}
+
+impl MultizoneResponse {
+    /// Converts error reply to the request with specified `request_id` into `Error::CastError`.
+    /// Returns `None` for any other response.
+    fn to_cast_error(&self, request_id: u32) -> Option<Error> {
+        match *self {
+            MultizoneResponse::InvalidRequest(ref error) if error.request_id == request_id => {
+                Some(Error::CastError {
+                    type_: MESSAGE_TYPE_INVALID_REQUEST.to_string(),
+                    reason: error.reason.clone(),
+                    request_id: Some(i64::from(request_id)),
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Channel that manages speaker groups (Cast audio groups): reports group members and allows to
+/// control volume of a single member. Messages should be exchanged with the receiver of the group
+/// itself.
+pub struct MultizoneChannel<'a, W>
+where
+    W: Read + Write,
+{
+    sender: Cow<'a, str>,
+    receiver: Cow<'a, str>,
+    message_manager: Lrc<MessageManager<W>>,
+}
+
+impl<'a, W> MultizoneChannel<'a, W>
+where
+    W: Read + Write,
+{
+    pub fn new<S>(
+        sender: S,
+        receiver: S,
+        message_manager: Lrc<MessageManager<W>>,
+    ) -> MultizoneChannel<'a, W>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        MultizoneChannel {
+            sender: sender.into(),
+            receiver: receiver.into(),
+            message_manager,
+        }
+    }
+
+    /// Retrieves statuses of all speaker group members.
+    ///
+    /// # Return value
+    ///
+    /// Returned `Result` should consist of either `Status` instance or an `Error`.
+    pub fn get_status(&self) -> Result<Status, Error> {
+        let request_id = self.message_manager.generate_request_id().get();
+
+        let payload = serde_json::to_string(&proxies::multizone::GetStatusRequest {
+            typ: MESSAGE_TYPE_GET_STATUS.to_string(),
+            request_id,
+        })?;
+
+        self.message_manager.send(CastMessage {
+            namespace: namespaces::MULTIZONE.to_string(),
+            source: self.sender.to_string(),
+            destination: self.receiver.to_string(),
+            payload: CastMessagePayload::String(payload),
+        })?;
+
+        self.message_manager.receive_find_map(|message| {
+            if !self.can_handle(message) {
+                return Ok(None);
+            }
+
+            let response = self.parse(message)?;
+            if let Some(error) = response.to_cast_error(request_id) {
+                return Err(error);
+            }
+
+            if let MultizoneResponse::Status(status) = response {
+                if status.request_id == request_id {
+                    return Ok(Some(status));
+                }
+            }
+
+            Ok(None)
+        })
+    }
+
+    /// Sets volume of a single speaker group member, leaving volume of the other members intact.
+    ///
+    /// # Arguments
+    ///
+    /// * `device_id` - identifier of the group member from `Device` instance;
+    /// * `volume` - anything that can be converted to a valid `Volume` structure. It's possible to
+    ///   set volume level, mute/unmute state or both altogether.
+    ///
+    /// # Return value
+    ///
+    /// Updated `Device` instance reported by receiver (either as `DEVICE_UPDATED` event or as part
+    /// of the `MULTIZONE_STATUS` reply).
+    pub fn set_device_volume<S, T>(&self, device_id: S, volume: T) -> Result<Device, Error>
+    where
+        S: Into<Cow<'a, str>>,
+        T: Into<Volume>,
+    {
+        let request_id = self.message_manager.generate_request_id().get();
+        let device_id = device_id.into();
+        let volume = volume.into();
+
+        let payload = serde_json::to_string(&proxies::multizone::SetDeviceVolumeRequest {
+            typ: MESSAGE_TYPE_SET_DEVICE_VOLUME.to_string(),
+            request_id,
+            device_id: device_id.to_string(),
+            volume: proxies::receiver::Volume {
+                level: volume.level,
+                muted: volume.muted,
+                control_type: None,
+                step_interval: None,
+            },
+        })?;
+
+        self.message_manager.send(CastMessage {
+            namespace: namespaces::MULTIZONE.to_string(),
+            source: self.sender.to_string(),
+            destination: self.receiver.to_string(),
+            payload: CastMessagePayload::String(payload),
+        })?;
+
+        self.message_manager.receive_find_map(|message| {
+            if !self.can_handle(message) {
+                return Ok(None);
+            }
+
+            let response = self.parse(message)?;
+            if let Some(error) = response.to_cast_error(request_id) {
+                return Err(error);
+            }
+
+            match response {
+                MultizoneResponse::DeviceUpdated(device) if device.device_id == device_id => {
+                    Ok(Some(device))
+                }
+                MultizoneResponse::Status(mut status) if status.request_id == request_id => {
+                    let position = status
+                        .devices
+                        .iter()
+                        .position(|device| device.device_id == device_id);
+
+                    Ok(position.map(|position| status.devices.remove(position)))
+                }
+                _ => Ok(None),
+            }
+        })
+    }
+
+    pub fn can_handle(&self, message: &CastMessage) -> bool {
+        message.namespace == namespaces::MULTIZONE
+    }
+
+    pub fn parse(&self, message: &CastMessage) -> Result<MultizoneResponse, Error> {
+        self.parse_envelope(message)
+            .map(|(_, _, response)| response)
+    }
+
+    /// Same as `parse`, but also returns the raw JSON payload the response has been parsed from,
+    /// e.g. to log or archive exactly what the cast device has sent.
+    ///
+    /// # Return value
+    ///
+    /// Tuple of the parsed response and raw payload.
+    pub fn parse_with_raw(
+        &self,
+        message: &CastMessage,
+    ) -> Result<(MultizoneResponse, serde_json::Value), Error> {
+        channels::parse_with_raw(message, |message| self.parse(message))
+    }
+
+    /// Same as `parse`, but also returns `requestId` (if any) and message type (empty for binary
+    /// payload) of `message`, e.g. for custom dispatching and logging.
+    ///
+    /// # Return value
+    ///
+    /// Tuple of the request id, message type and parsed response.
+    pub fn parse_envelope(
+        &self,
+        message: &CastMessage,
+    ) -> Result<(Option<i64>, String, MultizoneResponse), Error> {
+        channels::parse_envelope(message, namespaces::MULTIZONE, |message_type, reply| {
+            Ok(match message_type {
+                MESSAGE_TYPE_MULTIZONE_STATUS => {
+                    let reply: proxies::multizone::StatusReply =
+                        serde_json::value::from_value(reply)?;
+
+                    MultizoneResponse::Status(Status {
+                        request_id: reply.request_id,
+                        devices: reply.status.devices.iter().map(Device::decode).collect(),
+                        is_multichannel: reply.status.is_multichannel,
+                    })
+                }
+                MESSAGE_TYPE_DEVICE_ADDED => {
+                    let reply: proxies::multizone::DeviceReply =
+                        serde_json::value::from_value(reply)?;
+
+                    MultizoneResponse::DeviceAdded(Device::decode(&reply.device))
+                }
+                MESSAGE_TYPE_DEVICE_UPDATED => {
+                    let reply: proxies::multizone::DeviceReply =
+                        serde_json::value::from_value(reply)?;
+
+                    MultizoneResponse::DeviceUpdated(Device::decode(&reply.device))
+                }
+                MESSAGE_TYPE_DEVICE_REMOVED => {
+                    let reply: proxies::multizone::DeviceRemovedReply =
+                        serde_json::value::from_value(reply)?;
+
+                    MultizoneResponse::DeviceRemoved(reply.device_id)
+                }
+                MESSAGE_TYPE_INVALID_REQUEST => {
+                    let reply: proxies::receiver::InvalidRequestReply =
+                        serde_json::value::from_value(reply)?;
+
+                    MultizoneResponse::InvalidRequest(InvalidRequest {
+                        request_id: reply.request_id,
+                        reason: reply.reason,
+                    })
+                }
+                _ => MultizoneResponse::NotImplemented(message_type.to_string(), reply),
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{channels::tests::MockTcpStream, DEFAULT_RECEIVER_ID, DEFAULT_SENDER_ID};
+
+    use super::*;
+
+    fn create_message(payload: serde_json::Value) -> CastMessage {
+        CastMessage {
+            namespace: namespaces::MULTIZONE.to_string(),
+            source: DEFAULT_RECEIVER_ID.to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String(payload.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_get_status() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&create_message(serde_json::json!({
+            "type": "MULTIZONE_STATUS",
+            "requestId": 1,
+            "status": {
+                "devices": [{
+                    "deviceId": "kitchen",
+                    "name": "Kitchen speaker",
+                    "capabilities": 4,
+                    "volume": { "level": 0.3, "muted": false }
+                }, {
+                    "deviceId": "bedroom",
+                    "name": "Bedroom speaker",
+                    "capabilities": 4,
+                    "volume": { "level": 0.8, "muted": true }
+                }],
+                "isMultichannel": false
+            }
+        })));
+
+        let channel = MultizoneChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::new(MessageManager::new(stream)),
+        );
+
+        let status = channel.get_status().unwrap();
+
+        assert_eq!(status.devices.len(), 2);
+        assert_eq!(status.devices[0].device_id, "kitchen");
+        assert_eq!(status.devices[0].name, "Kitchen speaker");
+        assert_eq!(status.devices[0].capabilities, 4);
+        assert_eq!(status.devices[0].volume.level, Some(0.3));
+        assert_eq!(status.devices[1].volume.muted, Some(true));
+    }
+
+    #[test]
+    fn test_set_device_volume() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&create_message(serde_json::json!({
+            "type": "DEVICE_UPDATED",
+            "device": {
+                "deviceId": "bedroom",
+                "name": "Bedroom speaker",
+                "capabilities": 4,
+                "volume": { "level": 0.8, "muted": false }
+            }
+        })));
+        stream.add_message(&create_message(serde_json::json!({
+            "type": "DEVICE_UPDATED",
+            "device": {
+                "deviceId": "kitchen",
+                "name": "Kitchen speaker",
+                "capabilities": 4,
+                "volume": { "level": 0.5, "muted": false }
+            }
+        })));
+
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = MultizoneChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::clone(&message_manager),
+        );
+
+        let device = channel.set_device_volume("kitchen", 0.5).unwrap();
+
+        assert_eq!(device.device_id, "kitchen");
+        assert_eq!(device.volume.level, Some(0.5));
+
+        let written = message_manager.inner().write_buffer.clone();
+        let written = String::from_utf8_lossy(&written);
+        assert!(written.contains("\"type\":\"SET_DEVICE_VOLUME\""));
+        assert!(written.contains("\"deviceId\":\"kitchen\""));
+    }
+
+    #[test]
+    fn test_parse_device_removed() {
+        let channel = MultizoneChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::new(MessageManager::new(MockTcpStream::new())),
+        );
+
+        let response = channel
+            .parse(&create_message(serde_json::json!({
+                "type": "DEVICE_REMOVED",
+                "deviceId": "kitchen"
+            })))
+            .unwrap();
+
+        assert!(matches!(response, MultizoneResponse::DeviceRemoved(id) if id == "kitchen"));
+    }
+}
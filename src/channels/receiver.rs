@@ -1,42 +1,167 @@
 use std::{
     borrow::Cow,
+    collections::HashMap,
     convert::Into,
+    fmt::{self, Display, Formatter},
     io::{Read, Write},
     str::FromStr,
-    string::ToString,
+    time::Duration,
 };
 
 use serde::Serialize;
 
 use crate::{
     cast::proxies,
+    channels,
     errors::Error,
     message_manager::{CastMessage, CastMessagePayload, MessageManager},
-    Lrc,
+    namespaces, Lock, Lrc,
 };
 
-const CHANNEL_NAMESPACE: &str = "urn:x-cast:com.google.cast.receiver";
+pub(crate) const MESSAGE_TYPE_LAUNCH: &str = "LAUNCH";
+pub(crate) const MESSAGE_TYPE_STOP: &str = "STOP";
+pub(crate) const MESSAGE_TYPE_GET_STATUS: &str = "GET_STATUS";
+pub(crate) const MESSAGE_TYPE_SET_VOLUME: &str = "SET_VOLUME";
+const MESSAGE_TYPE_GET_APP_AVAILABILITY: &str = "GET_APP_AVAILABILITY";
 
-const MESSAGE_TYPE_LAUNCH: &str = "LAUNCH";
-const MESSAGE_TYPE_STOP: &str = "STOP";
-const MESSAGE_TYPE_GET_STATUS: &str = "GET_STATUS";
-const MESSAGE_TYPE_SET_VOLUME: &str = "SET_VOLUME";
-
-const MESSAGE_TYPE_RECEIVER_STATUS: &str = "RECEIVER_STATUS";
+pub(crate) const MESSAGE_TYPE_RECEIVER_STATUS: &str = "RECEIVER_STATUS";
 const MESSAGE_TYPE_LAUNCH_ERROR: &str = "LAUNCH_ERROR";
+const MESSAGE_TYPE_LAUNCH_STATUS: &str = "LAUNCH_STATUS";
 const MESSAGE_TYPE_INVALID_REQUEST: &str = "INVALID_REQUEST";
 
-const APP_DEFAULT_MEDIA_RECEIVER_ID: &str = "CC1AD845";
-const APP_BACKDROP_ID: &str = "E8C28D3C";
-const APP_YOUTUBE_ID: &str = "233637DE";
+const APP_AVAILABLE: &str = "APP_AVAILABLE";
+
+/// Default time blocking receiver operations (e.g. `ReceiverChannel::launch_app`) wait for the
+/// reply of the cast device.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Volume step used by `volume_up`/`volume_down` if cast device doesn't report its own.
+const DEFAULT_VOLUME_STEP_INTERVAL: f32 = 0.05;
+
+/// App id of the Default Media Receiver, generic player for the simple media (e.g. video URLs).
+pub const DEFAULT_MEDIA_RECEIVER: &str = "CC1AD845";
+/// App id of the Backdrop, application cast device shows while idle.
+pub const BACKDROP: &str = "E8C28D3C";
+/// App id of the YouTube receiver application.
+pub const YOUTUBE: &str = "233637DE";
+
+/// Describes the way cast device volume can be controlled.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum VolumeControlType {
+    /// Cast device volume can be changed.
+    Attenuation,
+    /// Cast device volume is fixed and can't be changed (e.g. HDMI-CEC controlled TV).
+    Fixed,
+    /// Cast device volume is the master volume of the system.
+    Master,
+}
+
+impl FromStr for VolumeControlType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<VolumeControlType, Error> {
+        match s {
+            "attenuation" => Ok(VolumeControlType::Attenuation),
+            "fixed" => Ok(VolumeControlType::Fixed),
+            "master" => Ok(VolumeControlType::Master),
+            _ => Err(Error::Internal(format!(
+                "Unknown volume control type {}",
+                s
+            ))),
+        }
+    }
+}
+
+impl Display for VolumeControlType {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let control_type = match *self {
+            VolumeControlType::Attenuation => "attenuation",
+            VolumeControlType::Fixed => "fixed",
+            VolumeControlType::Master => "master",
+        };
+
+        f.write_str(control_type)
+    }
+}
 
 /// Structure that describes possible cast device volume options.
 #[derive(Copy, Clone, Debug)]
 pub struct Volume {
-    /// Volume level.
+    /// Volume level (`0.0` - `1.0`) exactly as reported by the cast device. Devices quantize the
+    /// level (e.g. to 1/255 steps), so level that has been set to `0.5` may be reported back as
+    /// `0.50196`: use `approx_eq` rather than exact comparison.
     pub level: Option<f32>,
     /// Mute/unmute state.
     pub muted: Option<bool>,
+    /// The way volume can be controlled, reported by the cast device only.
+    pub control_type: Option<VolumeControlType>,
+    /// Minimal volume level change the cast device supports, reported by the cast device only.
+    pub step_interval: Option<f32>,
+}
+
+impl Volume {
+    pub(crate) fn decode(volume: &proxies::receiver::Volume) -> Volume {
+        Volume {
+            level: volume.level,
+            muted: volume.muted,
+            control_type: volume
+                .control_type
+                .as_ref()
+                .and_then(|control_type| VolumeControlType::from_str(control_type).ok()),
+            step_interval: volume.step_interval,
+        }
+    }
+
+    /// Checks whether volume levels differ by no more than `epsilon` and mute states are equal.
+    /// Properties reported by the cast device only (control type and step interval) are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_cast::channels::receiver::Volume;
+    ///
+    /// let reported = Volume::from(0.50196);
+    /// assert!(reported.approx_eq(&Volume::from(0.5), 0.01));
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `other` - volume to compare with;
+    /// * `epsilon` - maximum allowed difference of the levels, e.g. `step_interval`.
+    pub fn approx_eq(&self, other: &Volume, epsilon: f32) -> bool {
+        let level_eq = match (self.level, other.level) {
+            (Some(level), Some(other_level)) => (level - other_level).abs() <= epsilon,
+            (level, other_level) => level == other_level,
+        };
+
+        level_eq && self.muted == other.muted
+    }
+
+    /// Applies partial volume update (e.g. the one returned by `ReceiverChannel::set_volume` or
+    /// pushed with receiver status) to the known volume: only properties that `update` provides
+    /// are overwritten, the rest stay as they were.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use rust_cast::channels::receiver::Volume;
+    ///
+    /// let mut volume = Volume::from((0.5, false));
+    /// volume.update(&Volume::from(true));
+    ///
+    /// assert_eq!(volume.level, Some(0.5));
+    /// assert_eq!(volume.muted, Some(true));
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `update` - volume with the changed properties only.
+    pub fn update(&mut self, update: &Volume) {
+        self.level = update.level.or(self.level);
+        self.muted = update.muted.or(self.muted);
+        self.control_type = update.control_type.or(self.control_type);
+        self.step_interval = update.step_interval.or(self.step_interval);
+    }
 }
 
 /// This `From<f32>` implementation is useful when only volume level is needed.
@@ -45,6 +170,8 @@ impl From<f32> for Volume {
         Self {
             level: Some(level),
             muted: None,
+            control_type: None,
+            step_interval: None,
         }
     }
 }
@@ -55,6 +182,8 @@ impl From<bool> for Volume {
         Self {
             level: None,
             muted: Some(muted),
+            control_type: None,
+            step_interval: None,
         }
     }
 }
@@ -66,6 +195,8 @@ impl From<(f32, bool)> for Volume {
         Self {
             level: Some(level),
             muted: Some(muted),
+            control_type: None,
+            step_interval: None,
         }
     }
 }
@@ -85,6 +216,56 @@ pub struct Application {
     pub display_name: String,
     /// Descriptive text for the current application content, for example “My vacations”.
     pub status_text: String,
+    /// Whether application has been launched from the cloud (e.g. by voice assistant) rather than
+    /// by a sender. `false` if cast device doesn't report it.
+    pub launched_from_cloud: bool,
+    /// Whether application runs on the entertainment device (e.g. TV), reported only by some cast
+    /// devices.
+    pub is_on_entertainment_device: Option<bool>,
+}
+
+impl Application {
+    /// The identifier of the Cast application.
+    pub fn app_id(&self) -> &str {
+        &self.app_id
+    }
+
+    /// Session id that media and other application-level commands refer to.
+    pub fn session_id(&self) -> &str {
+        &self.session_id
+    }
+
+    /// Destination id to `CONNECT` to and send application messages to (e.g. `web-1`).
+    pub fn transport_id(&self) -> &str {
+        &self.transport_id
+    }
+
+    /// The human-readable name of the Cast application.
+    pub fn display_name(&self) -> &str {
+        &self.display_name
+    }
+
+    /// Namespaces supported by the receiver application.
+    pub fn namespaces(&self) -> &[String] {
+        &self.namespaces
+    }
+
+    /// Checks whether receiver application advertises support of the `namespace` (e.g.
+    /// `namespaces::MEDIA`), messages of other namespaces are most likely left unanswered.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - namespace to look for.
+    pub fn supports_namespace(&self, namespace: &str) -> bool {
+        self.namespaces
+            .iter()
+            .any(|supported| supported == namespace)
+    }
+
+    /// Descriptive text for the current application content.
+    pub fn status_text(&self) -> &str {
+        &self.status_text
+    }
 }
 
 /// Describes the current status of the receiver cast device.
@@ -102,15 +283,51 @@ pub struct Status {
     pub volume: Volume,
 }
 
+impl Status {
+    /// Finds the running application with the specified identifier (e.g.
+    /// `CastDeviceApp::DefaultMediaReceiver.to_string()`) among the ones reported in status.
+    pub fn running_application(&self, app_id: &str) -> Option<&Application> {
+        self.applications.iter().find(|app| app.app_id == app_id)
+    }
+
+    /// Checks whether application with the specified identifier is running, e.g. to notice that
+    /// user has stopped it from the TV and return to the idle screen.
+    pub fn is_app_running(&self, app_id: &str) -> bool {
+        self.running_application(app_id).is_some()
+    }
+}
+
+/// Reason of the launch error reported when requested application isn't available on the cast
+/// device, as opposed to the transient failures that may go away on retry.
+pub const LAUNCH_ERROR_REASON_NOT_FOUND: &str = "NOT_FOUND";
+
 /// Describes the application launch error.
 #[derive(Clone, Debug)]
 pub struct LaunchError {
-    /// Unique id of the request that tried to launch application.
+    /// Unique id of the request that tried to launch application, `0` if cast device didn't
+    /// provide it.
     pub request_id: u32,
-    /// Description of the launch error reason if available.
+    /// Description of the launch error reason if available, e.g. `NOT_FOUND` or `CANCELLED`.
     pub reason: Option<String>,
 }
 
+impl LaunchError {
+    /// Checks whether application failed to launch because it isn't available on the cast device.
+    pub fn is_not_found(&self) -> bool {
+        self.reason.as_deref() == Some(LAUNCH_ERROR_REASON_NOT_FOUND)
+    }
+}
+
+/// Describes the intermediate status of the application launch, e.g. when launch has to be
+/// authorized by the user on the cast device.
+#[derive(Clone, Debug)]
+pub struct LaunchStatus {
+    /// Unique id of the request that launches application.
+    pub request_id: u32,
+    /// Launch status, e.g. `USER_PENDING_AUTHORIZATION` or `USER_ALLOWED`.
+    pub status: String,
+}
+
 /// Describes the invalid request error.
 #[derive(Clone, Debug)]
 pub struct InvalidRequest {
@@ -120,6 +337,15 @@ pub struct InvalidRequest {
     pub reason: Option<String>,
 }
 
+/// Describes availability of the applications on the cast device.
+#[derive(Clone, Debug)]
+pub struct AppAvailability {
+    /// Unique id of the request that requested the availability.
+    pub request_id: u32,
+    /// Availability of every application requested, keyed by application id.
+    pub availability: HashMap<String, bool>,
+}
+
 /// Represents all currently supported incoming messages that receiver channel can handle.
 #[derive(Clone, Debug)]
 pub enum ReceiverResponse {
@@ -127,13 +353,58 @@ pub enum ReceiverResponse {
     Status(Status),
     /// Error indicating that receiver failed to launch application.
     LaunchError(LaunchError),
+    /// Intermediate status of the application launch.
+    LaunchStatus(LaunchStatus),
     /// Error indicating that request is not valid.
     InvalidRequest(InvalidRequest),
+    /// Availability of the requested applications.
+    AppAvailability(AppAvailability),
     /// Used every time when channel can't parse the message. Associated data contains `type` string
     /// field and raw JSON data returned from cast device.
     NotImplemented(String, serde_json::Value),
 }
 
+/// Notable changes of the receiver state, either caused by this sender or by anyone else (e.g. TV
+/// remote or another sender), see `ReceiverChannel::event`.
+#[derive(Copy, Clone, Debug)]
+pub enum ReceiverEvent {
+    /// Volume level or mute state of the cast device has changed.
+    VolumeChanged(Volume),
+}
+
+impl ReceiverResponse {
+    /// Converts error reply to the request with specified `request_id` into `Error::CastError`.
+    /// Returns `None` for any other response.
+    pub(crate) fn to_cast_error(&self, request_id: u32) -> Option<Error> {
+        let (type_, reason) = match *self {
+            ReceiverResponse::LaunchError(ref error) if error.request_id == request_id => {
+                (MESSAGE_TYPE_LAUNCH_ERROR, error.reason.clone())
+            }
+            ReceiverResponse::InvalidRequest(ref error) if error.request_id == request_id => {
+                (MESSAGE_TYPE_INVALID_REQUEST, error.reason.clone())
+            }
+            _ => return None,
+        };
+
+        Some(Error::CastError {
+            type_: type_.to_string(),
+            reason,
+            request_id: Some(i64::from(request_id)),
+        })
+    }
+}
+
+/// Describes what `ReceiverChannel::launch_app_with_mode` does if requested application is already
+/// running on the cast device.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LaunchMode {
+    /// Joins the running application (returns its `Application`) without launching it again, so
+    /// that its current session is preserved.
+    JoinIfRunning,
+    /// Stops the running application first, so that it's launched with a fresh session.
+    ForceRelaunch,
+}
+
 #[derive(Clone, Debug, PartialEq)]
 pub enum CastDeviceApp {
     DefaultMediaReceiver,
@@ -147,9 +418,9 @@ impl FromStr for CastDeviceApp {
 
     fn from_str(s: &str) -> Result<CastDeviceApp, ()> {
         let app = match s {
-            APP_DEFAULT_MEDIA_RECEIVER_ID | "default" => CastDeviceApp::DefaultMediaReceiver,
-            APP_BACKDROP_ID | "backdrop" => CastDeviceApp::Backdrop,
-            APP_YOUTUBE_ID | "youtube" => CastDeviceApp::YouTube,
+            DEFAULT_MEDIA_RECEIVER | "default" => CastDeviceApp::DefaultMediaReceiver,
+            BACKDROP | "backdrop" => CastDeviceApp::Backdrop,
+            YOUTUBE | "youtube" => CastDeviceApp::YouTube,
             custom => CastDeviceApp::Custom(custom.to_string()),
         };
 
@@ -157,13 +428,13 @@ impl FromStr for CastDeviceApp {
     }
 }
 
-impl ToString for CastDeviceApp {
-    fn to_string(&self) -> String {
+impl Display for CastDeviceApp {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match *self {
-            CastDeviceApp::DefaultMediaReceiver => APP_DEFAULT_MEDIA_RECEIVER_ID.to_string(),
-            CastDeviceApp::Backdrop => APP_BACKDROP_ID.to_string(),
-            CastDeviceApp::YouTube => APP_YOUTUBE_ID.to_string(),
-            CastDeviceApp::Custom(ref app_id) => app_id.to_string(),
+            CastDeviceApp::DefaultMediaReceiver => f.write_str(DEFAULT_MEDIA_RECEIVER),
+            CastDeviceApp::Backdrop => f.write_str(BACKDROP),
+            CastDeviceApp::YouTube => f.write_str(YOUTUBE),
+            CastDeviceApp::Custom(ref app_id) => f.write_str(app_id),
         }
     }
 }
@@ -175,6 +446,10 @@ where
     sender: Cow<'a, str>,
     receiver: Cow<'a, str>,
     message_manager: Lrc<MessageManager<W>>,
+    /// Last volume cast device has reported.
+    volume: Lock<Option<Volume>>,
+    /// Event caused by the last response passed to `event`.
+    last_event: Lock<Option<ReceiverEvent>>,
 }
 
 impl<'a, W> ReceiverChannel<'a, W>
@@ -193,9 +468,58 @@ where
             sender: sender.into(),
             receiver: receiver.into(),
             message_manager,
+            volume: Lock::new(None),
+            last_event: Lock::new(None),
         }
     }
 
+    /// Checks whether `response` changes receiver state that is worth reacting to, e.g. whether
+    /// receiver status (pushed by the cast device whenever volume is changed with the TV remote or
+    /// by another sender) reports the volume that differs from the last known one. Statuses this
+    /// channel has requested itself (e.g. with `get_status` or `set_volume`) update the last known
+    /// volume as well, so the change is reported only once and calling this method again for the
+    /// same response returns `None`. The result is also remembered, see `last_event`.
+    ///
+    /// `CastDevice::receive` and `Player::receive` already call it for every receiver message, so
+    /// use `last_event` with them instead.
+    ///
+    /// # Arguments
+    ///
+    /// * `response` - response parsed with `parse`.
+    pub fn event(&self, response: &ReceiverResponse) -> Option<ReceiverEvent> {
+        let event = match *response {
+            ReceiverResponse::Status(ref status) if self.track_volume(&status.volume) => {
+                Some(ReceiverEvent::VolumeChanged(status.volume))
+            }
+            _ => None,
+        };
+
+        *self.last_event.borrow_mut() = event;
+        event
+    }
+
+    /// Returns the event caused by the last receiver message `CastDevice::receive` or
+    /// `Player::receive` has returned (or the last response passed to `event`), `None` if that
+    /// message hasn't changed receiver state.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rust_cast::{channels::receiver::ReceiverEvent, ChannelMessage};
+    ///
+    /// # use rust_cast::CastDevice;
+    /// # let cast_device = CastDevice::connect_without_host_verification("192.168.1.2", 8009)?;
+    /// if let ChannelMessage::Receiver(_) = cast_device.receive()? {
+    ///     if let Some(ReceiverEvent::VolumeChanged(volume)) = cast_device.receiver.last_event() {
+    ///         log::info!("Volume: {:?}, muted: {:?}", volume.level, volume.muted);
+    ///     }
+    /// }
+    /// # Ok::<(), rust_cast::errors::Error>(())
+    /// ```
+    pub fn last_event(&self) -> Option<ReceiverEvent> {
+        *self.last_event.borrow()
+    }
+
     /// Launches the specified receiver's application.
     ///
     /// # Examples
@@ -211,52 +535,182 @@ where
     /// # Arguments
     ///
     /// * `app` - `CastDeviceApp` instance reference to run.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if application hasn't been launched within `DEFAULT_TIMEOUT`.
     pub fn launch_app(&self, app: &CastDeviceApp) -> Result<Application, Error> {
+        self.launch_app_with_timeout(app, DEFAULT_TIMEOUT)
+    }
+
+    /// Same as `launch_app`, but waits for the launched application for up to `timeout`.
+    ///
+    /// # Arguments
+    ///
+    /// * `app` - `CastDeviceApp` instance reference to run;
+    /// * `timeout` - how long to wait for the cast device to report launched application.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if application hasn't been launched within `timeout`.
+    pub fn launch_app_with_timeout(
+        &self,
+        app: &CastDeviceApp,
+        timeout: Duration,
+    ) -> Result<Application, Error> {
+        self.launch(app, None, None, timeout)
+    }
+
+    /// Launches the specified receiver's application, explicitly choosing what to do if it's
+    /// already running (plain `launch_app` leaves that up to the cast device, which usually just
+    /// reports the running application). Receiver status is requested first to find out whether
+    /// application is running.
+    ///
+    /// # Arguments
+    ///
+    /// * `app` - `CastDeviceApp` instance reference to run;
+    /// * `mode` - whether to join the running application or relaunch it.
+    pub fn launch_app_with_mode(
+        &self,
+        app: &CastDeviceApp,
+        mode: LaunchMode,
+    ) -> Result<Application, Error> {
+        let running_application = self
+            .get_status()?
+            .running_application(&app.to_string())
+            .cloned();
+
+        match (mode, running_application) {
+            (LaunchMode::JoinIfRunning, Some(application)) => Ok(application),
+            (LaunchMode::ForceRelaunch, Some(application)) => {
+                self.stop_app(application.session_id.as_str())?;
+                self.launch(app, None, Some(&application.session_id), DEFAULT_TIMEOUT)
+            }
+            (_, None) => self.launch(app, None, None, DEFAULT_TIMEOUT),
+        }
+    }
+
+    /// Launches the Default Media Receiver, see `launch_app`.
+    ///
+    /// # Return value
+    ///
+    /// Launched `Application`, its `transport_id` should be used to connect to it and control the
+    /// media.
+    pub fn launch_default_media_receiver(&self) -> Result<Application, Error> {
+        self.launch_app(&CastDeviceApp::DefaultMediaReceiver)
+    }
+
+    /// Launches the specified receiver's application passing launch-time parameters to it (e.g.
+    /// deep link to the content). Parameters are sent as `appParams` of the `LAUNCH` request and
+    /// their meaning is up to the receiver application.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::str::FromStr;
+    /// use rust_cast::{CastDevice, channels::receiver::CastDeviceApp};
+    ///
+    /// # let cast_device = CastDevice::connect_without_host_verification("host", 1234).unwrap();
+    /// cast_device.receiver.launch_app_with_params(
+    ///     &CastDeviceApp::from_str("ABCD1234").unwrap(),
+    ///     serde_json::json!({ "contentId": "episode-42" }),
+    /// );
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `app` - `CastDeviceApp` instance reference to run;
+    /// * `params` - JSON value with the launch parameters.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::CastError` if cast device replies with `LAUNCH_ERROR` (e.g. because it
+    /// rejects the parameters).
+    pub fn launch_app_with_params(
+        &self,
+        app: &CastDeviceApp,
+        params: serde_json::Value,
+    ) -> Result<Application, Error> {
+        self.launch(app, Some(params), None, DEFAULT_TIMEOUT)
+    }
+
+    /// Sends LAUNCH request and waits for the status that includes launched application.
+    ///
+    /// # Arguments
+    ///
+    /// * `app` - `CastDeviceApp` instance reference to run;
+    /// * `app_params` - launch-time parameters, if any;
+    /// * `stale_session_id` - session of the stopped application instance that shouldn't be
+    ///   mistaken for the launched one;
+    /// * `timeout` - how long to wait for the status.
+    fn launch(
+        &self,
+        app: &CastDeviceApp,
+        app_params: Option<serde_json::Value>,
+        stale_session_id: Option<&str>,
+        timeout: Duration,
+    ) -> Result<Application, Error> {
         let request_id = self.message_manager.generate_request_id().get();
+        let app_id = app.to_string();
 
         let payload = serde_json::to_string(&proxies::receiver::AppLaunchRequest {
             typ: MESSAGE_TYPE_LAUNCH.to_string(),
             request_id,
-            app_id: app.to_string(),
+            app_id: app_id.clone(),
+            app_params,
         })?;
 
         self.message_manager.send(CastMessage {
-            namespace: CHANNEL_NAMESPACE.to_string(),
+            namespace: namespaces::RECEIVER.to_string(),
             source: self.sender.to_string(),
             destination: self.receiver.to_string(),
             payload: CastMessagePayload::String(payload),
         })?;
 
         // Once application is run cast receiver device should emit status update event, or launch
-        // error event if something went wrong.
-        self.message_manager.receive_find_map(|message| {
-            if !self.can_handle(message) {
-                return Ok(None);
-            }
+        // error event if something went wrong. If application is already running, receiver may not
+        // relaunch it and just broadcast its current status, so any status that includes requested
+        // application is good enough.
+        self.message_manager
+            .receive_find_map_with_timeout(timeout, |message| {
+                if !self.can_handle(message) {
+                    return Ok(None);
+                }
 
-            match self.parse(message)? {
-                ReceiverResponse::Status(mut status) => {
-                    if status.request_id == request_id {
-                        if status.applications.is_empty() {
-                            return Err(Error::Internal("Could not run application".into()))
-                        }
+                let response = self.parse(message)?;
+                if let Some(error) = response.to_cast_error(request_id) {
+                    return Err(error);
+                }
 
-                        return Ok(Some(status.applications.remove(0)));
+                // Some cast devices omit request id in launch errors, since applications are
+                // launched one at a time such errors can only be caused by the pending launch.
+                if let ReceiverResponse::LaunchError(ref error) = response {
+                    if error.request_id == 0 {
+                        return Err(Error::CastError {
+                            type_: MESSAGE_TYPE_LAUNCH_ERROR.to_string(),
+                            reason: error.reason.clone(),
+                            request_id: None,
+                        });
                     }
                 }
-                ReceiverResponse::LaunchError(error) => {
-                    if error.request_id == request_id {
-                        return Err(Error::Internal(format!(
-                            "Could not run application ({}).",
-                            error.reason.unwrap_or_else(|| "Unknown".to_string())
-                        )));
+
+                if let ReceiverResponse::Status(mut status) = response {
+                    let position = status.applications.iter().position(|application| {
+                        application.app_id == app_id
+                            && Some(application.session_id.as_str()) != stale_session_id
+                    });
+
+                    match position {
+                        Some(position) => return Ok(Some(status.applications.remove(position))),
+                        None if status.request_id == request_id => {
+                            return Err(Error::Internal("Could not run application".into()));
+                        }
+                        None => {}
                     }
                 }
-                _ => {}
-            }
 
-            Ok(None)
-        })
+                Ok(None)
+            })
     }
 
     /// Broadcasts a message over a cast device's message bus.
@@ -297,11 +751,32 @@ where
         Ok(())
     }
 
-    /// Stops currently active app using corresponding `session_id`.
+    /// Stops currently active app using corresponding `session_id`. To unload the media but keep
+    /// the application running use `MediaChannel::stop` instead.
     ///
     /// # Arguments
     /// * `session_id` - identifier of the active application session from `Application` instance.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if application hasn't been stopped within `DEFAULT_TIMEOUT`.
     pub fn stop_app<S>(&self, session_id: S) -> Result<(), Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.stop_app_with_timeout(session_id, DEFAULT_TIMEOUT)
+    }
+
+    /// Same as `stop_app`, but waits for the application to stop for up to `timeout`.
+    ///
+    /// # Arguments
+    /// * `session_id` - identifier of the active application session from `Application` instance;
+    /// * `timeout` - how long to wait for the cast device to confirm that application is stopped.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if application hasn't been stopped within `timeout`.
+    pub fn stop_app_with_timeout<S>(&self, session_id: S, timeout: Duration) -> Result<(), Error>
     where
         S: Into<Cow<'a, str>>,
     {
@@ -314,7 +789,7 @@ where
         })?;
 
         self.message_manager.send(CastMessage {
-            namespace: CHANNEL_NAMESPACE.to_string(),
+            namespace: namespaces::RECEIVER.to_string(),
             source: self.sender.to_string(),
             destination: self.receiver.to_string(),
             payload: CastMessagePayload::String(payload),
@@ -322,30 +797,25 @@ where
 
         // Once application is stopped cast receiver device should emit status update event, or
         // invalid request event if provided session id is not valid.
-        self.message_manager.receive_find_map(|message| {
-            if !self.can_handle(message) {
-                return Ok(None);
-            }
+        self.message_manager
+            .receive_find_map_with_timeout(timeout, |message| {
+                if !self.can_handle(message) {
+                    return Ok(None);
+                }
 
-            match self.parse(message)? {
-                ReceiverResponse::Status(status) => {
+                let response = self.parse(message)?;
+                if let Some(error) = response.to_cast_error(request_id) {
+                    return Err(error);
+                }
+
+                if let ReceiverResponse::Status(status) = response {
                     if status.request_id == request_id {
                         return Ok(Some(()));
                     }
                 }
-                ReceiverResponse::InvalidRequest(error) => {
-                    if error.request_id == request_id {
-                        return Err(Error::Internal(format!(
-                            "Invalid request ({}).",
-                            error.reason.unwrap_or_else(|| "Unknown".to_string())
-                        )));
-                    }
-                }
-                _ => {}
-            }
 
-            Ok(None)
-        })
+                Ok(None)
+            })
     }
 
     /// Retrieves status of the cast device receiver.
@@ -353,7 +823,24 @@ where
     /// # Return value
     ///
     /// Returned `Result` should consist of either `Status` instance or an `Error`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if status hasn't been received within `DEFAULT_TIMEOUT`.
     pub fn get_status(&self) -> Result<Status, Error> {
+        self.get_status_with_timeout(DEFAULT_TIMEOUT)
+    }
+
+    /// Same as `get_status`, but waits for the status for up to `timeout`.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - how long to wait for the status.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if status hasn't been received within `timeout`.
+    pub fn get_status_with_timeout(&self, timeout: Duration) -> Result<Status, Error> {
         let request_id = self.message_manager.generate_request_id().get();
 
         let payload = serde_json::to_string(&proxies::receiver::GetStatusRequest {
@@ -362,26 +849,118 @@ where
         })?;
 
         self.message_manager.send(CastMessage {
-            namespace: CHANNEL_NAMESPACE.to_string(),
+            namespace: namespaces::RECEIVER.to_string(),
             source: self.sender.to_string(),
             destination: self.receiver.to_string(),
             payload: CastMessagePayload::String(payload),
         })?;
 
-        self.message_manager.receive_find_map(|message| {
-            if !self.can_handle(message) {
-                return Ok(None);
-            }
+        self.message_manager
+            .receive_find_map_with_timeout(timeout, |message| {
+                if !self.can_handle(message) {
+                    return Ok(None);
+                }
 
-            let message = self.parse(message)?;
-            if let ReceiverResponse::Status(status) = message {
-                if status.request_id == request_id {
-                    return Ok(Some(status));
+                let response = self.parse(message)?;
+                if let Some(error) = response.to_cast_error(request_id) {
+                    return Err(error);
                 }
-            }
 
-            Ok(None)
-        })
+                if let ReceiverResponse::Status(status) = response {
+                    if status.request_id == request_id {
+                        self.track_volume(&status.volume);
+                        return Ok(Some(status));
+                    }
+                }
+
+                Ok(None)
+            })
+    }
+
+    /// Retrieves all applications that are currently running on the cast device. Idle device
+    /// usually reports no applications at all (or just Backdrop), which isn't an error.
+    ///
+    /// # Return value
+    ///
+    /// Running applications in the order reported by the cast device.
+    pub fn running_applications(&self) -> Result<Vec<Application>, Error> {
+        Ok(self.get_status()?.applications)
+    }
+
+    /// Checks whether specified applications are available on the cast device.
+    ///
+    /// # Arguments
+    ///
+    /// * `app_ids` - identifiers of the applications to check.
+    ///
+    /// # Return value
+    ///
+    /// Availability of every requested application keyed by application id. Applications that
+    /// device hasn't reported anything about are considered unavailable.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if availability hasn't been reported within `DEFAULT_TIMEOUT`.
+    pub fn get_app_availability(&self, app_ids: &[&str]) -> Result<HashMap<String, bool>, Error> {
+        self.get_app_availability_with_timeout(app_ids, DEFAULT_TIMEOUT)
+    }
+
+    /// Same as `get_app_availability`, but waits for the reply for up to `timeout`.
+    ///
+    /// # Arguments
+    ///
+    /// * `app_ids` - identifiers of the applications to check;
+    /// * `timeout` - how long to wait for the cast device to report availability.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if availability hasn't been reported within `timeout`.
+    pub fn get_app_availability_with_timeout(
+        &self,
+        app_ids: &[&str],
+        timeout: Duration,
+    ) -> Result<HashMap<String, bool>, Error> {
+        let request_id = self.message_manager.generate_request_id().get();
+
+        let payload = serde_json::to_string(&proxies::receiver::GetAppAvailabilityRequest {
+            typ: MESSAGE_TYPE_GET_APP_AVAILABILITY.to_string(),
+            request_id,
+            app_id: app_ids.iter().map(|app_id| app_id.to_string()).collect(),
+        })?;
+
+        self.message_manager.send(CastMessage {
+            namespace: namespaces::RECEIVER.to_string(),
+            source: self.sender.to_string(),
+            destination: self.receiver.to_string(),
+            payload: CastMessagePayload::String(payload),
+        })?;
+
+        let mut availability =
+            self.message_manager
+                .receive_find_map_with_timeout(timeout, |message| {
+                    if !self.can_handle(message) {
+                        return Ok(None);
+                    }
+
+                    let response = self.parse(message)?;
+                    if let Some(error) = response.to_cast_error(request_id) {
+                        return Err(error);
+                    }
+
+                    if let ReceiverResponse::AppAvailability(reply) = response {
+                        if reply.request_id == request_id {
+                            return Ok(Some(reply.availability));
+                        }
+                    }
+
+                    Ok(None)
+                })?;
+
+        for app_id in app_ids {
+            availability.entry(app_id.to_string()).or_insert(false);
+        }
+
+        Ok(availability)
     }
 
     /// Sets volume for the active cast device.
@@ -389,21 +968,53 @@ where
     /// # Arguments
     ///
     /// * `volume` - anything that can be converted to a valid `Volume` structure. It's possible to
-    ///              set volume level, mute/unmute state or both altogether.
+    ///   set volume level, mute/unmute state or both altogether, properties that are not provided
+    ///   are not sent and thus stay untouched.
     ///
     /// # Return value
     ///
-    /// Actual `Volume` instance returned by receiver.
+    /// Actual `Volume` instance returned by receiver, its level may slightly differ from the
+    /// requested one since cast devices quantize it (see `Volume::level`). Some receivers report
+    /// only the changed properties, use `Volume::update` to apply them to the known volume.
     ///
     /// # Errors
     ///
     /// Usually method can fail only if network connection with cast device is lost for some reason.
+    /// If volume level is requested to be changed, but cast device reports fixed volume control
+    /// type, `Error::UnsupportedOperation` is returned. Request isn't sent at all if fixed volume
+    /// control type is already known from the previous receiver status. Returns `Error::Timeout`
+    /// if volume hasn't been reported within `DEFAULT_TIMEOUT`.
     pub fn set_volume<T>(&self, volume: T) -> Result<Volume, Error>
     where
         T: Into<Volume>,
     {
-        let request_id = self.message_manager.generate_request_id().get();
+        self.set_volume_with_timeout(volume, DEFAULT_TIMEOUT)
+    }
+
+    /// Same as `set_volume`, but waits for the reported volume for up to `timeout`.
+    ///
+    /// # Arguments
+    ///
+    /// * `volume` - anything that can be converted to a valid `Volume` structure;
+    /// * `timeout` - how long to wait for the reported volume.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if volume hasn't been reported within `timeout`.
+    pub fn set_volume_with_timeout<T>(&self, volume: T, timeout: Duration) -> Result<Volume, Error>
+    where
+        T: Into<Volume>,
+    {
         let volume = volume.into();
+        let known_volume = *self.volume.borrow();
+
+        if volume.level.is_some()
+            && known_volume.and_then(|volume| volume.control_type) == Some(VolumeControlType::Fixed)
+        {
+            return Err(fixed_volume_error());
+        }
+
+        let request_id = self.message_manager.generate_request_id().get();
 
         let payload = serde_json::to_string(&proxies::receiver::SetVolumeRequest {
             typ: MESSAGE_TYPE_SET_VOLUME.to_string(),
@@ -411,110 +1022,1058 @@ where
             volume: proxies::receiver::Volume {
                 level: volume.level,
                 muted: volume.muted,
+                control_type: None,
+                step_interval: None,
             },
         })?;
 
         self.message_manager.send(CastMessage {
-            namespace: CHANNEL_NAMESPACE.to_string(),
+            namespace: namespaces::RECEIVER.to_string(),
             source: self.sender.to_string(),
             destination: self.receiver.to_string(),
             payload: CastMessagePayload::String(payload),
         })?;
 
-        self.message_manager.receive_find_map(|message| {
-            if !self.can_handle(message) {
-                return Ok(None);
-            }
+        let actual_volume =
+            self.message_manager
+                .receive_find_map_with_timeout(timeout, |message| {
+                    if !self.can_handle(message) {
+                        return Ok(None);
+                    }
 
-            let message = self.parse(message)?;
-            if let ReceiverResponse::Status(status) = message {
-                if status.request_id == request_id {
-                    return Ok(Some(status.volume));
-                }
-            }
+                    let response = self.parse(message)?;
+                    if let Some(error) = response.to_cast_error(request_id) {
+                        return Err(error);
+                    }
 
-            Ok(None)
-        })
+                    if let ReceiverResponse::Status(status) = response {
+                        if status.request_id == request_id {
+                            self.track_volume(&status.volume);
+                            return Ok(Some(status.volume));
+                        }
+                    }
+
+                    Ok(None)
+                })?;
+
+        if volume.level.is_some() && actual_volume.control_type == Some(VolumeControlType::Fixed) {
+            return Err(fixed_volume_error());
+        }
+
+        Ok(actual_volume)
+    }
+
+    /// Mutes or unmutes the active cast device.
+    ///
+    /// # Arguments
+    ///
+    /// * `muted` - whether cast device should be muted or not.
+    ///
+    /// # Return value
+    ///
+    /// Actual `Volume` instance returned by receiver.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if volume hasn't been reported within `DEFAULT_TIMEOUT`.
+    pub fn set_mute(&self, muted: bool) -> Result<Volume, Error> {
+        self.set_volume(muted)
+    }
+
+    /// Same as `set_mute`, but waits for the reported volume for up to `timeout`.
+    ///
+    /// # Arguments
+    ///
+    /// * `muted` - whether cast device should be muted or not;
+    /// * `timeout` - how long to wait for the reported volume.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if volume hasn't been reported within `timeout`.
+    pub fn set_mute_with_timeout(&self, muted: bool, timeout: Duration) -> Result<Volume, Error> {
+        self.set_volume_with_timeout(muted, timeout)
+    }
+
+    /// Increases volume of the active cast device by one step. Step size is the `step_interval`
+    /// reported by cast device or 0.05 if it's unknown, resulting level never exceeds 1.0.
+    ///
+    /// If cast device is muted, it's unmuted within the same `SET_VOLUME` request, just like
+    /// pressing "volume up" on a remote does.
+    ///
+    /// # Return value
+    ///
+    /// Actual `Volume` instance returned by receiver.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnsupportedOperation` if cast device has fixed volume or doesn't report its
+    /// current volume level.
+    pub fn volume_up(&self) -> Result<Volume, Error> {
+        self.step_volume(1.0)
+    }
+
+    /// Decreases volume of the active cast device by one step. Step size is the `step_interval`
+    /// reported by cast device or 0.05 if it's unknown, resulting level never goes below 0.0.
+    ///
+    /// Mute state is left untouched, so muted device stays muted.
+    ///
+    /// # Return value
+    ///
+    /// Actual `Volume` instance returned by receiver.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::UnsupportedOperation` if cast device has fixed volume or doesn't report its
+    /// current volume level.
+    pub fn volume_down(&self) -> Result<Volume, Error> {
+        self.step_volume(-1.0)
+    }
+
+    /// Changes volume level by `direction` steps relative to the level reported in the current
+    /// receiver status.
+    fn step_volume(&self, direction: f32) -> Result<Volume, Error> {
+        let volume = self.get_status()?.volume;
+
+        if volume.control_type == Some(VolumeControlType::Fixed) {
+            return Err(fixed_volume_error());
+        }
+
+        let level = volume.level.ok_or_else(|| {
+            Error::UnsupportedOperation("Cast device doesn't report its volume level.".to_string())
+        })?;
+        let step = volume
+            .step_interval
+            .filter(|step| *step > 0.0)
+            .unwrap_or(DEFAULT_VOLUME_STEP_INTERVAL);
+        let level = (level + direction * step).clamp(0.0, 1.0);
+
+        if direction > 0.0 && volume.muted == Some(true) {
+            self.set_volume((level, false))
+        } else {
+            self.set_volume(level)
+        }
     }
 
     pub fn can_handle(&self, message: &CastMessage) -> bool {
-        message.namespace == CHANNEL_NAMESPACE
+        message.namespace == namespaces::RECEIVER
     }
 
     pub fn parse(&self, message: &CastMessage) -> Result<ReceiverResponse, Error> {
-        let reply = match message.payload {
-            CastMessagePayload::String(ref payload) => {
-                serde_json::from_str::<serde_json::Value>(payload)?
+        self.parse_envelope(message)
+            .map(|(_, _, response)| response)
+    }
+
+    /// Same as `parse`, but also returns the raw JSON payload the response has been parsed from,
+    /// e.g. to log or archive exactly what the cast device has sent.
+    ///
+    /// # Return value
+    ///
+    /// Tuple of the parsed response and raw payload.
+    pub fn parse_with_raw(
+        &self,
+        message: &CastMessage,
+    ) -> Result<(ReceiverResponse, serde_json::Value), Error> {
+        channels::parse_with_raw(message, |message| self.parse(message))
+    }
+
+    /// Same as `parse`, but also returns `requestId` (if any) and message type (empty for binary
+    /// payload) of `message`, e.g. for custom dispatching and logging.
+    ///
+    /// # Return value
+    ///
+    /// Tuple of the request id, message type and parsed response.
+    pub fn parse_envelope(
+        &self,
+        message: &CastMessage,
+    ) -> Result<(Option<i64>, String, ReceiverResponse), Error> {
+        channels::parse_envelope(message, namespaces::RECEIVER, parse_response)
+    }
+
+    /// Merges `volume` reported by the cast device into the last known one, returns whether
+    /// volume level or mute state has changed (or wasn't known before).
+    fn track_volume(&self, volume: &Volume) -> bool {
+        let mut known_volume = self.volume.borrow_mut();
+
+        let previous_volume = *known_volume;
+        let current_volume = known_volume.get_or_insert(*volume);
+        current_volume.update(volume);
+
+        !previous_volume.is_some_and(|previous| previous.approx_eq(current_volume, 0.0))
+    }
+}
+
+fn fixed_volume_error() -> Error {
+    Error::UnsupportedOperation(
+        "Cast device has fixed volume, its level can't be changed.".to_string(),
+    )
+}
+
+/// Parses JSON payload of the receiver message with `message_type` into `ReceiverResponse`.
+pub(crate) fn parse_response(
+    message_type: &str,
+    reply: serde_json::Value,
+) -> Result<ReceiverResponse, Error> {
+    Ok(match message_type {
+        MESSAGE_TYPE_RECEIVER_STATUS => {
+            let status_reply: proxies::receiver::StatusReply =
+                serde_json::value::from_value(reply)?;
+
+            let status = Status {
+                request_id: status_reply.request_id,
+                applications: status_reply
+                    .status
+                    .applications
+                    .iter()
+                    .map(|app| Application {
+                        app_id: app.app_id.clone(),
+                        session_id: app.session_id.clone(),
+                        transport_id: app.transport_id.clone(),
+                        namespaces: app
+                            .namespaces
+                            .iter()
+                            .map(|ns| ns.name.clone())
+                            .collect::<Vec<String>>(),
+                        display_name: app.display_name.clone(),
+                        status_text: app.status_text.clone(),
+                        launched_from_cloud: app.launched_from_cloud,
+                        is_on_entertainment_device: app.is_on_entertainment_device,
+                    })
+                    .collect::<Vec<Application>>(),
+                is_active_input: status_reply.status.is_active_input,
+                is_stand_by: status_reply.status.is_stand_by,
+                volume: Volume::decode(&status_reply.status.volume),
+            };
+
+            ReceiverResponse::Status(status)
+        }
+        MESSAGE_TYPE_LAUNCH_ERROR => {
+            let reply: proxies::receiver::LaunchErrorReply = serde_json::value::from_value(reply)?;
+
+            ReceiverResponse::LaunchError(LaunchError {
+                request_id: reply.request_id,
+                reason: reply.reason,
+            })
+        }
+        MESSAGE_TYPE_LAUNCH_STATUS => {
+            let reply: proxies::receiver::LaunchStatusReply = serde_json::value::from_value(reply)?;
+
+            ReceiverResponse::LaunchStatus(LaunchStatus {
+                request_id: reply.launch_request_id,
+                status: reply.status,
+            })
+        }
+        MESSAGE_TYPE_INVALID_REQUEST => {
+            let reply: proxies::receiver::InvalidRequestReply =
+                serde_json::value::from_value(reply)?;
+
+            ReceiverResponse::InvalidRequest(InvalidRequest {
+                request_id: reply.request_id,
+                reason: reply.reason,
+            })
+        }
+        MESSAGE_TYPE_GET_APP_AVAILABILITY => {
+            let reply: proxies::receiver::AppAvailabilityReply =
+                serde_json::value::from_value(reply)?;
+
+            ReceiverResponse::AppAvailability(AppAvailability {
+                request_id: reply.request_id,
+                availability: reply
+                    .availability
+                    .into_iter()
+                    .map(|(app_id, availability)| (app_id, availability == APP_AVAILABLE))
+                    .collect(),
+            })
+        }
+        _ => ReceiverResponse::NotImplemented(message_type.to_string(), reply),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{channels::tests::MockTcpStream, DEFAULT_RECEIVER_ID, DEFAULT_SENDER_ID};
+
+    use super::*;
+
+    /// Builds `RECEIVER_STATUS` message sent by the receiver in reply to the request with
+    /// `request_id` (`0` for broadcasts), `status` is the status object.
+    fn receiver_status(request_id: u32, status: serde_json::Value) -> CastMessage {
+        CastMessage {
+            namespace: namespaces::RECEIVER.to_string(),
+            source: DEFAULT_RECEIVER_ID.to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String(
+                serde_json::json!({
+                    "type": "RECEIVER_STATUS",
+                    "requestId": request_id,
+                    "status": status
+                })
+                .to_string(),
+            ),
+        }
+    }
+
+    #[test]
+    fn test_launch_app_with_mode() {
+        let written = |message_manager: &MessageManager<MockTcpStream>| {
+            String::from_utf8_lossy(&message_manager.inner().write_buffer).to_string()
+        };
+
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&receiver_status(
+            1,
+            serde_json::json!({
+                "applications": [{
+                    "appId": DEFAULT_MEDIA_RECEIVER,
+                    "sessionId": "1",
+                    "transportId": "web-1"
+                }]
+            }),
+        ));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = ReceiverChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::clone(&message_manager),
+        );
+
+        let application = channel
+            .launch_app_with_mode(
+                &CastDeviceApp::DefaultMediaReceiver,
+                LaunchMode::JoinIfRunning,
+            )
+            .unwrap();
+        assert_eq!(application.session_id, "1");
+        assert!(!written(&message_manager).contains("\"LAUNCH\""));
+
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&receiver_status(
+            1,
+            serde_json::json!({
+                "applications": [{
+                    "appId": DEFAULT_MEDIA_RECEIVER,
+                    "sessionId": "1",
+                    "transportId": "web-1"
+                }]
+            }),
+        ));
+        stream.add_message(&receiver_status(
+            2,
+            serde_json::json!({ "applications": [] }),
+        ));
+        // Stale broadcast that still lists the stopped session must be ignored.
+        stream.add_message(&receiver_status(
+            0,
+            serde_json::json!({
+                "applications": [{
+                    "appId": DEFAULT_MEDIA_RECEIVER,
+                    "sessionId": "1",
+                    "transportId": "web-1"
+                }]
+            }),
+        ));
+        stream.add_message(&receiver_status(
+            3,
+            serde_json::json!({
+                "applications": [{
+                    "appId": DEFAULT_MEDIA_RECEIVER,
+                    "sessionId": "2",
+                    "transportId": "web-2"
+                }]
+            }),
+        ));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = ReceiverChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::clone(&message_manager),
+        );
+
+        let application = channel
+            .launch_app_with_mode(
+                &CastDeviceApp::DefaultMediaReceiver,
+                LaunchMode::ForceRelaunch,
+            )
+            .unwrap();
+        assert_eq!(application.session_id, "2");
+        assert_eq!(application.transport_id, "web-2");
+
+        let written = written(&message_manager);
+        assert!(written.contains("\"STOP\""));
+        assert!(written.contains("\"LAUNCH\""));
+    }
+
+    #[test]
+    fn test_launch_app_already_running() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&receiver_status(
+            0,
+            serde_json::json!({
+                "applications": [{
+                    "appId": BACKDROP,
+                    "sessionId": "session-1",
+                    "transportId": "web-1"
+                }],
+                "volume": { "level": 1.0, "muted": false }
+            }),
+        ));
+        stream.add_message(&receiver_status(
+            0,
+            serde_json::json!({
+                "applications": [{
+                    "appId": DEFAULT_MEDIA_RECEIVER,
+                    "sessionId": "session-1",
+                    "transportId": "web-1"
+                }],
+                "volume": { "level": 1.0, "muted": false }
+            }),
+        ));
+
+        let channel = ReceiverChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::new(MessageManager::new(stream)),
+        );
+
+        let application = channel
+            .launch_app(&CastDeviceApp::DefaultMediaReceiver)
+            .unwrap();
+
+        assert_eq!(application.app_id, DEFAULT_MEDIA_RECEIVER);
+        assert_eq!(application.transport_id, "web-1");
+    }
+
+    #[test]
+    fn test_launch_default_media_receiver() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&receiver_status(
+            1,
+            serde_json::json!({
+                "applications": [{
+                    "appId": DEFAULT_MEDIA_RECEIVER,
+                    "sessionId": "session-1",
+                    "transportId": "web-1"
+                }],
+                "volume": { "level": 1.0, "muted": false }
+            }),
+        ));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = ReceiverChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::clone(&message_manager),
+        );
+
+        let application = channel.launch_default_media_receiver().unwrap();
+
+        assert_eq!(application.app_id, DEFAULT_MEDIA_RECEIVER);
+        assert_eq!(application.transport_id, "web-1");
+
+        let written = String::from_utf8_lossy(&message_manager.inner().write_buffer).to_string();
+        assert!(written.contains("\"appId\":\"CC1AD845\""));
+    }
+
+    #[test]
+    fn test_get_app_availability() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&CastMessage {
+            namespace: namespaces::RECEIVER.to_string(),
+            source: DEFAULT_RECEIVER_ID.to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String(
+                serde_json::json!({
+                    "requestId": 1,
+                    "responseType": "GET_APP_AVAILABILITY",
+                    "availability": {
+                        "CC1AD845": "APP_AVAILABLE",
+                        "233637DE": "APP_UNAVAILABLE"
+                    }
+                })
+                .to_string(),
+            ),
+        });
+
+        let channel = ReceiverChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::new(MessageManager::new(stream)),
+        );
+
+        let availability = channel
+            .get_app_availability(&["CC1AD845", "233637DE", "E8C28D3C"])
+            .unwrap();
+
+        assert_eq!(availability.get("CC1AD845"), Some(&true));
+        assert_eq!(availability.get("233637DE"), Some(&false));
+        assert_eq!(availability.get("E8C28D3C"), Some(&false));
+    }
+
+    #[test]
+    fn test_set_volume_fixed_control_type() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&receiver_status(
+            1,
+            serde_json::json!({
+                "volume": {
+                    "level": 1.0,
+                    "muted": false,
+                    "controlType": "fixed",
+                    "stepInterval": 0.05
+                }
+            }),
+        ));
+
+        let channel = ReceiverChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::new(MessageManager::new(stream)),
+        );
+
+        let result = channel.set_volume(0.5);
+
+        assert!(matches!(result, Err(Error::UnsupportedOperation(_))));
+
+        // Fixed volume is known now, so the next request isn't even sent.
+        let written = channel.message_manager.inner().write_buffer.len();
+
+        assert!(matches!(
+            channel.set_volume(0.25),
+            Err(Error::UnsupportedOperation(_))
+        ));
+        assert_eq!(channel.message_manager.inner().write_buffer.len(), written);
+    }
+
+    #[test]
+    fn test_running_application() {
+        let message = receiver_status(
+            1,
+            serde_json::json!({
+                "applications": [{
+                    "appId": BACKDROP,
+                    "sessionId": "session-1",
+                    "transportId": "web-1",
+                    "displayName": "Backdrop",
+                    "statusText": ""
+                }, {
+                    "appId": DEFAULT_MEDIA_RECEIVER,
+                    "sessionId": "session-2",
+                    "transportId": "web-2",
+                    "displayName": "Default Media Receiver",
+                    "namespaces": [{ "name": "urn:x-cast:com.google.cast.media" }],
+                    "statusText": "Ready To Cast"
+                }],
+                "volume": { "level": 1.0, "muted": false }
+            }),
+        );
+        let channel = ReceiverChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::new(MessageManager::new(MockTcpStream::new())),
+        );
+
+        let status = match channel.parse(&message).unwrap() {
+            ReceiverResponse::Status(status) => status,
+            response => panic!("expected Status, but got {:?}", response),
+        };
+        let app = status.running_application(DEFAULT_MEDIA_RECEIVER).unwrap();
+
+        assert_eq!(app.app_id(), DEFAULT_MEDIA_RECEIVER);
+        assert_eq!(app.session_id(), "session-2");
+        assert_eq!(app.transport_id(), "web-2");
+        assert_eq!(app.display_name(), "Default Media Receiver");
+        assert_eq!(app.status_text(), "Ready To Cast");
+        assert!(status.is_app_running(BACKDROP));
+        assert!(!status.is_app_running(YOUTUBE));
+        assert_eq!(app.namespaces(), ["urn:x-cast:com.google.cast.media"]);
+        assert!(app.supports_namespace(namespaces::MEDIA));
+        assert!(!app.supports_namespace(namespaces::MULTIZONE));
+        assert!(status.running_application("unknown").is_none());
+    }
+
+    #[test]
+    fn test_launch_app_with_params() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&receiver_status(
+            1,
+            serde_json::json!({
+                "applications": [{
+                    "appId": "ABCD1234",
+                    "sessionId": "session-1",
+                    "transportId": "web-1"
+                }],
+                "volume": { "level": 1.0, "muted": false }
+            }),
+        ));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = ReceiverChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::clone(&message_manager),
+        );
+
+        let app = channel
+            .launch_app_with_params(
+                &CastDeviceApp::Custom("ABCD1234".to_string()),
+                serde_json::json!({ "contentId": "episode-42" }),
+            )
+            .unwrap();
+        assert_eq!(app.app_id, "ABCD1234");
+
+        let written = message_manager.inner().write_buffer.clone();
+        let written = String::from_utf8_lossy(&written);
+        assert!(written.contains("\"appParams\":{\"contentId\":\"episode-42\"}"));
+    }
+
+    #[test]
+    fn test_launch_app_not_found() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&CastMessage {
+            namespace: namespaces::RECEIVER.to_string(),
+            source: DEFAULT_RECEIVER_ID.to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String(
+                r#"{"launchRequestId":1,"status":"USER_PENDING_AUTHORIZATION","type":"LAUNCH_STATUS"}"#
+                    .to_string(),
+            ),
+        });
+        stream.add_message(&CastMessage {
+            namespace: namespaces::RECEIVER.to_string(),
+            source: DEFAULT_RECEIVER_ID.to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String(
+                r#"{"reason":"NOT_FOUND","requestId":1,"type":"LAUNCH_ERROR"}"#.to_string(),
+            ),
+        });
+        let channel = ReceiverChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::new(MessageManager::new(stream)),
+        );
+
+        match channel.launch_app(&CastDeviceApp::Custom("ABCD1234".to_string())) {
+            Err(Error::CastError {
+                type_,
+                reason,
+                request_id,
+            }) => {
+                assert_eq!(type_, "LAUNCH_ERROR");
+                assert_eq!(reason.as_deref(), Some(LAUNCH_ERROR_REASON_NOT_FOUND));
+                assert_eq!(request_id, Some(1));
             }
-            _ => {
-                return Err(Error::Internal(
-                    "Binary payload is not supported!".to_string(),
-                ))
+            result => panic!("expected Error::CastError, but got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_launch_app_error_without_request_id() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&CastMessage {
+            namespace: namespaces::RECEIVER.to_string(),
+            source: DEFAULT_RECEIVER_ID.to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String(
+                r#"{"reason":"CANCELLED","type":"LAUNCH_ERROR"}"#.to_string(),
+            ),
+        });
+        let channel = ReceiverChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::new(MessageManager::new(stream)),
+        );
+
+        match channel.launch_app(&CastDeviceApp::YouTube) {
+            Err(Error::CastError {
+                reason, request_id, ..
+            }) => {
+                assert_eq!(reason.as_deref(), Some("CANCELLED"));
+                assert_eq!(request_id, None);
             }
+            result => panic!("expected Error::CastError, but got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_volume_approx_eq() {
+        let reported = Volume {
+            level: Some(0.501_960_8),
+            muted: Some(false),
+            control_type: Some(VolumeControlType::Attenuation),
+            step_interval: Some(0.05),
         };
 
-        let message_type = reply
-            .as_object()
-            .and_then(|object| object.get("type"))
-            .and_then(|property| property.as_str())
-            .unwrap_or("")
-            .to_string();
-
-        let response = match message_type.as_ref() {
-            MESSAGE_TYPE_RECEIVER_STATUS => {
-                let status_reply: proxies::receiver::StatusReply =
-                    serde_json::value::from_value(reply)?;
-
-                let status = Status {
-                    request_id: status_reply.request_id,
-                    applications: status_reply
-                        .status
-                        .applications
-                        .iter()
-                        .map(|app| Application {
-                            app_id: app.app_id.clone(),
-                            session_id: app.session_id.clone(),
-                            transport_id: app.transport_id.clone(),
-                            namespaces: app
-                                .namespaces
-                                .iter()
-                                .map(|ns| ns.name.clone())
-                                .collect::<Vec<String>>(),
-                            display_name: app.display_name.clone(),
-                            status_text: app.status_text.clone(),
-                        })
-                        .collect::<Vec<Application>>(),
-                    is_active_input: status_reply.status.is_active_input,
-                    is_stand_by: status_reply.status.is_stand_by,
-                    volume: Volume {
-                        level: status_reply.status.volume.level,
-                        muted: status_reply.status.volume.muted,
-                    },
-                };
+        assert!(reported.approx_eq(&Volume::from((0.5, false)), 1.0 / 255.0));
+        assert!(!reported.approx_eq(&Volume::from((0.5, false)), 0.001));
+        assert!(!reported.approx_eq(&Volume::from((0.5, true)), 1.0 / 255.0));
+        assert!(!reported.approx_eq(&Volume::from(false), 1.0));
+    }
+
+    #[test]
+    fn test_set_mute_partial_volume_update() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&receiver_status(
+            1,
+            serde_json::json!({ "volume": { "muted": true } }),
+        ));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = ReceiverChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::clone(&message_manager),
+        );
+
+        let volume = channel.set_mute(true).unwrap();
+
+        assert_eq!(volume.muted, Some(true));
+        assert_eq!(volume.level, None);
+        assert_eq!(volume.control_type, None);
+        assert_eq!(volume.step_interval, None);
+
+        // Only mute state is sent, level is left untouched.
+        let written = message_manager.inner().write_buffer.clone();
+        let written = String::from_utf8_lossy(&written);
+        assert!(written.contains("\"volume\":{\"muted\":true}"));
+    }
+
+    #[test]
+    fn test_set_volume_level_and_mute() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&receiver_status(
+            1,
+            serde_json::json!({ "volume": { "level": 0.25, "muted": false } }),
+        ));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = ReceiverChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::clone(&message_manager),
+        );
+
+        let mut volume = Volume {
+            level: Some(0.5),
+            muted: Some(true),
+            control_type: Some(VolumeControlType::Attenuation),
+            step_interval: Some(0.05),
+        };
+        volume.update(&channel.set_volume((0.25, false)).unwrap());
+
+        assert_eq!(volume.level, Some(0.25));
+        assert_eq!(volume.muted, Some(false));
+        assert_eq!(volume.control_type, Some(VolumeControlType::Attenuation));
+        assert_eq!(volume.step_interval, Some(0.05));
+
+        let written = message_manager.inner().write_buffer.clone();
+        let written = String::from_utf8_lossy(&written);
+        assert!(written.contains("\"volume\":{\"level\":0.25,\"muted\":false}"));
+    }
 
-                ReceiverResponse::Status(status)
+    #[test]
+    fn test_parse_status_without_volume() {
+        let message = receiver_status(0, serde_json::json!({}));
+        let channel = ReceiverChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::new(MessageManager::new(MockTcpStream::new())),
+        );
+
+        match channel.parse(&message).unwrap() {
+            ReceiverResponse::Status(status) => {
+                assert_eq!(status.volume.level, None);
+                assert_eq!(status.volume.muted, None);
             }
-            MESSAGE_TYPE_LAUNCH_ERROR => {
-                let reply: proxies::receiver::LaunchErrorReply =
-                    serde_json::value::from_value(reply)?;
+            response => panic!("expected Status, but got {:?}", response),
+        }
+    }
 
-                ReceiverResponse::LaunchError(LaunchError {
-                    request_id: reply.request_id,
-                    reason: reply.reason,
-                })
+    fn last_requested_volume(message_manager: &MessageManager<MockTcpStream>) -> serde_json::Value {
+        let written = message_manager.inner().write_buffer.clone();
+        let written = String::from_utf8_lossy(&written);
+        let start = written.rfind("\"volume\":").unwrap() + "\"volume\":".len();
+        let mut volumes = serde_json::Deserializer::from_str(&written[start..]).into_iter();
+
+        volumes.next().unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_volume_up_unmutes() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&receiver_status(
+            1,
+            serde_json::json!({ "volume": { "level": 0.5, "muted": true, "stepInterval": 0.1 } }),
+        ));
+        stream.add_message(&receiver_status(
+            2,
+            serde_json::json!({ "volume": { "level": 0.6, "muted": false, "stepInterval": 0.1 } }),
+        ));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = ReceiverChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::clone(&message_manager),
+        );
+
+        let volume = channel.volume_up().unwrap();
+        assert_eq!(volume.muted, Some(false));
+
+        let volume = last_requested_volume(&message_manager);
+        assert!((volume["level"].as_f64().unwrap() - 0.6).abs() < 1e-6);
+        assert_eq!(volume["muted"], false);
+    }
+
+    #[test]
+    fn test_volume_down_clamps_with_default_step() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&receiver_status(
+            1,
+            serde_json::json!({ "volume": { "level": 0.02, "muted": true } }),
+        ));
+        stream.add_message(&receiver_status(
+            2,
+            serde_json::json!({ "volume": { "level": 0.0, "muted": true } }),
+        ));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = ReceiverChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::clone(&message_manager),
+        );
+
+        channel.volume_down().unwrap();
+
+        let volume = last_requested_volume(&message_manager);
+        assert_eq!(volume["level"], 0.0);
+        assert!(volume.get("muted").is_none());
+    }
+
+    #[test]
+    fn test_parse_status_with_unknown_fields() {
+        let message = receiver_status(
+            0,
+            serde_json::json!({
+                "applications": [{
+                    "appId": BACKDROP,
+                    "sessionId": "session-1",
+                    "isIdleScreen": true,
+                    "launchedFromCloud": false,
+                    "universalAppId": BACKDROP
+                }],
+                "userEq": { "high_shelf": { "frequency": 4500.0 } },
+                "volume": { "level": 0.5, "muted": false, "newVolumeField": 1 }
+            }),
+        );
+        let channel = ReceiverChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::new(MessageManager::new(MockTcpStream::new())),
+        );
+
+        match channel.parse(&message).unwrap() {
+            ReceiverResponse::Status(status) => {
+                assert_eq!(status.request_id, 0);
+                assert_eq!(status.applications[0].app_id, BACKDROP);
+                assert_eq!(status.applications[0].display_name, "");
+                assert_eq!(status.volume.level, Some(0.5));
             }
-            MESSAGE_TYPE_INVALID_REQUEST => {
-                let reply: proxies::receiver::InvalidRequestReply =
-                    serde_json::value::from_value(reply)?;
+            response => panic!("expected Status, but got {:?}", response),
+        }
+    }
 
-                ReceiverResponse::InvalidRequest(InvalidRequest {
-                    request_id: reply.request_id,
-                    reason: reply.reason,
-                })
+    #[test]
+    fn test_parse_application_flags() {
+        let message = receiver_status(
+            0,
+            serde_json::json!({
+                "applications": [
+                    {
+                        "appId": YOUTUBE,
+                        "sessionId": "session-1",
+                        "launchedFromCloud": true,
+                        "isOnEntertainmentDevice": true
+                    },
+                    { "appId": BACKDROP, "sessionId": "session-2" }
+                ]
+            }),
+        );
+        let channel = ReceiverChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::new(MessageManager::new(MockTcpStream::new())),
+        );
+
+        match channel.parse(&message).unwrap() {
+            ReceiverResponse::Status(status) => {
+                assert!(status.applications[0].launched_from_cloud);
+                assert_eq!(
+                    status.applications[0].is_on_entertainment_device,
+                    Some(true)
+                );
+                assert!(!status.applications[1].launched_from_cloud);
+                assert_eq!(status.applications[1].is_on_entertainment_device, None);
             }
-            _ => ReceiverResponse::NotImplemented(message_type.to_string(), reply),
-        };
+            response => panic!("expected Status, but got {:?}", response),
+        }
+    }
+
+    #[test]
+    fn test_running_applications() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&receiver_status(
+            1,
+            serde_json::json!({
+                "applications": [{
+                    "appId": BACKDROP,
+                    "sessionId": "session-1",
+                    "transportId": "web-1"
+                }],
+                "volume": { "level": 1.0, "muted": false }
+            }),
+        ));
+        stream.add_message(&receiver_status(
+            2,
+            serde_json::json!({ "volume": { "level": 1.0, "muted": false } }),
+        ));
+        let channel = ReceiverChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::new(MessageManager::new(stream)),
+        );
+
+        let applications = channel.running_applications().unwrap();
+        assert_eq!(applications.len(), 1);
+        assert_eq!(applications[0].app_id, BACKDROP);
+
+        assert!(channel.running_applications().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_get_status_timeout() {
+        let mut stream = MockTcpStream::new();
+        stream.timeout_when_empty = true;
+        stream.add_message(&receiver_status(
+            42,
+            serde_json::json!({
+                "applications": [{
+                    "appId": BACKDROP,
+                    "sessionId": "session-1",
+                    "transportId": "web-1"
+                }],
+                "volume": { "level": 1.0, "muted": false }
+            }),
+        ));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let channel = ReceiverChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::clone(&message_manager),
+        );
+
+        match channel.get_status_with_timeout(Duration::ZERO) {
+            Err(Error::Timeout(_)) => {}
+            result => panic!("expected Error::Timeout, but got {:?}", result),
+        }
+
+        // Unrelated status that has been read while waiting is not lost.
+        let message = message_manager.receive().unwrap();
+        assert_eq!(message.namespace, namespaces::RECEIVER);
+    }
+
+    #[test]
+    fn test_timeout_on_silent_device() {
+        let timeout = Duration::from_millis(20);
+        let user_read_timeout = Some(Duration::from_secs(10));
+
+        let mut stream = MockTcpStream::new();
+        stream.timeout_when_empty = true;
+        stream.read_timeouts.borrow_mut().push(user_read_timeout);
+        let message_manager = Lrc::new(MessageManager::new(stream).with_read_timeout_control());
+        let channel = ReceiverChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::clone(&message_manager),
+        );
+
+        assert!(matches!(
+            channel.get_status_with_timeout(timeout),
+            Err(Error::Timeout(_))
+        ));
+        assert!(matches!(
+            channel.launch_app_with_timeout(&CastDeviceApp::Backdrop, timeout),
+            Err(Error::Timeout(_))
+        ));
+        assert!(matches!(
+            channel.stop_app_with_timeout("session-1", timeout),
+            Err(Error::Timeout(_))
+        ));
+        assert!(matches!(
+            channel.get_app_availability_with_timeout(&[BACKDROP], timeout),
+            Err(Error::Timeout(_))
+        ));
+        assert!(matches!(
+            channel.set_volume_with_timeout(0.5, timeout),
+            Err(Error::Timeout(_))
+        ));
+        assert!(matches!(
+            channel.set_mute_with_timeout(true, timeout),
+            Err(Error::Timeout(_))
+        ));
+
+        // Reads are bounded by the time left while waiting, user read timeout is restored after
+        // each of the six waits (hence it's seen seven times along with the initial one).
+        let stream = message_manager.inner();
+        let read_timeouts = stream.read_timeouts.borrow();
+        let (bounded, restored): (Vec<_>, Vec<_>) =
+            read_timeouts.iter().partition(|read_timeout| {
+                read_timeout.is_some_and(|read_timeout| read_timeout <= timeout)
+            });
+        assert!(!bounded.is_empty());
+        assert_eq!(restored.len(), 7);
+        assert!(restored
+            .iter()
+            .all(|&&read_timeout| read_timeout == user_read_timeout));
+        assert_eq!(read_timeouts.last().copied(), Some(user_read_timeout));
+    }
 
-        Ok(response)
+    #[test]
+    fn test_volume_changed_event() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&receiver_status(
+            1,
+            serde_json::json!({ "volume": { "level": 0.5, "muted": false } }),
+        ));
+        let channel = ReceiverChannel::new(
+            DEFAULT_SENDER_ID,
+            DEFAULT_RECEIVER_ID,
+            Lrc::new(MessageManager::new(stream)),
+        );
+
+        channel.set_volume(0.5).unwrap();
+
+        let event = |message: &CastMessage| channel.event(&channel.parse(message).unwrap());
+
+        // Unsolicited status that just echoes volume this sender has set.
+        assert!(event(&receiver_status(
+            0,
+            serde_json::json!({ "volume": { "level": 0.5, "muted": false } })
+        ))
+        .is_none());
+
+        match event(&receiver_status(
+            0,
+            serde_json::json!({ "volume": { "level": 0.2, "muted": false } }),
+        )) {
+            Some(ReceiverEvent::VolumeChanged(volume)) => assert_eq!(volume.level, Some(0.2)),
+            event => panic!("expected ReceiverEvent::VolumeChanged, but got {:?}", event),
+        }
+        assert!(event(&receiver_status(
+            0,
+            serde_json::json!({ "volume": { "level": 0.2, "muted": false } })
+        ))
+        .is_none());
+
+        match event(&receiver_status(
+            0,
+            serde_json::json!({ "volume": { "level": 0.2, "muted": true } }),
+        )) {
+            Some(ReceiverEvent::VolumeChanged(volume)) => assert_eq!(volume.muted, Some(true)),
+            event => panic!("expected ReceiverEvent::VolumeChanged, but got {:?}", event),
+        }
     }
 }
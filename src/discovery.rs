@@ -0,0 +1,191 @@
+use std::{
+    net::IpAddr,
+    time::{Duration, Instant},
+};
+
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+
+use crate::errors::Error;
+
+/// mDNS service type that cast devices advertise themselves with.
+pub const SERVICE_TYPE: &str = "_googlecast._tcp.local.";
+
+const TXT_KEY_ID: &str = "id";
+const TXT_KEY_FRIENDLY_NAME: &str = "fn";
+const TXT_KEY_MODEL: &str = "md";
+
+/// Describes the cast device discovered via mDNS.
+#[derive(Clone, Debug)]
+pub struct DiscoveredDevice {
+    /// Full mDNS name of the service instance.
+    pub fullname: String,
+    /// Unique identifier of the cast device (`id` TXT record).
+    pub id: Option<String>,
+    /// Human-readable name of the cast device, e.g. "Living Room TV" (`fn` TXT record).
+    pub friendly_name: Option<String>,
+    /// Model name of the cast device, e.g. "Chromecast" (`md` TXT record).
+    pub model: Option<String>,
+    /// Network addresses of the cast device.
+    pub addresses: Vec<IpAddr>,
+    /// Port number cast device listens to.
+    pub port: u16,
+}
+
+impl DiscoveredDevice {
+    fn from_service_info(info: &ServiceInfo) -> DiscoveredDevice {
+        let mut addresses = info
+            .get_addresses()
+            .iter()
+            .map(|address| IpAddr::V4(*address))
+            .collect::<Vec<_>>();
+        addresses.sort();
+
+        DiscoveredDevice {
+            fullname: info.get_fullname().to_string(),
+            id: info.get_property_val(TXT_KEY_ID).map(decode_txt_value),
+            friendly_name: info
+                .get_property_val(TXT_KEY_FRIENDLY_NAME)
+                .map(decode_txt_value),
+            model: info.get_property_val(TXT_KEY_MODEL).map(decode_txt_value),
+            addresses,
+            port: info.get_port(),
+        }
+    }
+}
+
+/// Browses the local network for cast devices during the `timeout` period.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// for device in rust_cast::discovery::discover(Duration::from_secs(3))? {
+///     println!("{:?} at {:?}:{}", device.friendly_name, device.addresses, device.port);
+/// }
+/// # Ok::<(), rust_cast::errors::Error>(())
+/// ```
+///
+/// # Arguments
+///
+/// * `timeout` - how long to wait for the cast devices to respond.
+///
+/// # Return value
+///
+/// List of the discovered cast devices.
+pub fn discover(timeout: Duration) -> Result<Vec<DiscoveredDevice>, Error> {
+    let mut devices: Vec<DiscoveredDevice> = vec![];
+
+    discover_with(Some(timeout), |device| {
+        devices.retain(|known| known.fullname != device.fullname);
+        devices.push(device);
+        true
+    })?;
+
+    Ok(devices)
+}
+
+/// Browses the local network for cast devices and calls `on_device` for every resolved device.
+/// The same device may be reported more than once (e.g. if its address changes).
+///
+/// # Arguments
+///
+/// * `timeout` - how long to browse for, if `None` browsing stops only once `on_device` asks for
+///   that;
+/// * `on_device` - function that is called for every resolved device, browsing stops as soon as it
+///   returns `false`.
+pub fn discover_with<F>(timeout: Option<Duration>, mut on_device: F) -> Result<(), Error>
+where
+    F: FnMut(DiscoveredDevice) -> bool,
+{
+    let mdns = ServiceDaemon::new()
+        .map_err(|err| Error::Internal(format!("Failed to create mDNS daemon: {}", err)))?;
+
+    let receiver = mdns
+        .browse(SERVICE_TYPE)
+        .map_err(|err| Error::Internal(format!("Failed to browse mDNS services: {}", err)))?;
+
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+
+    loop {
+        let event = match deadline {
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                receiver.recv_timeout(remaining).ok()
+            }
+            None => receiver.recv().ok(),
+        };
+
+        match event {
+            Some(ServiceEvent::ServiceResolved(info)) => {
+                let device = DiscoveredDevice::from_service_info(&info);
+
+                log::debug!("Discovered cast device: {:?}", device);
+
+                if !on_device(device) {
+                    break;
+                }
+            }
+            Some(_) => {}
+            // Either browsing timed out or mDNS daemon has gone away.
+            None => break,
+        }
+    }
+
+    // Shutdown failures are not interesting at this point, all we need is already collected.
+    let _ = mdns.shutdown();
+
+    Ok(())
+}
+
+/// Decodes TXT record value that may be percent-encoded (some cast devices encode non-ASCII
+/// friendly names this way). Value is returned as is if it doesn't look like a valid
+/// percent-encoded UTF-8 string.
+fn decode_txt_value(value: &str) -> String {
+    if !value.contains('%') {
+        return value.to_string();
+    }
+
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+
+    while index < bytes.len() {
+        if bytes[index] != b'%' {
+            decoded.push(bytes[index]);
+            index += 1;
+            continue;
+        }
+
+        let byte = bytes
+            .get(index + 1..index + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+
+        match byte {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => return value.to_string(),
+        }
+    }
+
+    String::from_utf8(decoded).unwrap_or_else(|_| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_txt_value() {
+        assert_eq!(decode_txt_value("Living Room"), "Living Room");
+        assert_eq!(decode_txt_value("Living%20Room"), "Living Room");
+        assert_eq!(decode_txt_value("K%C3%BCche"), "Küche");
+        assert_eq!(decode_txt_value("Küche"), "Küche");
+        assert_eq!(decode_txt_value("100%"), "100%");
+        assert_eq!(decode_txt_value("100%zz"), "100%zz");
+        assert_eq!(decode_txt_value("%FF"), "%FF");
+    }
+}
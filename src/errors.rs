@@ -1,27 +1,38 @@
+#[cfg(feature = "openssl")]
 use openssl::{error::ErrorStack, ssl::HandshakeError};
 use protobuf::Error as ProtobufError;
 use serde_json::error::Error as SerializationError;
+#[cfg(feature = "openssl")]
+use std::net::TcpStream;
 use std::{
     error::Error as StdError,
     fmt::{Display, Formatter, Result},
     io::Error as IoError,
-    net::TcpStream,
 };
 
-/// Consolidates possible error types that can occur in the OpenSSL lib.
+/// Consolidates possible error types that can occur in the TLS backend (OpenSSL or rustls).
 #[derive(Debug)]
 pub enum SslError {
     /// This variant includes everything related to the existing SSL connection.
+    #[cfg(feature = "openssl")]
     Generic(ErrorStack),
     /// This variant describes an error or intermediate state after a TLS handshake attempt.
+    #[cfg(feature = "openssl")]
     Handshake(HandshakeError<TcpStream>),
+    /// This variant includes any error that comes from rustls, including rejected certificates.
+    #[cfg(feature = "rustls")]
+    Rustls(rustls::Error),
 }
 
 impl Display for SslError {
     fn fmt(&self, f: &mut Formatter) -> Result {
         match *self {
+            #[cfg(feature = "openssl")]
             SslError::Generic(ref err) => Display::fmt(&err, f),
+            #[cfg(feature = "openssl")]
             SslError::Handshake(ref err) => Display::fmt(&err, f),
+            #[cfg(feature = "rustls")]
+            SslError::Rustls(ref err) => Display::fmt(&err, f),
         }
     }
 }
@@ -29,8 +40,12 @@ impl Display for SslError {
 impl StdError for SslError {
     fn source(&self) -> Option<&(dyn StdError + 'static)> {
         match *self {
+            #[cfg(feature = "openssl")]
             SslError::Generic(ref e) => e.source(),
+            #[cfg(feature = "openssl")]
             SslError::Handshake(ref e) => e.source(),
+            #[cfg(feature = "rustls")]
+            SslError::Rustls(ref e) => Some(e),
         }
     }
 }
@@ -49,10 +64,74 @@ pub enum Error {
     Serialization(SerializationError),
     /// Errors parsing messages (valid JSON but bad semantics)
     Parsing(String),
-    /// This variant includes any error that comes from OpenSSL.
+    /// This variant includes any error that comes from the TLS backend (OpenSSL or rustls).
     Ssl(SslError),
+    /// This variant is used when TCP connection to the cast device can't be established.
+    Connect {
+        /// Address connection has been attempted to, e.g. `192.168.1.2:8009`.
+        address: String,
+        /// Underlying network error.
+        source: IoError,
+    },
     /// Problems with given namespace
     Namespace(String),
+    /// This variant is used when channel is asked to parse message that belongs to another
+    /// namespace.
+    UnexpectedNamespace {
+        /// Namespace the channel handles, e.g. `urn:x-cast:com.google.cast.media`.
+        expected: String,
+        /// Namespace of the message.
+        actual: String,
+    },
+    /// This variant is used when expected event (e.g. response from the device) didn't happen in
+    /// time.
+    Timeout(String),
+    /// This variant is used when awaited operation has been cancelled with `StopHandle` before it
+    /// completed.
+    Cancelled,
+    /// This variant is used when requested operation is not supported by the cast device.
+    UnsupportedOperation(String),
+    /// This variant is used when incoming message declares length that exceeds the maximum message
+    /// size, so that bogus length prefix doesn't make us allocate gigabytes.
+    MessageTooLarge {
+        /// Length of the message declared by its length prefix.
+        size: usize,
+        /// Maximum allowed length of the message.
+        max_size: usize,
+    },
+    /// This variant is used when request is malformed (e.g. required field is missing) or can't be
+    /// fulfilled in the current state (e.g. seek target is out of range), so it's rejected before
+    /// being sent to the cast device.
+    InvalidRequest(String),
+    /// This variant is used when cast device explicitly rejects the request (e.g. with
+    /// `LOAD_FAILED` or `INVALID_REQUEST` reply).
+    CastError {
+        /// Type of the error reply, e.g. `LOAD_FAILED`.
+        type_: String,
+        /// Reason of the error if cast device provided it, e.g. `INVALID_MEDIA_SESSION_ID`.
+        reason: Option<String>,
+        /// Unique id of the request that has been rejected, if known.
+        request_id: Option<i64>,
+    },
+    /// This variant is used when operation kept failing with transport errors until all retry
+    /// attempts have been used up.
+    RetriesExhausted {
+        /// Number of attempts made.
+        attempts: u32,
+        /// Error the last attempt failed with.
+        last_error: Box<Error>,
+    },
+}
+
+impl Error {
+    /// Checks whether error is caused by the broken or unavailable connection to the cast device
+    /// (rather than by the device rejecting the request), so that reconnecting may help.
+    pub fn is_transport_error(&self) -> bool {
+        matches!(
+            *self,
+            Error::Io(_) | Error::Ssl(_) | Error::Connect { .. } | Error::Timeout(_)
+        )
+    }
 }
 
 impl Display for Error {
@@ -64,7 +143,40 @@ impl Display for Error {
             Error::Serialization(ref err) => Display::fmt(&err, f),
             Error::Parsing(ref message) => f.write_str(message),
             Error::Ssl(ref err) => Display::fmt(&err, f),
+            Error::Connect {
+                ref address,
+                ref source,
+            } => write!(f, "Failed to connect to {}: {}", address, source),
             Error::Namespace(ref err) => Display::fmt(&err, f),
+            Error::UnexpectedNamespace {
+                ref expected,
+                ref actual,
+            } => write!(
+                f,
+                "Expected message from {} namespace, but got one from {}",
+                expected, actual
+            ),
+            Error::Timeout(ref message) => f.write_str(message),
+            Error::Cancelled => f.write_str("Operation has been cancelled."),
+            Error::UnsupportedOperation(ref message) => f.write_str(message),
+            Error::InvalidRequest(ref message) => f.write_str(message),
+            Error::MessageTooLarge { size, max_size } => write!(
+                f,
+                "Message of {} bytes exceeds maximum size of {} bytes",
+                size, max_size
+            ),
+            Error::CastError {
+                ref type_,
+                ref reason,
+                ..
+            } => match *reason {
+                Some(ref reason) => write!(f, "Cast device replied with {} ({})", type_, reason),
+                None => write!(f, "Cast device replied with {}", type_),
+            },
+            Error::RetriesExhausted {
+                attempts,
+                ref last_error,
+            } => write!(f, "Giving up after {} attempts: {}", attempts, last_error),
         }
     }
 }
@@ -75,10 +187,19 @@ impl StdError for Error {
             Error::Io(ref err) => Some(err),
             Error::Protobuf(ref err) => Some(err),
             Error::Ssl(ref err) => Some(err),
+            Error::Connect { ref source, .. } => Some(source),
             Error::Serialization(ref err) => Some(err),
             Error::Parsing(_) => None,
             Error::Internal(_) => None,
             Error::Namespace(_) => None,
+            Error::UnexpectedNamespace { .. } => None,
+            Error::Timeout(_) => None,
+            Error::Cancelled => None,
+            Error::UnsupportedOperation(_) => None,
+            Error::InvalidRequest(_) => None,
+            Error::MessageTooLarge { .. } => None,
+            Error::CastError { .. } => None,
+            Error::RetriesExhausted { ref last_error, .. } => Some(last_error.as_ref()),
         }
     }
 }
@@ -101,14 +222,23 @@ impl From<SerializationError> for Error {
     }
 }
 
+#[cfg(feature = "openssl")]
 impl From<ErrorStack> for Error {
     fn from(err: ErrorStack) -> Error {
         Error::Ssl(SslError::Generic(err))
     }
 }
 
+#[cfg(feature = "openssl")]
 impl From<HandshakeError<TcpStream>> for Error {
     fn from(err: HandshakeError<TcpStream>) -> Error {
         Error::Ssl(SslError::Handshake(err))
     }
 }
+
+#[cfg(feature = "rustls")]
+impl From<rustls::Error> for Error {
+    fn from(err: rustls::Error) -> Error {
+        Error::Ssl(SslError::Rustls(err))
+    }
+}
@@ -1,26 +1,44 @@
 #![deny(warnings)]
 #![allow(dead_code)]
 
+#[cfg(feature = "async")]
+pub mod asynchronous;
 mod cast;
 pub mod channels;
+#[cfg(feature = "discovery")]
+pub mod discovery;
 pub mod errors;
 pub mod message_manager;
+pub mod namespaces;
+pub mod player;
+pub mod reconnect;
+pub mod tls;
 mod utils;
 
-use std::{borrow::Cow, net::TcpStream, ops::{Deref, DerefMut}};
+use std::{
+    borrow::Cow,
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    io::{Error as IoError, ErrorKind},
+    net::{TcpStream, ToSocketAddrs},
+    ops::{Deref, DerefMut},
+    time::Duration,
+};
 
-use openssl::ssl::{SslConnector, SslMethod, SslStream, SslVerifyMode};
+use tls::{TlsBackend, TlsMode, TlsStream};
 
 use channels::{
     connection::{ConnectionChannel, ConnectionResponse},
+    custom::CustomChannel,
     heartbeat::{HeartbeatChannel, HeartbeatResponse},
     media::{MediaChannel, MediaResponse},
+    multizone::{MultizoneChannel, MultizoneResponse},
     receiver::{ReceiverChannel, ReceiverResponse},
 };
 
 use errors::Error;
 
-use message_manager::{CastMessage, MessageManager};
+use message_manager::{CastMessage, MessageManager, StopHandle, DEFAULT_MAX_MESSAGE_SIZE};
 
 const DEFAULT_SENDER_ID: &str = "sender-0";
 const DEFAULT_RECEIVER_ID: &str = "receiver-0";
@@ -108,6 +126,8 @@ pub enum ChannelMessage {
     Heartbeat(HeartbeatResponse),
     /// Message to be processed by `MediaChannel`.
     Media(MediaResponse),
+    /// Message to be processed by `MultizoneChannel`.
+    Multizone(MultizoneResponse),
     /// Message to be processed by `ReceiverChannel`.
     Receiver(ReceiverResponse),
     /// Raw message is returned when built-in channels can't process it (e.g. because of unknown
@@ -115,21 +135,216 @@ pub enum ChannelMessage {
     Raw(CastMessage),
 }
 
+/// Port number cast devices listen to by default.
+pub const DEFAULT_PORT: u16 = 8009;
+
+/// Generates unique sender id following Cast convention (e.g. `sender-3f2a9c1b`). Receiver tracks
+/// virtual connections by sender id, so it should stay the same for all messages of the session,
+/// but a fresh one should be used for every new session (e.g. after reconnect), otherwise receiver
+/// may mix up the new session with the stale one.
+pub fn generate_sender_id() -> String {
+    format!(
+        "sender-{:08x}",
+        RandomState::new().build_hasher().finish() as u32
+    )
+}
+
+/// Configures and establishes connection to a cast device.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use rust_cast::{tls::TlsMode, CastDeviceBuilder};
+///
+/// let device = CastDeviceBuilder::new("192.168.1.2")
+///     .connect_timeout(Duration::from_secs(5))
+///     .tls_mode(TlsMode::AcceptAny)
+///     .connect()?;
+/// # Ok::<(), rust_cast::errors::Error>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct CastDeviceBuilder<'a> {
+    host: Cow<'a, str>,
+    port: u16,
+    connect_timeout: Option<Duration>,
+    tls_mode: TlsMode,
+    tls_backend: TlsBackend,
+    sender_id: Option<Cow<'a, str>>,
+    max_message_size: usize,
+}
+
+impl<'a> CastDeviceBuilder<'a> {
+    /// Creates builder for the cast device with the specified host name or IP address. IPv6
+    /// address can be specified either as is (`fe80::1`) or in brackets (`[fe80::1]`), link-local
+    /// address may include zone (e.g. `fe80::1%en0`).
+    pub fn new<S>(host: S) -> CastDeviceBuilder<'a>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        CastDeviceBuilder {
+            host: host.into(),
+            port: DEFAULT_PORT,
+            connect_timeout: None,
+            tls_mode: TlsMode::default(),
+            tls_backend: TlsBackend::default(),
+            sender_id: None,
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
+        }
+    }
+
+    /// Sets cast device port number, `DEFAULT_PORT` is used if not specified.
+    pub fn port(mut self, port: u16) -> CastDeviceBuilder<'a> {
+        self.port = port;
+        self
+    }
+
+    /// Sets how long to wait for the TCP connection to be established, by default it's up to the
+    /// operating system.
+    pub fn connect_timeout(mut self, timeout: Duration) -> CastDeviceBuilder<'a> {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets how the cast device certificate is verified, `TlsMode::AcceptAny` is used if not
+    /// specified since cast devices use self-signed certificates.
+    pub fn tls_mode(mut self, tls_mode: TlsMode) -> CastDeviceBuilder<'a> {
+        self.tls_mode = tls_mode;
+        self
+    }
+
+    /// Sets TLS library that performs the handshake, useful when both `openssl` and `rustls`
+    /// features are enabled. `TlsBackend::default()` is used if not specified.
+    pub fn tls_backend(mut self, tls_backend: TlsBackend) -> CastDeviceBuilder<'a> {
+        self.tls_backend = tls_backend;
+        self
+    }
+
+    /// Sets sender id all channels of the connected `CastDevice` use. If not specified, every
+    /// `connect` call generates a fresh one with `generate_sender_id`, so that id is stable within
+    /// the session, but differs between sessions.
+    pub fn sender_id<S>(mut self, sender_id: S) -> CastDeviceBuilder<'a>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        self.sender_id = Some(sender_id.into());
+        self
+    }
+
+    /// Sets maximum length of the message cast device may send (see
+    /// `MessageManager::with_max_message_size`), `DEFAULT_MAX_MESSAGE_SIZE` is used if not
+    /// specified.
+    pub fn max_message_size(mut self, max_message_size: usize) -> CastDeviceBuilder<'a> {
+        self.max_message_size = max_message_size;
+        self
+    }
+
+    /// Connects to the cast device.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Connect` if TCP connection can't be established and `Error::Ssl` if TLS
+    /// handshake fails (e.g. certificate is rejected).
+    ///
+    /// # Return value
+    ///
+    /// Instance of `CastDevice` that allows you to manage connection.
+    pub fn connect(self) -> Result<CastDevice<'a>, Error> {
+        let host = self
+            .host
+            .strip_prefix('[')
+            .and_then(|host| host.strip_suffix(']'))
+            .unwrap_or(&self.host);
+        let address = if host.contains(':') {
+            format!("[{}]:{}", host, self.port)
+        } else {
+            format!("{}:{}", host, self.port)
+        };
+
+        log::debug!(
+            "Establishing connection with cast device at {} ({:?}, {:?})...",
+            address,
+            self.tls_mode,
+            self.tls_backend
+        );
+
+        // Host is resolved with `ToSocketAddrs`, which understands zone (scope id) of link-local
+        // IPv6 addresses (e.g. `fe80::1%en0`), but zone is not a part of the TLS server name.
+        let tcp_stream = self.connect_tcp(host).map_err(|source| Error::Connect {
+            address: address.clone(),
+            source,
+        })?;
+        let tls_stream =
+            self.tls_mode
+                .connect(self.tls_backend, tls_server_name(host), tcp_stream)?;
+
+        log::debug!("Connection with {} successfully established.", address);
+
+        let sender = self
+            .sender_id
+            .unwrap_or_else(|| Cow::from(generate_sender_id()));
+
+        CastDevice::connect_to_device(
+            MessageManager::new(tls_stream)
+                .with_max_message_size(self.max_message_size)
+                .with_read_timeout_control(),
+            sender,
+        )
+    }
+
+    fn connect_tcp(&self, host: &str) -> Result<TcpStream, IoError> {
+        let timeout = match self.connect_timeout {
+            Some(timeout) => timeout,
+            None => return TcpStream::connect((host, self.port)),
+        };
+
+        let mut last_error = None;
+        for address in (host, self.port).to_socket_addrs()? {
+            match TcpStream::connect_timeout(&address, timeout) {
+                Ok(stream) => return Ok(stream),
+                Err(err) => last_error = Some(err),
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| {
+            IoError::new(
+                ErrorKind::InvalidInput,
+                "Host name didn't resolve to any address.",
+            )
+        }))
+    }
+}
+
+/// Strips zone (scope id) from the IPv6 address `host`, so that it can be used as TLS server name.
+fn tls_server_name(host: &str) -> &str {
+    match host.split_once('%') {
+        Some((address, _)) if address.contains(':') => address,
+        _ => host,
+    }
+}
+
 /// Structure that manages connection to a cast device.
 pub struct CastDevice<'a> {
-    pub message_manager: Lrc<MessageManager<SslStream<TcpStream>>>,
+    pub message_manager: Lrc<MessageManager<TlsStream>>,
+
+    /// Sender id all channels use within this session.
+    sender: Cow<'a, str>,
 
     /// Channel that manages connection responses/requests.
-    pub connection: ConnectionChannel<'a, SslStream<TcpStream>>,
+    pub connection: ConnectionChannel<'a, TlsStream>,
 
     /// Channel that allows connection to stay alive (via ping-pong requests/responses).
-    pub heartbeat: HeartbeatChannel<'a, SslStream<TcpStream>>,
+    pub heartbeat: HeartbeatChannel<'a, TlsStream>,
 
     /// Channel that manages various media stuff.
-    pub media: MediaChannel<'a, SslStream<TcpStream>>,
+    pub media: MediaChannel<'a, TlsStream>,
+
+    /// Channel that manages speaker groups (members and their volume).
+    pub multizone: MultizoneChannel<'a, TlsStream>,
 
     /// Channel that manages receiving platform (e.g. Chromecast).
-    pub receiver: ReceiverChannel<'a, SslStream<TcpStream>>,
+    pub receiver: ReceiverChannel<'a, TlsStream>,
 }
 
 impl<'a> CastDevice<'a> {
@@ -161,18 +376,10 @@ impl<'a> CastDevice<'a> {
     where
         S: Into<Cow<'a, str>>,
     {
-        let host = host.into();
-
-        log::debug!(
-            "Establishing connection with cast device at {}:{}...",
-            host,
-            port
-        );
-
-        let connector = SslConnector::builder(SslMethod::tls())?.build();
-        let tcp_stream = TcpStream::connect((host.as_ref(), port))?;
-
-        CastDevice::connect_to_device(connector.connect(host.as_ref(), tcp_stream)?)
+        CastDeviceBuilder::new(host)
+            .port(port)
+            .tls_mode(TlsMode::Verify)
+            .connect()
     }
 
     /// Connects to the cast device using host name and port _without_ host verification. Use on
@@ -204,31 +411,14 @@ impl<'a> CastDevice<'a> {
     where
         S: Into<Cow<'a, str>>,
     {
-        let host = host.into();
-
-        log::debug!(
-            "Establishing non-verified connection with cast device at {}:{}...",
-            host,
-            port
-        );
-
-        let mut builder = SslConnector::builder(SslMethod::tls())?;
-        builder.set_verify(SslVerifyMode::NONE);
-
-        let connector = builder.build();
-        let tcp_stream = TcpStream::connect((host.as_ref(), port))?;
-
-        log::debug!(
-            "Connection with {}:{} successfully established.",
-            host,
-            port
-        );
-
-        CastDevice::connect_to_device(connector.connect(host.as_ref(), tcp_stream)?)
+        CastDeviceBuilder::new(host)
+            .port(port)
+            .tls_mode(TlsMode::AcceptAny)
+            .connect()
     }
 
     /// Waits for any message returned by cast device (e.g. Chromecast) and returns its parsed
-    /// version.
+    /// version. PING messages are answered with PONG automatically.
     ///
     /// # Examples
     ///
@@ -240,7 +430,7 @@ impl<'a> CastDevice<'a> {
     ///
     /// match cast_device.receive() {
     ///     Ok(ChannelMessage::Connection(res)) => log::debug!("Connection message: {:?}", res),
-    ///     Ok(ChannelMessage::Heartbeat(_)) => cast_device.heartbeat.pong()?,
+    ///     Ok(ChannelMessage::Heartbeat(res)) => log::debug!("Heartbeat message: {:?}", res),
     ///     Ok(_) => {},
     ///     Err(err) => log::error!("Error occurred while receiving message {}", err)
     /// }
@@ -255,8 +445,165 @@ impl<'a> CastDevice<'a> {
     ///
     /// Parsed channel message.
     pub fn receive(&self) -> Result<ChannelMessage, Error> {
-        let cast_message = self.message_manager.receive()?;
+        self.dispatch(self.message_manager.receive()?)
+    }
+
+    /// Waits for the incoming messages and passes their parsed versions to `handler` until `stop`
+    /// handle is triggered or an error occurs (see `MessageManager::run_event_loop` for details).
+    /// Consider setting read timeout with `set_read_timeout` so that loop can notice stop request
+    /// even if cast device doesn't send anything.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rust_cast::{message_manager::StopHandle, ChannelMessage};
+    ///
+    /// # use rust_cast::CastDevice;
+    /// # let cast_device = CastDevice::connect_without_host_verification("192.168.1.2", 8009)?;
+    /// let stop = StopHandle::new();
+    ///
+    /// cast_device.run_event_loop(&stop, |message| {
+    ///     match message {
+    ///         ChannelMessage::Heartbeat(res) => log::debug!("Heartbeat message: {:?}", res),
+    ///         ChannelMessage::Media(res) => log::debug!("Media message: {:?}", res),
+    ///         _ => {}
+    ///     }
+    ///     Ok(())
+    /// })?;
+    /// # Ok::<(), rust_cast::errors::Error>(())
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `stop` - handle that stops the loop once triggered;
+    /// * `handler` - function that is called for every incoming message, loop stops if it fails.
+    pub fn run_event_loop<F>(&self, stop: &StopHandle, mut handler: F) -> Result<(), Error>
+    where
+        F: FnMut(ChannelMessage) -> Result<(), Error>,
+    {
+        self.message_manager
+            .run_event_loop(stop, |message| handler(self.dispatch(message)?))
+    }
 
+    /// Creates channel that exchanges JSON messages in the custom `namespace` of the receiver
+    /// application. Incoming messages in that namespace are returned as `ChannelMessage::Raw` by
+    /// `receive` and can be parsed with `CustomChannel::parse`.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rust_cast::CastDevice;
+    /// # let cast_device = CastDevice::connect_without_host_verification("192.168.1.2", 8009)?;
+    /// let channel = cast_device.custom_channel("urn:x-cast:com.example.app");
+    /// channel.send_json("web-1", &serde_json::json!({ "command": "next" }))?;
+    /// # Ok::<(), rust_cast::errors::Error>(())
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - custom namespace that should start with `urn:x-cast:`.
+    pub fn custom_channel<S>(&self, namespace: S) -> CustomChannel<'a, TlsStream>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        CustomChannel::new(
+            namespace.into(),
+            self.sender.clone(),
+            Lrc::clone(&self.message_manager),
+        )
+    }
+
+    /// Returns sender id all channels use within this session, see `CastDeviceBuilder::sender_id`.
+    pub fn sender_id(&self) -> &str {
+        &self.sender
+    }
+
+    /// Sets the read timeout of the connection with the cast device, so that `receive` (and any
+    /// other call waiting for the device reply) fails with `Error::Timeout` instead of blocking
+    /// forever when device goes away.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::time::Duration;
+    ///
+    /// # use rust_cast::CastDevice;
+    /// # let cast_device = CastDevice::connect_without_host_verification("192.168.1.2", 8009)?;
+    /// cast_device.set_read_timeout(Some(Duration::from_secs(10)))?;
+    /// # Ok::<(), rust_cast::errors::Error>(())
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - read timeout, `None` means that reads block indefinitely.
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> Result<(), Error> {
+        self.message_manager.set_read_timeout(timeout)
+    }
+
+    /// Gracefully shuts the session down: optionally stops the applications this sender is
+    /// connected to, closes every established virtual connection (the one to the receiver goes
+    /// last) and then shuts down the socket. Shutdown is best-effort, so it continues after a
+    /// failed step.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use rust_cast::CastDevice;
+    /// # let cast_device = CastDevice::connect_without_host_verification("192.168.1.2", 8009)?;
+    /// for error in cast_device.shutdown(true) {
+    ///     eprintln!("Shutdown step failed: {}", error);
+    /// }
+    /// # Ok::<(), rust_cast::errors::Error>(())
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `stop_app` - whether to send receiver `STOP` for every running application whose
+    ///   transport is connected.
+    ///
+    /// # Return value
+    ///
+    /// Errors of all the failed steps, empty if shutdown went fine.
+    pub fn shutdown(&self, stop_app: bool) -> Vec<Error> {
+        let mut errors = vec![];
+
+        if stop_app {
+            match self.receiver.running_applications() {
+                Ok(applications) => {
+                    for application in applications.iter().filter(|application| {
+                        self.connection.is_connected(&application.transport_id)
+                    }) {
+                        if let Err(err) = self.receiver.stop_app(application.session_id.as_str()) {
+                            errors.push(err);
+                        }
+                    }
+                }
+                Err(err) => errors.push(err),
+            }
+        }
+
+        let mut destinations = self.connection.connected_destinations();
+        destinations.sort_by_key(|destination| destination == DEFAULT_RECEIVER_ID);
+
+        for destination in destinations {
+            if let Err(err) = self.connection.disconnect(destination) {
+                errors.push(err);
+            }
+        }
+
+        if let Err(err) = self.message_manager.close() {
+            errors.push(err);
+        }
+
+        errors
+    }
+
+    /// Parses `cast_message` with the channel that can handle it.
+    ///
+    /// # Arguments
+    ///
+    /// * `cast_message` - message received from the cast device.
+    fn dispatch(&self, cast_message: CastMessage) -> Result<ChannelMessage, Error> {
         if self.connection.can_handle(&cast_message) {
             return Ok(ChannelMessage::Connection(
                 self.connection.parse(&cast_message)?,
@@ -264,55 +611,77 @@ impl<'a> CastDevice<'a> {
         }
 
         if self.heartbeat.can_handle(&cast_message) {
-            return Ok(ChannelMessage::Heartbeat(
-                self.heartbeat.parse(&cast_message)?,
-            ));
+            let response = self.heartbeat.parse(&cast_message)?;
+            if let HeartbeatResponse::Ping = response {
+                self.heartbeat.pong(cast_message.source)?;
+            }
+
+            return Ok(ChannelMessage::Heartbeat(response));
         }
 
         if self.media.can_handle(&cast_message) {
             return Ok(ChannelMessage::Media(self.media.parse(&cast_message)?));
         }
 
-        if self.receiver.can_handle(&cast_message) {
-            return Ok(ChannelMessage::Receiver(
-                self.receiver.parse(&cast_message)?,
+        if self.multizone.can_handle(&cast_message) {
+            return Ok(ChannelMessage::Multizone(
+                self.multizone.parse(&cast_message)?,
             ));
         }
 
+        if self.receiver.can_handle(&cast_message) {
+            let response = self.receiver.parse(&cast_message)?;
+            self.receiver.event(&response);
+
+            return Ok(ChannelMessage::Receiver(response));
+        }
+
         Ok(ChannelMessage::Raw(cast_message))
     }
 
-    /// Connects to the cast device using provided ssl stream.
+    /// Connects to the cast device using provided message manager.
     ///
     /// # Arguments
     ///
-    /// * `ssl_stream` - SSL Stream for the TCP connection established with the device.
+    /// * `message_manager` - `MessageManager` that talks over the TLS connection established with
+    ///   the device;
+    /// * `sender` - sender id all channels should use.
     ///
     /// # Return value
     ///
     /// Instance of `CastDevice` that allows you to manage connection.
-    fn connect_to_device(ssl_stream: SslStream<TcpStream>) -> Result<CastDevice<'a>, Error> {
-        let message_manager_rc = Lrc::new(MessageManager::new(ssl_stream));
+    fn connect_to_device(
+        message_manager: MessageManager<TlsStream>,
+        sender: Cow<'a, str>,
+    ) -> Result<CastDevice<'a>, Error> {
+        let message_manager_rc = Lrc::new(message_manager);
 
         let heartbeat = HeartbeatChannel::new(
-            DEFAULT_SENDER_ID,
-            DEFAULT_RECEIVER_ID,
+            sender.clone(),
+            Cow::from(DEFAULT_RECEIVER_ID),
             Lrc::clone(&message_manager_rc),
         );
-        let connection = ConnectionChannel::new(DEFAULT_SENDER_ID, Lrc::clone(&message_manager_rc));
+        let connection = ConnectionChannel::new(sender.clone(), Lrc::clone(&message_manager_rc));
         let receiver = ReceiverChannel::new(
-            DEFAULT_SENDER_ID,
-            DEFAULT_RECEIVER_ID,
+            sender.clone(),
+            Cow::from(DEFAULT_RECEIVER_ID),
+            Lrc::clone(&message_manager_rc),
+        );
+        let media = MediaChannel::new(sender.clone(), Lrc::clone(&message_manager_rc));
+        let multizone = MultizoneChannel::new(
+            sender.clone(),
+            Cow::from(DEFAULT_RECEIVER_ID),
             Lrc::clone(&message_manager_rc),
         );
-        let media = MediaChannel::new(DEFAULT_SENDER_ID, Lrc::clone(&message_manager_rc));
 
         Ok(CastDevice {
             message_manager: message_manager_rc,
+            sender,
             heartbeat,
             connection,
             receiver,
             media,
+            multizone,
         })
     }
 }
@@ -330,4 +699,179 @@ pub(crate) mod tests {
         is_sync::<CastDevice>();
         is_send::<CastDevice>();
     }
+
+    #[test]
+    fn test_builder_connect() {
+        use std::{net::TcpListener, time::Duration};
+
+        use crate::{errors::Error, tls::tests, CastDeviceBuilder};
+
+        let (certificate, key) = tests::self_signed_certificate();
+        let port = tests::serve_once(certificate, key);
+
+        assert!(CastDeviceBuilder::new("127.0.0.1")
+            .port(port)
+            .connect_timeout(Duration::from_secs(5))
+            .connect()
+            .is_ok());
+
+        let closed_port = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+
+        match CastDeviceBuilder::new("[::1]").port(closed_port).connect() {
+            Err(Error::Connect { address, .. }) => {
+                assert_eq!(address, format!("[::1]:{}", closed_port))
+            }
+            result => panic!("expected Error::Connect, but got {:?}", result.err()),
+        }
+    }
+
+    #[test]
+    fn test_shutdown() {
+        use crate::{tls::tests, CastDeviceBuilder};
+
+        let (certificate, key) = tests::self_signed_certificate();
+        let (port, server) = tests::serve_once_and_read(certificate, key);
+
+        let cast_device = CastDeviceBuilder::new("127.0.0.1")
+            .port(port)
+            .connect()
+            .unwrap();
+        cast_device.connection.connect("receiver-0").unwrap();
+        cast_device.connection.connect("web-1").unwrap();
+
+        assert!(cast_device.shutdown(false).is_empty());
+        assert!(cast_device.connection.connected_destinations().is_empty());
+
+        let received = String::from_utf8_lossy(&server.join().unwrap()).to_string();
+        assert_eq!(received.matches("\"type\":\"CLOSE\"").count(), 2);
+    }
+
+    #[test]
+    fn test_receive_answers_ping() {
+        use crate::{
+            channels::heartbeat::HeartbeatResponse,
+            message_manager::{encode_message, CastMessage, CastMessagePayload},
+            namespaces,
+            tls::tests,
+            CastDeviceBuilder, ChannelMessage, DEFAULT_RECEIVER_ID,
+        };
+
+        let ping = CastMessage {
+            namespace: namespaces::HEARTBEAT.to_string(),
+            source: DEFAULT_RECEIVER_ID.to_string(),
+            destination: "*".to_string(),
+            payload: CastMessagePayload::String(r#"{"type":"PING"}"#.to_string()),
+        };
+
+        let (certificate, key) = tests::self_signed_certificate();
+        let (port, server) =
+            tests::serve_once_and_write(certificate, key, encode_message(&ping).unwrap());
+
+        let cast_device = CastDeviceBuilder::new("127.0.0.1")
+            .port(port)
+            .connect()
+            .unwrap();
+
+        assert!(matches!(
+            cast_device.receive(),
+            Ok(ChannelMessage::Heartbeat(HeartbeatResponse::Ping))
+        ));
+        drop(cast_device);
+
+        let received = String::from_utf8_lossy(&server.join().unwrap()).to_string();
+        assert_eq!(received.matches("\"type\":\"PONG\"").count(), 1);
+    }
+
+    #[test]
+    fn test_receive_volume_changed_event() {
+        use crate::{
+            channels::receiver::ReceiverEvent,
+            message_manager::{encode_message, CastMessage, CastMessagePayload},
+            namespaces,
+            tls::tests,
+            CastDeviceBuilder, ChannelMessage, DEFAULT_RECEIVER_ID,
+        };
+
+        let volume_status_message = |level: f32| CastMessage {
+            namespace: namespaces::RECEIVER.to_string(),
+            source: DEFAULT_RECEIVER_ID.to_string(),
+            destination: "*".to_string(),
+            payload: CastMessagePayload::String(
+                serde_json::json!({
+                    "type": "RECEIVER_STATUS",
+                    "requestId": 0,
+                    "status": { "volume": { "level": level, "muted": false } }
+                })
+                .to_string(),
+            ),
+        };
+
+        let mut data = encode_message(&volume_status_message(0.5)).unwrap();
+        data.extend(encode_message(&volume_status_message(0.5)).unwrap());
+        data.extend(encode_message(&volume_status_message(0.2)).unwrap());
+
+        let (certificate, key) = tests::self_signed_certificate();
+        let (port, _) = tests::serve_once_and_write(certificate, key, data);
+
+        let cast_device = CastDeviceBuilder::new("127.0.0.1")
+            .port(port)
+            .connect()
+            .unwrap();
+
+        // Volume isn't known before the first status, so the first one is reported as a change.
+        let mut volumes = vec![];
+        for _ in 0..3 {
+            match cast_device.receive().unwrap() {
+                ChannelMessage::Receiver(_) => {
+                    if let Some(ReceiverEvent::VolumeChanged(volume)) =
+                        cast_device.receiver.last_event()
+                    {
+                        volumes.push(volume.level)
+                    }
+                }
+                message => panic!("expected receiver message, but got {:?}", message),
+            }
+        }
+
+        assert_eq!(volumes, vec![Some(0.5), Some(0.2)]);
+    }
+
+    #[test]
+    fn test_generate_sender_id() {
+        use crate::generate_sender_id;
+
+        let sender_id = generate_sender_id();
+        assert!(sender_id.starts_with("sender-"));
+        assert_eq!(sender_id.len(), "sender-".len() + 8);
+        assert_ne!(sender_id, generate_sender_id());
+    }
+
+    #[test]
+    fn test_link_local_host() {
+        use std::net::{SocketAddr, ToSocketAddrs};
+
+        use crate::tls_server_name;
+
+        assert_eq!(tls_server_name("fe80::1%en0"), "fe80::1");
+        assert_eq!(tls_server_name("fe80::1"), "fe80::1");
+        assert_eq!(tls_server_name("192.168.1.2"), "192.168.1.2");
+
+        // Numeric zone is always valid, so the address should resolve with scope id preserved.
+        let address = ("fe80::1%1", 8009)
+            .to_socket_addrs()
+            .unwrap()
+            .next()
+            .unwrap();
+        match address {
+            SocketAddr::V6(address) => {
+                assert_eq!(address.scope_id(), 1);
+                assert_eq!(address.port(), 8009);
+            }
+            address => panic!("expected IPv6 address, but got {:?}", address),
+        }
+    }
 }
@@ -1,17 +1,54 @@
 use std::{
-    io::{Read, Write},
+    collections::HashMap,
+    io::{Error as IoError, ErrorKind, Read, Write},
+    net::{Shutdown, TcpStream},
     num::NonZeroU32,
+    ops::DerefMut,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
 };
 
+use serde::{Deserialize, Serialize};
+
 use crate::{
     cast::{
         cast_channel,
         cast_channel::cast_message::{PayloadType, ProtocolVersion},
     },
     errors::Error,
+    namespaces,
+    tls::TlsStream,
     utils, Lock,
 };
 
+/// Types of the replies cast device uses to reject requests.
+const ERROR_REPLY_TYPES: [&str; 5] = [
+    "LOAD_FAILED",
+    "LOAD_CANCELLED",
+    "INVALID_PLAYER_STATE",
+    "INVALID_REQUEST",
+    "LAUNCH_ERROR",
+];
+
+/// Namespace of the device authentication messages that carry binary protobuf payload.
+pub const DEVICE_AUTH_NAMESPACE: &str = namespaces::DEVICE_AUTH;
+
+/// Default maximum length (in bytes) of the incoming encoded message, length prefix excluded. Cast
+/// protocol itself limits messages to 64 KiB, but some receivers are known to be less strict.
+pub const DEFAULT_MAX_MESSAGE_SIZE: usize = 1024 * 1024;
+
+/// Maximum number of bytes read from the stream at once.
+pub(crate) const READ_CHUNK_LENGTH: usize = 16 * 1024;
+
+#[cfg(feature = "device_auth")]
+pub use crate::cast::cast_channel::{
+    auth_error::ErrorType as AuthErrorType, AuthChallenge, AuthError, AuthResponse,
+    DeviceAuthMessage, HashAlgorithm, SignatureAlgorithm,
+};
+
 /// Type of the payload that `CastMessage` can have.
 #[derive(Debug, Clone)]
 pub enum CastMessagePayload {
@@ -21,6 +58,58 @@ pub enum CastMessagePayload {
     Binary(Vec<u8>),
 }
 
+impl CastMessagePayload {
+    /// Returns UTF-8 string payload or `None` if payload is binary.
+    pub fn as_str(&self) -> Option<&str> {
+        match *self {
+            CastMessagePayload::String(ref payload) => Some(payload),
+            CastMessagePayload::Binary(_) => None,
+        }
+    }
+
+    /// Returns binary payload or `None` if payload is a string.
+    pub fn as_binary(&self) -> Option<&[u8]> {
+        match *self {
+            CastMessagePayload::String(_) => None,
+            CastMessagePayload::Binary(ref payload) => Some(payload),
+        }
+    }
+
+    /// Returns `type` of the JSON string payload without parsing the whole payload (see
+    /// `peek_type`), `None` if payload is binary or has no type.
+    pub fn message_type(&self) -> Option<&str> {
+        self.as_str().and_then(peek_type)
+    }
+
+    /// Returns payload as JSON value exactly as it has been sent by the cast device. Binary payload
+    /// is represented as an array of bytes, just like in `NotImplemented` channel responses.
+    ///
+    /// # Errors
+    ///
+    /// Fails if string payload isn't a valid JSON.
+    pub fn to_json(&self) -> Result<serde_json::Value, Error> {
+        match *self {
+            CastMessagePayload::String(ref payload) => Ok(serde_json::from_str(payload)?),
+            CastMessagePayload::Binary(ref payload) => Ok(serde_json::Value::from(payload.clone())),
+        }
+    }
+
+    /// Decodes binary payload of the `urn:x-cast:com.google.cast.tp.deviceauth` namespace message.
+    ///
+    /// # Errors
+    ///
+    /// Fails if payload is not binary or isn't a valid `DeviceAuthMessage` protobuf message.
+    #[cfg(feature = "device_auth")]
+    pub fn decode_device_auth(&self) -> Result<DeviceAuthMessage, Error> {
+        match *self {
+            CastMessagePayload::Binary(ref payload) => utils::from_vec(payload.clone()),
+            CastMessagePayload::String(_) => Err(Error::Internal(
+                "Device auth message should have binary payload!".to_string(),
+            )),
+        }
+    }
+}
+
 /// Base structure that represents messages that are exchanged between Receiver and Sender.
 #[derive(Debug, Clone)]
 pub struct CastMessage {
@@ -35,69 +124,302 @@ pub struct CastMessage {
     pub payload: CastMessagePayload,
 }
 
+impl CastMessage {
+    /// Returns builder that allows to construct `CastMessage` step by step.
+    pub fn builder() -> CastMessageBuilder {
+        CastMessageBuilder::default()
+    }
+}
+
+/// Builder for `CastMessage`, see `CastMessage::builder`. Namespace, source and destination are
+/// mandatory, payload defaults to an empty string.
+#[derive(Debug, Default)]
+pub struct CastMessageBuilder {
+    namespace: Option<String>,
+    source: Option<String>,
+    destination: Option<String>,
+    payload: Option<Result<CastMessagePayload, Error>>,
+}
+
+impl CastMessageBuilder {
+    /// Sets namespace of the message.
+    pub fn namespace<S: Into<String>>(mut self, namespace: S) -> Self {
+        self.namespace = Some(namespace.into());
+        self
+    }
+
+    /// Sets identifier of the application that sends the message.
+    pub fn source<S: Into<String>>(mut self, source: S) -> Self {
+        self.source = Some(source.into());
+        self
+    }
+
+    /// Sets identifier of the application the message is addressed to.
+    pub fn destination<S: Into<String>>(mut self, destination: S) -> Self {
+        self.destination = Some(destination.into());
+        self
+    }
+
+    /// Sets string payload as is.
+    pub fn payload_str<S: Into<String>>(mut self, payload: S) -> Self {
+        self.payload = Some(Ok(CastMessagePayload::String(payload.into())));
+        self
+    }
+
+    /// Sets binary payload as is, e.g. for device authentication or custom receivers that exchange
+    /// raw bytes.
+    pub fn payload_binary<B: Into<Vec<u8>>>(mut self, payload: B) -> Self {
+        self.payload = Some(Ok(CastMessagePayload::Binary(payload.into())));
+        self
+    }
+
+    /// Sets string payload to the JSON representation of `payload`. Serialization errors are
+    /// reported by `build`.
+    pub fn payload_json<T: Serialize>(mut self, payload: &T) -> Self {
+        self.payload = Some(
+            serde_json::to_string(payload)
+                .map(CastMessagePayload::String)
+                .map_err(Error::from),
+        );
+        self
+    }
+
+    /// Builds `CastMessage`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Internal` if any mandatory field is missing, or `Error::Serialization` if
+    /// JSON payload couldn't be serialized.
+    pub fn build(self) -> Result<CastMessage, Error> {
+        let missing = |field: &str| Error::Internal(format!("CastMessage {} is not set.", field));
+
+        Ok(CastMessage {
+            namespace: self.namespace.ok_or_else(|| missing("namespace"))?,
+            source: self.source.ok_or_else(|| missing("source"))?,
+            destination: self.destination.ok_or_else(|| missing("destination"))?,
+            payload: self
+                .payload
+                .unwrap_or_else(|| Ok(CastMessagePayload::String(String::new())))?,
+        })
+    }
+}
+
+/// Handle that allows to stop the event loop (see `MessageManager::run_event_loop`) or cancel the
+/// awaited request (see e.g. `MessageManager::send_and_await_cancellable`). Can be cloned and sent
+/// to another thread or captured by the message handler.
+#[derive(Clone, Debug, Default)]
+pub struct StopHandle(Arc<AtomicBool>);
+
+impl StopHandle {
+    pub fn new() -> StopHandle {
+        StopHandle::default()
+    }
+
+    /// Asks the event loop to stop (or the awaited request to be cancelled). Loop stops once it's
+    /// done with the message it's currently waiting for or processing.
+    pub fn stop(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Checks whether stop has been requested.
+    pub fn is_stopped(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Function that receives messages that arrive while `MessageManager` waits for a specific reply,
+/// see `MessageManager::set_out_of_band_handler`.
+#[cfg(feature = "thread_safe")]
+pub type MessageHandler = Box<dyn FnMut(&CastMessage) + Send>;
+
+/// Function that receives messages that arrive while `MessageManager` waits for a specific reply,
+/// see `MessageManager::set_out_of_band_handler`.
+#[cfg(not(feature = "thread_safe"))]
+pub type MessageHandler = Box<dyn FnMut(&CastMessage)>;
+
+/// Direction of the message passed to the message tap, see `MessageManager::set_message_tap`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Direction {
+    /// Message is sent to the cast device.
+    Sent,
+    /// Message is received from the cast device.
+    Received,
+}
+
+/// Function that observes every message sent or received by `MessageManager`, see
+/// `MessageManager::set_message_tap`.
+#[cfg(feature = "thread_safe")]
+pub type MessageTap = Box<dyn Fn(Direction, &CastMessage) + Send + Sync>;
+
+/// Function that observes every message sent or received by `MessageManager`, see
+/// `MessageManager::set_message_tap`.
+#[cfg(not(feature = "thread_safe"))]
+pub type MessageTap = Box<dyn Fn(Direction, &CastMessage)>;
+
+/// Streams that allow changing their read timeout, see `MessageManager::with_read_timeout_control`.
+pub trait ReadTimeout {
+    /// Returns the current read timeout, `None` means that reads block indefinitely.
+    fn read_timeout(&self) -> Result<Option<Duration>, IoError>;
+
+    /// Sets the read timeout, `None` means that reads block indefinitely.
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> Result<(), IoError>;
+}
+
+impl ReadTimeout for TcpStream {
+    fn read_timeout(&self) -> Result<Option<Duration>, IoError> {
+        TcpStream::read_timeout(self)
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> Result<(), IoError> {
+        TcpStream::set_read_timeout(self, timeout)
+    }
+}
+
+impl ReadTimeout for TlsStream {
+    fn read_timeout(&self) -> Result<Option<Duration>, IoError> {
+        self.get_ref().read_timeout()
+    }
+
+    fn set_read_timeout(&self, timeout: Option<Duration>) -> Result<(), IoError> {
+        self.get_ref().set_read_timeout(timeout)
+    }
+}
+
+/// Getter and setter of the stream read timeout, see `ReadTimeout`.
+type ReadTimeoutControl<S> = (
+    fn(&S) -> Result<Option<Duration>, IoError>,
+    fn(&S, Option<Duration>) -> Result<(), IoError>,
+);
+
 /// Static structure that is responsible for (de)serializing and sending/receiving Cast protocol
 /// messages.
+///
+/// Messages that arrive while a specific reply is awaited (e.g. `RECEIVER_STATUS` with volume
+/// change pushed while `LOAD` reply is expected) are never dropped: they are either placed into
+/// internal message buffer, or passed to the out-of-band handler if one is set. In both cases
+/// messages keep the order they have been received in and are buffered (or handled) before the
+/// awaited reply is returned. Buffered messages are returned by `receive` before anything is read
+/// from the stream.
+///
+/// With `thread_safe` feature enabled `MessageManager` (and all channels built on top of it) is
+/// `Send + Sync` as long as the stream is `Send`, so it can be shared between e.g. the receiving
+/// thread and the one that sends commands. Stream is locked for the duration of the blocking read,
+/// so consider setting stream read timeout to let sending thread in while nothing arrives.
 pub struct MessageManager<S>
 where
     S: Write + Read,
 {
     message_buffer: Lock<Vec<CastMessage>>,
+    out_of_band_handler: Lock<Option<MessageHandler>>,
+    message_tap: Lock<Option<MessageTap>>,
     stream: Lock<S>,
+    frame_buffer: Lock<FrameBuffer>,
+    max_message_size: usize,
     request_counter: Lock<NonZeroU32>,
+    last_received_at: Lock<Option<Instant>>,
+    last_activity: Lock<HashMap<String, Instant>>,
+    read_timeout_control: Option<ReadTimeoutControl<S>>,
 }
 
 impl<S> MessageManager<S>
 where
     S: Write + Read,
 {
+    /// Creates `MessageManager` that exchanges length-prefixed Cast protocol frames over `stream`.
+    /// Any `Read + Write` transport works, not just the TLS connection to the cast device, which
+    /// makes it possible to drive channels with scripted in-memory streams.
     pub fn new(stream: S) -> Self {
         MessageManager {
             stream: Lock::new(stream),
+            frame_buffer: Lock::new(FrameBuffer::default()),
+            max_message_size: DEFAULT_MAX_MESSAGE_SIZE,
             message_buffer: Lock::new(vec![]),
+            out_of_band_handler: Lock::new(None),
+            message_tap: Lock::new(None),
             request_counter: Lock::new(NonZeroU32::MIN),
+            last_received_at: Lock::new(None),
+            last_activity: Lock::new(HashMap::new()),
+            read_timeout_control: None,
         }
     }
 
+    /// Lets methods that wait with a deadline (e.g. `receive_find_map_with_timeout`) bound stream
+    /// read timeout to the time left, so that they give up in time even if cast device doesn't
+    /// send anything at all. Stream read timeout is restored once the wait is over. `CastDevice`
+    /// enables it for its connection.
+    pub fn with_read_timeout_control(mut self) -> Self
+    where
+        S: ReadTimeout,
+    {
+        self.read_timeout_control = Some((S::read_timeout, S::set_read_timeout));
+        self
+    }
+
+    /// Overrides maximum length (in bytes, length prefix excluded) of the incoming message,
+    /// `DEFAULT_MAX_MESSAGE_SIZE` is used if not specified. Once length prefix of the message
+    /// exceeds it, `receive` and other methods that wait for the incoming messages fail with
+    /// `Error::MessageTooLarge` rather than trying to receive the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_message_size` - maximum length of the incoming message in bytes.
+    pub fn with_max_message_size(mut self, max_message_size: usize) -> Self {
+        self.max_message_size = max_message_size;
+        self
+    }
+
     /// Sends `message` to the Cast Device.
     ///
     /// # Arguments
     ///
     /// * `message` - `CastMessage` instance to be sent to the Cast Device.
     pub fn send(&self, message: CastMessage) -> Result<(), Error> {
-        let mut raw_message = cast_channel::CastMessage::new();
-
-        raw_message.set_protocol_version(ProtocolVersion::CASTV2_1_0);
-
-        raw_message.set_namespace(message.namespace);
-        raw_message.set_source_id(message.source);
-        raw_message.set_destination_id(message.destination);
-
-        match message.payload {
-            CastMessagePayload::String(payload) => {
-                raw_message.set_payload_type(PayloadType::STRING);
-                raw_message.set_payload_utf8(payload);
-            }
+        self.tap(Direction::Sent, &message);
+        log_message(Direction::Sent, &message);
 
-            CastMessagePayload::Binary(payload) => {
-                raw_message.set_payload_type(PayloadType::BINARY);
-                raw_message.set_payload_binary(payload);
-            }
-        };
-
-        let message_content_buffer = utils::to_vec(&raw_message)?;
-        let message_length_buffer =
-            utils::write_u32_to_buffer(message_content_buffer.len() as u32)?;
+        let destination = message.destination.clone();
+        let (_, frame) = encode_frame(message)?;
 
-        let writer = &mut *self.stream.borrow_mut();
+        self.stream.borrow_mut().write_all(&frame)?;
 
-        writer.write_all(&message_length_buffer)?;
-        writer.write_all(&message_content_buffer)?;
-
-        log::debug!("Message sent: {:?}", raw_message);
+        self.last_activity
+            .borrow_mut()
+            .insert(destination, Instant::now());
 
         Ok(())
     }
 
+    /// Sends message with binary `payload` to the Cast Device.
+    ///
+    /// # Arguments
+    ///
+    /// * `namespace` - namespace the message belongs to;
+    /// * `source` - identifier of the application that sends the message, e.g. `sender-0`;
+    /// * `destination` - identifier of the application the message is addressed to;
+    /// * `payload` - raw bytes to send.
+    pub fn send_binary<N, F, D, B>(
+        &self,
+        namespace: N,
+        source: F,
+        destination: D,
+        payload: B,
+    ) -> Result<(), Error>
+    where
+        N: Into<String>,
+        F: Into<String>,
+        D: Into<String>,
+        B: Into<Vec<u8>>,
+    {
+        self.send(
+            CastMessage::builder()
+                .namespace(namespace)
+                .source(source)
+                .destination(destination)
+                .payload_binary(payload)
+                .build()?,
+        )
+    }
+
     /// Waits for the next `CastMessage` available. Can also return existing message from the
     /// internal message buffer containing messages that have been received previously, but haven't
     /// been consumed for some reason (e.g. during `receive_find_map` call).
@@ -116,9 +438,35 @@ where
         }
     }
 
+    /// Sets the function that receives messages that arrive while `receive_find_map` or
+    /// `send_and_await` wait for a specific message, instead of placing them into the internal
+    /// message buffer. Handler is called synchronously in the order messages are received, before
+    /// the awaited message is returned. Handler must not call `set_out_of_band_handler` itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `handler` - function to call for every out-of-band message, `None` restores buffering.
+    pub fn set_out_of_band_handler(&self, handler: Option<MessageHandler>) {
+        *self.out_of_band_handler.borrow_mut() = handler;
+    }
+
+    /// Sets the function that observes every message exactly as it's sent to or received from the
+    /// stream, which is useful for dumping the protocol frames or asserting on them in tests. Sent
+    /// messages are passed right before they are written, received ones right after they are
+    /// read, before any parsing or buffering. Tap can't alter the messages and must not call
+    /// `MessageManager` methods itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `tap` - function to call for every message, `None` removes the tap.
+    pub fn set_message_tap(&self, tap: Option<MessageTap>) {
+        *self.message_tap.borrow_mut() = tap;
+    }
+
     /// Waits for the next `CastMessage` for which `f` returns valid mapped value. Messages in which
     /// `f` is not interested are placed into internal message buffer and can be later retrieved
-    /// with `receive`. This method always reads from the stream.
+    /// with `receive` (or passed to the out-of-band handler if one is set). This method always
+    /// reads from the stream.
     ///
     /// # Example
     ///
@@ -147,8 +495,8 @@ where
     /// # Arguments
     ///
     /// * `f` - Function that analyzes and maps `CastMessage` to any other type. If message doesn't
-    /// look like something `f` is looking for, then `Ok(None)` should be returned so that message
-    /// is not lost and placed into internal message buffer for later retrieval.
+    ///   look like something `f` is looking for, then `Ok(None)` should be returned so that message
+    ///   is not lost and placed into internal message buffer for later retrieval.
     ///
     /// # Return value
     ///
@@ -164,11 +512,257 @@ where
             // in the buffer, it can be later retrieved with `receive`.
             match f(&message)? {
                 Some(r) => return Ok(r),
-                None => self.message_buffer.borrow_mut().push(message),
+                None => self.keep_out_of_band(message),
+            }
+        }
+    }
+
+    /// Same as `receive_find_map`, but gives up once `timeout` elapses. Messages that have been
+    /// read meanwhile are kept in the internal message buffer (or passed to the out-of-band
+    /// handler) as usual.
+    ///
+    /// Note that timeout is only checked when a message arrives or stream read times out, so unless
+    /// read timeout control is enabled (see `with_read_timeout_control`), it's recommended to set
+    /// stream read timeout (e.g. with `CastDevice::set_read_timeout`).
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - How long to wait for the message `f` is looking for;
+    /// * `f` - Function that analyzes and maps `CastMessage` to any other type, see
+    ///   `receive_find_map`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if no matching message has been received within `timeout`.
+    pub fn receive_find_map_with_timeout<F, B>(&self, timeout: Duration, f: F) -> Result<B, Error>
+    where
+        F: Fn(&CastMessage) -> Result<Option<B>, Error>,
+    {
+        let deadline = Instant::now() + timeout;
+        let timeout_error =
+            || Error::Timeout(format!("No reply has been received within {:?}.", timeout));
+
+        let read_timeout = match self.read_timeout_control {
+            Some((read_timeout, _)) => read_timeout(&self.stream.borrow())?,
+            None => None,
+        };
+
+        let result = loop {
+            if let Err(err) = self.bound_read_timeout(deadline, read_timeout) {
+                break Err(err);
+            }
+
+            let result = self.receive_find_map(|message| {
+                if let Some(result) = f(message)? {
+                    return Ok(Some(result));
+                }
+
+                if Instant::now() >= deadline {
+                    // Message has been read already, so it shouldn't be lost along with the wait.
+                    self.keep_out_of_band(message.clone());
+                    return Err(timeout_error());
+                }
+
+                self.bound_read_timeout(deadline, read_timeout)?;
+                Ok(None)
+            });
+
+            match result {
+                Err(Error::Timeout(_)) if Instant::now() < deadline => {}
+                Err(Error::Timeout(_)) => break Err(timeout_error()),
+                result => break result,
+            }
+        };
+
+        if let Some((_, set_read_timeout)) = self.read_timeout_control {
+            let restored = set_read_timeout(&self.stream.borrow(), read_timeout);
+            if result.is_ok() {
+                restored?;
+            }
+        }
+
+        result
+    }
+
+    /// Limits stream read timeout to the time left until `deadline` (but never extends
+    /// `read_timeout` set by the user), if read timeout control is enabled.
+    fn bound_read_timeout(
+        &self,
+        deadline: Instant,
+        read_timeout: Option<Duration>,
+    ) -> Result<(), Error> {
+        let Some((_, set_read_timeout)) = self.read_timeout_control else {
+            return Ok(());
+        };
+
+        // Zero read timeout is rejected by sockets, so the last read gets at least a millisecond.
+        let left = deadline
+            .saturating_duration_since(Instant::now())
+            .max(Duration::from_millis(1));
+        let timeout = read_timeout.map_or(left, |read_timeout| read_timeout.min(left));
+
+        Ok(set_read_timeout(&self.stream.borrow(), Some(timeout))?)
+    }
+
+    /// Reads incoming messages (including the buffered ones) and passes them to `handler` until
+    /// `stop` handle is triggered or an error occurs. This is the way to process unsolicited
+    /// messages (e.g. `MEDIA_STATUS` broadcasted when playback is changed by another sender).
+    ///
+    /// Since reading from the stream blocks, stop request is noticed only once the next message
+    /// arrives, unless stream read timeout is set: `Error::Timeout` is not considered an error
+    /// here and just gives loop a chance to check `stop` handle.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use std::net::TcpStream;
+    /// # use openssl::ssl::{SslConnector, SslMethod, SslStream, SslVerifyMode};
+    /// # use rust_cast::message_manager::{MessageManager, StopHandle};
+    /// # let connector = SslConnector::builder(SslMethod::tls()).unwrap().build();
+    /// # let tcp_stream = TcpStream::connect(("0", 8009)).unwrap();
+    /// # let ssl_stream = connector.connect("0", tcp_stream).unwrap();
+    /// # let message_manager = MessageManager::new(ssl_stream);
+    /// let stop = StopHandle::new();
+    ///
+    /// message_manager.run_event_loop(&stop, |message| {
+    ///     println!("Received message in {} namespace.", message.namespace);
+    ///     Ok(())
+    /// })?;
+    /// # Ok::<(), rust_cast::errors::Error>(())
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `stop` - handle that stops the loop once triggered;
+    /// * `handler` - function that is called for every incoming message, loop stops if it fails.
+    pub fn run_event_loop<F>(&self, stop: &StopHandle, mut handler: F) -> Result<(), Error>
+    where
+        F: FnMut(CastMessage) -> Result<(), Error>,
+    {
+        while !stop.is_stopped() {
+            match self.receive() {
+                Ok(message) => handler(message)?,
+                Err(Error::Timeout(_)) => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sends `message` to the Cast Device and waits for the reply to it. Unique `requestId` is
+    /// generated and injected into the JSON payload of the `message`, and the first incoming
+    /// message with the same `requestId` is considered a reply. Messages that are not replies are
+    /// placed into internal message buffer and can be later retrieved with `receive` (or passed to
+    /// the out-of-band handler if one is set).
+    ///
+    /// If cast device rejects the request with an error reply (e.g. `LOAD_FAILED` or
+    /// `INVALID_REQUEST`), `Error::CastError` is returned.
+    ///
+    /// Note that `timeout` is checked every time a message is read, so it can't interrupt a single
+    /// blocking read from the stream (see `set_read_timeout` for that).
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - `CastMessage` instance with JSON object payload to be sent to the Cast Device;
+    /// * `timeout` - maximum time to wait for the reply.
+    ///
+    /// # Return value
+    ///
+    /// `Result` containing reply `CastMessage` or `Error`.
+    pub fn send_and_await(
+        &self,
+        message: CastMessage,
+        timeout: Duration,
+    ) -> Result<CastMessage, Error> {
+        self.send_and_await_cancellable(message, timeout, &StopHandle::new())
+    }
+
+    /// Same as `send_and_await`, but gives up waiting for the reply once `cancel` handle is
+    /// triggered, without affecting the connection. Handle is checked every time a message is read
+    /// or stream read times out, so set stream read timeout (see `set_read_timeout`) to have
+    /// cancellation noticed when nothing arrives.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - `CastMessage` instance with JSON object payload to be sent to the Cast Device;
+    /// * `timeout` - maximum time to wait for the reply;
+    /// * `cancel` - handle that cancels waiting once triggered.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Cancelled` if `cancel` handle has been triggered before the reply arrived.
+    pub fn send_and_await_cancellable(
+        &self,
+        message: CastMessage,
+        timeout: Duration,
+        cancel: &StopHandle,
+    ) -> Result<CastMessage, Error> {
+        let deadline = Instant::now() + timeout;
+        let request_id = self.generate_request_id().get();
+
+        let mut payload = match message.payload {
+            CastMessagePayload::String(ref payload) => {
+                serde_json::from_str::<serde_json::Value>(payload)?
+            }
+            _ => {
+                return Err(Error::Internal(
+                    "Binary payload can't carry request id!".to_string(),
+                ))
+            }
+        };
+
+        payload
+            .as_object_mut()
+            .ok_or_else(|| Error::Internal("Payload should be a JSON object!".to_string()))?
+            .insert("requestId".to_string(), request_id.into());
+
+        self.send(CastMessage {
+            payload: CastMessagePayload::String(serde_json::to_string(&payload)?),
+            ..message
+        })?;
+
+        let timeout_error = || {
+            Error::Timeout(format!(
+                "No reply received for request {} within {:?}.",
+                request_id, timeout
+            ))
+        };
+
+        loop {
+            match self.read() {
+                Ok(message) if extract_request_id(&message) == Some(request_id) => {
+                    return match extract_cast_error(&message) {
+                        Some(error) => Err(error),
+                        None => Ok(message),
+                    };
+                }
+                Ok(message) => self.keep_out_of_band(message),
+                Err(Error::Timeout(_)) => {}
+                Err(err) => return Err(err),
+            }
+
+            if cancel.is_stopped() {
+                return Err(Error::Cancelled);
+            }
+
+            if Instant::now() >= deadline {
+                return Err(timeout_error());
             }
         }
     }
 
+    /// Passes message that isn't the awaited one to the out-of-band handler, or buffers it if
+    /// there is no handler.
+    pub(crate) fn keep_out_of_band(&self, message: CastMessage) {
+        if let Some(ref mut handler) = *self.out_of_band_handler.borrow_mut() {
+            handler(&message);
+            return;
+        }
+
+        self.message_buffer.borrow_mut().push(message);
+    }
+
     /// Generates unique integer number that is used in some requests to map them with the response.
     ///
     /// # Return value
@@ -181,46 +775,855 @@ where
         request_id
     }
 
+    /// Returns the moment when the last message has been read from the stream, if any.
+    pub fn last_received_at(&self) -> Option<Instant> {
+        *self.last_received_at.borrow()
+    }
+
+    /// Returns the moment when the last message has been either sent to or received from `peer`
+    /// (e.g. `receiver-0` or application transport id), if any.
+    pub fn last_activity_with(&self, peer: &str) -> Option<Instant> {
+        self.last_activity.borrow().get(peer).copied()
+    }
+
     /// Drains the internal message buffer.
     pub fn drain(&self) {
         self.message_buffer.borrow_mut().clear();
     }
 
+    /// Gives access to the underlying stream, e.g. to inspect it in tests or to replace it with
+    /// `std::mem::replace`. Stream stays locked while the returned guard is alive, so drop it
+    /// before calling any other method of the `MessageManager`.
+    pub fn inner(&self) -> impl DerefMut<Target = S> + '_ {
+        self.stream.borrow_mut()
+    }
+
     /// Reads next `CastMessage` from the stream.
     ///
     /// # Return value
     ///
     /// `Result` containing parsed `CastMessage` or `Error`.
     fn read(&self) -> Result<CastMessage, Error> {
-        let mut buffer: [u8; 4] = [0; 4];
-
         let reader = &mut *self.stream.borrow_mut();
+        let mut frame_buffer = self.frame_buffer.borrow_mut();
+        let mut chunk = [0; READ_CHUNK_LENGTH];
 
-        reader.read_exact(&mut buffer)?;
+        // Partially received frame is kept across calls, hence stream read timeout (if any) is
+        // surfaced as a distinct error and reading can be resumed later.
+        let frame = loop {
+            if let Some(frame) = frame_buffer.next_frame(self.max_message_size)? {
+                break frame;
+            }
 
-        let length = utils::read_u32_from_buffer(&buffer)?;
+            match reader.read(&mut chunk) {
+                Ok(0) => {
+                    return Err(Error::Io(IoError::new(
+                        ErrorKind::UnexpectedEof,
+                        "Stream has been closed before the whole frame has been received.",
+                    )))
+                }
+                Ok(read) => frame_buffer.extend(&chunk[..read]),
+                Err(err) if err.kind() == ErrorKind::Interrupted => {}
+                Err(err) if matches!(err.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    return Err(Error::Timeout(
+                        "Timed out while waiting for the next message.".to_string(),
+                    ))
+                }
+                Err(err) => return Err(Error::Io(err)),
+            }
+        };
 
-        let mut buffer: Vec<u8> = Vec::with_capacity(length as usize);
-        let mut limited_reader = reader.take(u64::from(length));
+        let message = decode_frame(frame)?;
 
-        limited_reader.read_to_end(&mut buffer)?;
+        let now = Instant::now();
+        *self.last_received_at.borrow_mut() = Some(now);
+        self.last_activity
+            .borrow_mut()
+            .insert(message.source.clone(), now);
 
-        let raw_message = utils::from_vec::<cast_channel::CastMessage>(buffer.to_vec())?;
+        self.tap(Direction::Received, &message);
+        log_message(Direction::Received, &message);
 
-        log::debug!("Message received: {:?}", raw_message);
+        Ok(message)
+    }
 
-        Ok(CastMessage {
-            namespace: raw_message.namespace().to_string(),
-            source: raw_message.source_id().to_string(),
-            destination: raw_message.destination_id().to_string(),
-            payload: match raw_message.payload_type() {
-                PayloadType::STRING => {
-                    CastMessagePayload::String(raw_message.payload_utf8().to_string())
-                }
-                PayloadType::BINARY => {
-                    CastMessagePayload::Binary(raw_message.payload_binary().to_owned())
+    /// Passes `message` to the message tap, if there is one.
+    fn tap(&self, direction: Direction, message: &CastMessage) {
+        if let Some(ref tap) = *self.message_tap.borrow() {
+            tap(direction, message);
+        }
+    }
+}
+
+impl MessageManager<TlsStream> {
+    /// Sets the read timeout of the underlying TCP socket. Once set, `receive` and other methods
+    /// that wait for the incoming messages fail with `Error::Timeout` if no message arrives in
+    /// time, instead of blocking forever.
+    ///
+    /// # Arguments
+    ///
+    /// * `timeout` - read timeout, `None` means that reads block indefinitely.
+    ///
+    /// # Errors
+    ///
+    /// Fails if zero `timeout` is provided.
+    pub fn set_read_timeout(&self, timeout: Option<Duration>) -> Result<(), Error> {
+        Ok(self.stream.borrow().get_ref().set_read_timeout(timeout)?)
+    }
+
+    /// Shuts down the underlying TCP socket, so that any subsequent read or write fails. Messages
+    /// that should reach the cast device (e.g. `CLOSE`) must be sent before that.
+    pub fn close(&self) -> Result<(), Error> {
+        Ok(self.stream.borrow().get_ref().shutdown(Shutdown::Both)?)
+    }
+}
+
+/// Extracts `requestId` from the JSON payload of the `message`, if any.
+pub(crate) fn extract_request_id(message: &CastMessage) -> Option<u32> {
+    match message.payload {
+        CastMessagePayload::String(ref payload) => {
+            serde_json::from_str::<serde_json::Value>(payload)
+                .ok()?
+                .get("requestId")?
+                .as_u64()
+                .and_then(|id| u32::try_from(id).ok())
+        }
+        _ => None,
+    }
+}
+
+/// Returns `type` field of the JSON `payload` without building the whole JSON tree, so that it's
+/// cheap to route messages by their type and fully parse only the ones that are handled.
+///
+/// # Examples
+///
+/// ```
+/// use rust_cast::message_manager::peek_type;
+///
+/// assert_eq!(
+///     peek_type(r#"{"requestId":1,"type":"MEDIA_STATUS","status":[]}"#),
+///     Some("MEDIA_STATUS")
+/// );
+/// assert_eq!(peek_type(r#"{"requestId":1}"#), None);
+/// ```
+///
+/// # Return value
+///
+/// Returns `None` if `payload` isn't a valid JSON object, has no string `type` field or the type
+/// contains escape sequences (actual message types never do).
+pub fn peek_type(payload: &str) -> Option<&str> {
+    #[derive(Deserialize)]
+    struct Envelope<'a> {
+        #[serde(rename = "type", borrow, default)]
+        type_: Option<&'a str>,
+    }
+
+    serde_json::from_str::<Envelope>(payload).ok()?.type_
+}
+
+/// Converts error reply `message` (e.g. `LOAD_FAILED`) into `Error::CastError`, returns `None` if
+/// `message` isn't an error reply.
+fn extract_cast_error(message: &CastMessage) -> Option<Error> {
+    // Most of the messages aren't error replies, so there is no need to parse them fully.
+    if !ERROR_REPLY_TYPES.contains(&message.payload.message_type()?) {
+        return None;
+    }
+
+    let payload = serde_json::from_str::<serde_json::Value>(message.payload.as_str()?).ok()?;
+    let type_ = payload.get("type")?.as_str()?;
+
+    Some(Error::CastError {
+        type_: type_.to_string(),
+        reason: payload
+            .get("reason")
+            .and_then(|reason| reason.as_str())
+            .map(|reason| reason.to_string()),
+        request_id: payload.get("requestId").and_then(|id| id.as_i64()),
+    })
+}
+
+/// Logs `message` at debug level (and its payload at trace level) with structured `namespace`,
+/// `source`, `destination`, `message_type` and `request_id` fields. Messages that belong to one of
+/// the built-in channels are logged under the target of that channel (e.g.
+/// `rust_cast::channels::media`), so that logs can be filtered per channel.
+pub(crate) fn log_message(direction: Direction, message: &CastMessage) {
+    let target = match message.namespace.as_str() {
+        namespaces::CONNECTION => "rust_cast::channels::connection",
+        namespaces::HEARTBEAT => "rust_cast::channels::heartbeat",
+        namespaces::MEDIA => "rust_cast::channels::media",
+        namespaces::MULTIZONE => "rust_cast::channels::multizone",
+        namespaces::RECEIVER => "rust_cast::channels::receiver",
+        _ => module_path!(),
+    };
+
+    if !log::log_enabled!(target: target, log::Level::Debug) {
+        return;
+    }
+
+    let payload = message
+        .payload
+        .as_str()
+        .and_then(|payload| serde_json::from_str::<serde_json::Value>(payload).ok());
+    let field = |name: &str| payload.as_ref().and_then(|payload| payload.get(name));
+
+    let message_type = field("type")
+        .or_else(|| field("responseType"))
+        .and_then(|message_type| message_type.as_str());
+    let request_id = field("requestId").and_then(|request_id| request_id.as_u64());
+    let direction = match direction {
+        Direction::Sent => "sent",
+        Direction::Received => "received",
+    };
+
+    log::debug!(
+        target: target,
+        namespace = message.namespace.as_str(),
+        source = message.source.as_str(),
+        destination = message.destination.as_str(),
+        message_type = message_type.unwrap_or_default(),
+        request_id:? = request_id;
+        "Message {}: {} {} -> {}",
+        direction,
+        message_type.unwrap_or("<binary>"),
+        message.source,
+        message.destination
+    );
+
+    log::trace!(
+        target: target,
+        namespace = message.namespace.as_str();
+        "Message {} payload: {:?}",
+        direction,
+        message.payload
+    );
+}
+
+/// Serializes `message` into the frame that can be written to the stream: 4-byte big-endian
+/// length prefix followed by the protobuf encoded message. Raw protobuf message is returned as
+/// well for logging purposes.
+pub(crate) fn encode_frame(
+    message: CastMessage,
+) -> Result<(cast_channel::CastMessage, Vec<u8>), Error> {
+    let mut raw_message = cast_channel::CastMessage::new();
+
+    raw_message.set_protocol_version(ProtocolVersion::CASTV2_1_0);
+
+    raw_message.set_namespace(message.namespace);
+    raw_message.set_source_id(message.source);
+    raw_message.set_destination_id(message.destination);
+
+    match message.payload {
+        CastMessagePayload::String(payload) => {
+            raw_message.set_payload_type(PayloadType::STRING);
+            raw_message.set_payload_utf8(payload);
+        }
+
+        CastMessagePayload::Binary(payload) => {
+            raw_message.set_payload_type(PayloadType::BINARY);
+            raw_message.set_payload_binary(payload);
+        }
+    };
+
+    let message_content_buffer = utils::to_vec(&raw_message)?;
+    let mut frame = utils::write_u32_to_buffer(message_content_buffer.len() as u32)?;
+    frame.extend(message_content_buffer);
+
+    Ok((raw_message, frame))
+}
+
+/// Serializes `message` into the frame exactly as it's sent over the wire: 4-byte big-endian
+/// length prefix followed by the protobuf encoded message.
+///
+/// # Examples
+///
+/// ```
+/// use rust_cast::message_manager::{decode_message, encode_message, CastMessage};
+///
+/// let message = CastMessage::builder()
+///     .namespace("urn:x-cast:com.google.cast.tp.heartbeat")
+///     .source("sender-0")
+///     .destination("receiver-0")
+///     .payload_str(r#"{"type":"PING"}"#)
+///     .build()?;
+///
+/// let frame = encode_message(&message)?;
+/// let (decoded, length) = decode_message(&frame)?.unwrap();
+///
+/// assert_eq!(length, frame.len());
+/// assert_eq!(decoded.payload.as_str(), Some(r#"{"type":"PING"}"#));
+/// # Ok::<(), rust_cast::errors::Error>(())
+/// ```
+pub fn encode_message(message: &CastMessage) -> Result<Vec<u8>, Error> {
+    encode_frame(message.clone()).map(|(_, frame)| frame)
+}
+
+/// Deserializes the first frame (see `encode_message`) from the beginning of `buffer`.
+///
+/// # Return value
+///
+/// Returns decoded `CastMessage` along with the number of bytes the frame occupies in `buffer`,
+/// or `None` if `buffer` doesn't contain the whole frame yet.
+///
+/// # Errors
+///
+/// Returns `Error::MessageTooLarge` if frame is longer than `DEFAULT_MAX_MESSAGE_SIZE` and
+/// `Error::Protobuf` if frame content isn't a valid message.
+pub fn decode_message(buffer: &[u8]) -> Result<Option<(CastMessage, usize)>, Error> {
+    decode_message_with_limit(buffer, DEFAULT_MAX_MESSAGE_SIZE)
+}
+
+/// Deserializes the first frame from the beginning of `buffer` just like `decode_message` does,
+/// but rejects frames longer than `max_message_size` right after reading the length prefix.
+///
+/// # Arguments
+///
+/// * `buffer` - bytes received from the stream;
+/// * `max_message_size` - maximum length of the message (length prefix excluded) in bytes.
+pub fn decode_message_with_limit(
+    buffer: &[u8],
+    max_message_size: usize,
+) -> Result<Option<(CastMessage, usize)>, Error> {
+    let Some(prefix) = buffer.get(..4) else {
+        return Ok(None);
+    };
+
+    let length = frame_length(prefix, max_message_size)?;
+
+    match buffer.get(4..4 + length) {
+        Some(content) => Ok(Some((decode_frame(content.to_vec())?, 4 + length))),
+        None => Ok(None),
+    }
+}
+
+/// Accumulates bytes read from the stream until they form a complete frame, since frame may arrive
+/// in any number of reads.
+#[derive(Default)]
+pub(crate) struct FrameBuffer {
+    /// Bytes read from the stream that haven't been returned as frames yet.
+    buffer: Vec<u8>,
+    /// Number of bytes of the rejected (oversized) frame that are yet to be skipped.
+    skip_length: usize,
+}
+
+impl FrameBuffer {
+    /// Appends `bytes` read from the stream.
+    pub(crate) fn extend(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Removes the next complete frame from the buffer and returns its content (without length
+    /// prefix), `None` if more bytes are needed. Frame is removed before it's decoded and
+    /// oversized frame is skipped without being buffered, so that a single bad frame doesn't break
+    /// the stream.
+    pub(crate) fn next_frame(&mut self, max_message_size: usize) -> Result<Option<Vec<u8>>, Error> {
+        if self.skip_length > 0 {
+            let skipped = self.skip_length.min(self.buffer.len());
+            self.buffer.drain(..skipped);
+            self.skip_length -= skipped;
+
+            if self.skip_length > 0 {
+                return Ok(None);
+            }
+        }
+
+        let Some(prefix) = self.buffer.get(..4) else {
+            return Ok(None);
+        };
+
+        let length = match frame_length(prefix, max_message_size) {
+            Ok(length) => length,
+            Err(err) => {
+                self.skip_length = utils::read_u32_from_buffer(prefix)? as usize;
+                self.buffer.drain(..4);
+                return Err(err);
+            }
+        };
+
+        if self.buffer.len() < 4 + length {
+            return Ok(None);
+        }
+
+        Ok(Some(self.buffer.drain(..4 + length).skip(4).collect()))
+    }
+}
+
+/// Reads frame content length from 4-byte `prefix`, rejecting lengths over `max_message_size`.
+pub(crate) fn frame_length(prefix: &[u8], max_message_size: usize) -> Result<usize, Error> {
+    let length = utils::read_u32_from_buffer(prefix)? as usize;
+
+    if length > max_message_size {
+        return Err(Error::MessageTooLarge {
+            size: length,
+            max_size: max_message_size,
+        });
+    }
+
+    Ok(length)
+}
+
+/// Deserializes `CastMessage` from the frame content (without length prefix).
+pub(crate) fn decode_frame(buffer: Vec<u8>) -> Result<CastMessage, Error> {
+    let raw_message = utils::from_vec::<cast_channel::CastMessage>(buffer)?;
+
+    Ok(CastMessage {
+        namespace: raw_message.namespace().to_string(),
+        source: raw_message.source_id().to_string(),
+        destination: raw_message.destination_id().to_string(),
+        payload: match raw_message.payload_type() {
+            PayloadType::STRING => {
+                CastMessagePayload::String(raw_message.payload_utf8().to_string())
+            }
+            PayloadType::BINARY => {
+                CastMessagePayload::Binary(raw_message.payload_binary().to_owned())
+            }
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::channels::tests::MockTcpStream;
+
+    use super::*;
+
+    fn create_message(payload: &str) -> CastMessage {
+        CastMessage {
+            namespace: "urn:x-cast:com.google.cast.media".to_string(),
+            source: "receiver-0".to_string(),
+            destination: "sender-0".to_string(),
+            payload: CastMessagePayload::String(payload.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_send_and_await_buffers_unrelated_messages() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&create_message(
+            "{\"type\":\"MEDIA_STATUS\",\"requestId\":0,\"status\":[]}",
+        ));
+        stream.add_message(&create_message(
+            "{\"type\":\"MEDIA_STATUS\",\"requestId\":1,\"status\":[]}",
+        ));
+
+        let message_manager = MessageManager::new(stream);
+
+        let reply = message_manager
+            .send_and_await(
+                create_message("{\"type\":\"GET_STATUS\"}"),
+                Duration::from_secs(5),
+            )
+            .unwrap();
+        assert_eq!(extract_request_id(&reply), Some(1));
+
+        let buffered = message_manager.receive().unwrap();
+        assert_eq!(extract_request_id(&buffered), Some(0));
+    }
+
+    #[test]
+    fn test_send_and_await_passes_unrelated_messages_to_handler() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&create_message(
+            "{\"type\":\"RECEIVER_STATUS\",\"requestId\":0,\"status\":{}}",
+        ));
+        stream.add_message(&create_message(
+            "{\"type\":\"MEDIA_STATUS\",\"requestId\":0,\"status\":[]}",
+        ));
+        stream.add_message(&create_message(
+            "{\"type\":\"MEDIA_STATUS\",\"requestId\":1,\"status\":[]}",
+        ));
+
+        let message_manager = MessageManager::new(stream);
+        let received = Arc::new(std::sync::Mutex::new(vec![]));
+        let handler_received = Arc::clone(&received);
+        message_manager.set_out_of_band_handler(Some(Box::new(move |message| {
+            handler_received
+                .lock()
+                .unwrap()
+                .push(message.payload.as_str().unwrap().to_string());
+        })));
+
+        let reply = message_manager
+            .send_and_await(
+                create_message("{\"type\":\"LOAD\"}"),
+                Duration::from_secs(5),
+            )
+            .unwrap();
+        assert_eq!(extract_request_id(&reply), Some(1));
+
+        let received = received.lock().unwrap();
+        assert_eq!(received.len(), 2);
+        assert!(received[0].contains("RECEIVER_STATUS"));
+        assert!(received[1].contains("MEDIA_STATUS"));
+        assert!(message_manager.message_buffer.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_send_and_await_error_reply() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&create_message(
+            "{\"type\":\"INVALID_REQUEST\",\"requestId\":1,\"reason\":\"INVALID_COMMAND\"}",
+        ));
+
+        let message_manager = MessageManager::new(stream);
+
+        match message_manager.send_and_await(
+            create_message("{\"type\":\"PLAY\"}"),
+            Duration::from_secs(5),
+        ) {
+            Err(Error::CastError {
+                type_,
+                reason,
+                request_id,
+            }) => {
+                assert_eq!(type_, "INVALID_REQUEST");
+                assert_eq!(reason.as_deref(), Some("INVALID_COMMAND"));
+                assert_eq!(request_id, Some(1));
+            }
+            result => panic!("expected Error::CastError, but got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn test_send_and_await_cancelled() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&create_message(
+            "{\"type\":\"MEDIA_STATUS\",\"requestId\":0}",
+        ));
+        stream.timeout_when_empty = true;
+
+        let message_manager = MessageManager::new(stream);
+        let cancel = StopHandle::new();
+        cancel.stop();
+
+        let result = message_manager.send_and_await_cancellable(
+            create_message("{\"type\":\"GET_STATUS\"}"),
+            Duration::from_secs(60),
+            &cancel,
+        );
+
+        assert!(matches!(result, Err(Error::Cancelled)));
+        assert_eq!(
+            message_manager.receive().unwrap().payload.as_str(),
+            Some("{\"type\":\"MEDIA_STATUS\",\"requestId\":0}")
+        );
+    }
+
+    #[test]
+    fn test_run_event_loop() {
+        let mut stream = MockTcpStream::new();
+        stream.timeout_when_empty = true;
+        stream.add_message(&create_message("{\"type\":\"MEDIA_STATUS\",\"status\":[]}"));
+        stream.add_message(&create_message("{\"type\":\"PING\"}"));
+
+        let message_manager = MessageManager::new(stream);
+        let stop = StopHandle::new();
+
+        let mut received = vec![];
+        message_manager
+            .run_event_loop(&stop, |message| {
+                received.push(message);
+                if received.len() == 2 {
+                    stop.stop();
                 }
-            },
+                Ok(())
+            })
+            .unwrap();
+
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[1].payload.as_str(), Some("{\"type\":\"PING\"}"));
+    }
+
+    #[test]
+    fn test_receive_timeout() {
+        let mut stream = MockTcpStream::new();
+        stream.timeout_when_empty = true;
+        stream.add_message(&create_message("{\"type\":\"PING\"}"));
+
+        let message_manager = MessageManager::new(stream);
+
+        assert!(message_manager.receive().is_ok());
+        assert!(matches!(message_manager.receive(), Err(Error::Timeout(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "device_auth")]
+    fn test_decode_device_auth() {
+        let mut auth_message = DeviceAuthMessage::new();
+        auth_message.challenge = protobuf::MessageField::some(AuthChallenge::new());
+
+        let payload = CastMessagePayload::Binary(utils::to_vec(&auth_message).unwrap());
+
+        assert_eq!(payload.decode_device_auth().unwrap(), auth_message);
+        assert!(CastMessagePayload::String("{}".to_string())
+            .decode_device_auth()
+            .is_err());
+    }
+
+    #[test]
+    fn test_cast_message_builder() {
+        let message = CastMessage::builder()
+            .namespace("urn:x-cast:com.example")
+            .source("sender-0")
+            .destination("receiver-0")
+            .payload_json(&serde_json::json!({ "type": "PING" }))
+            .build()
+            .unwrap();
+
+        assert_eq!(message.namespace, "urn:x-cast:com.example");
+        assert_eq!(message.source, "sender-0");
+        assert_eq!(message.destination, "receiver-0");
+        assert_eq!(message.payload.as_str(), Some("{\"type\":\"PING\"}"));
+
+        let result = CastMessage::builder()
+            .namespace("urn:x-cast:com.example")
+            .payload_str("{}")
+            .build();
+        assert!(matches!(result, Err(Error::Internal(_))));
+    }
+
+    #[test]
+    #[cfg(feature = "thread_safe")]
+    fn test_shared_between_threads() {
+        fn is_send_sync<T: Send + Sync>() {}
+        is_send_sync::<MessageManager<MockTcpStream>>();
+
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&create_message("{\"type\":\"PONG\"}"));
+        let message_manager = Arc::new(MessageManager::new(stream));
+
+        let sender = Arc::clone(&message_manager);
+        std::thread::spawn(move || sender.send(create_message("{\"type\":\"PING\"}")))
+            .join()
+            .unwrap()
+            .unwrap();
+
+        let message = message_manager.receive().unwrap();
+        assert_eq!(message.payload.as_str(), Some("{\"type\":\"PONG\"}"));
+        assert!(!message_manager.inner().write_buffer.is_empty());
+    }
+
+    #[test]
+    fn test_message_tap() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&create_message("{\"type\":\"PONG\"}"));
+        let message_manager = MessageManager::new(stream);
+
+        let tapped = Arc::new(std::sync::Mutex::new(vec![]));
+        let tap_tapped = Arc::clone(&tapped);
+        message_manager.set_message_tap(Some(Box::new(move |direction, message| {
+            tap_tapped
+                .lock()
+                .unwrap()
+                .push((direction, message.payload.as_str().unwrap().to_string()));
+        })));
+
+        message_manager
+            .send(create_message("{\"type\":\"PING\"}"))
+            .unwrap();
+        message_manager.receive().unwrap();
+
+        assert_eq!(
+            *tapped.lock().unwrap(),
+            vec![
+                (Direction::Sent, "{\"type\":\"PING\"}".to_string()),
+                (Direction::Received, "{\"type\":\"PONG\"}".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_send_binary_round_trip() {
+        let message_manager = MessageManager::new(MockTcpStream::new());
+
+        message_manager
+            .send_binary(
+                "urn:x-cast:com.example",
+                "sender-0",
+                "receiver-0",
+                vec![0, 1, 2, 255],
+            )
+            .unwrap();
+
+        {
+            let mut stream = message_manager.inner();
+            stream.read_buffer = std::mem::take(&mut stream.write_buffer);
+        }
+
+        let message = message_manager.receive().unwrap();
+        assert_eq!(message.namespace, "urn:x-cast:com.example");
+        assert_eq!(message.source, "sender-0");
+        assert_eq!(message.destination, "receiver-0");
+        match message.payload {
+            CastMessagePayload::Binary(payload) => assert_eq!(payload, vec![0, 1, 2, 255]),
+            payload => panic!("expected binary payload, but got {:?}", payload),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_framing_over_pipe() {
+        use std::os::unix::net::UnixStream;
+
+        let (sender_stream, receiver_stream) = UnixStream::pair().unwrap();
+        let sender = MessageManager::new(sender_stream);
+        let receiver = MessageManager::new(receiver_stream);
+
+        sender.send(create_message(r#"{"type":"PING"}"#)).unwrap();
+        sender
+            .send(create_message(&format!(
+                r#"{{"data":"{}"}}"#,
+                "x".repeat(20_000)
+            )))
+            .unwrap();
+
+        assert_eq!(
+            receiver.receive().unwrap().payload.as_str(),
+            Some(r#"{"type":"PING"}"#)
+        );
+        assert_eq!(
+            receiver.receive().unwrap().payload.as_str().map(str::len),
+            Some(20_011)
+        );
+
+        let replaced = std::mem::replace(&mut *receiver.inner(), UnixStream::pair().unwrap().0);
+        replaced.shutdown(std::net::Shutdown::Both).unwrap();
+    }
+
+    #[test]
+    fn test_decode_message() {
+        let message = create_message(r#"{"type":"PING"}"#);
+        let mut frame = encode_message(&message).unwrap();
+        let length = frame.len();
+
+        for end in 0..length {
+            assert!(decode_message(&frame[..end]).unwrap().is_none());
+        }
+
+        // Trailing bytes belong to the next frame.
+        frame.extend_from_slice(&frame.clone()[..6]);
+        let (decoded, consumed) = decode_message(&frame).unwrap().unwrap();
+        assert_eq!(consumed, length);
+        assert_eq!(decoded.namespace, message.namespace);
+        assert!(decode_message(&frame[consumed..]).unwrap().is_none());
+
+        let oversized = utils::write_u32_to_buffer(DEFAULT_MAX_MESSAGE_SIZE as u32 + 1).unwrap();
+        assert!(matches!(
+            decode_message(&oversized),
+            Err(Error::MessageTooLarge { .. })
+        ));
+
+        let mut garbage = utils::write_u32_to_buffer(4).unwrap();
+        garbage.extend_from_slice(&[0xff; 4]);
+        assert!(decode_message(&garbage).is_err());
+    }
+
+    #[test]
+    fn test_receive_frame_in_chunks() {
+        let payload = serde_json::json!({
+            "type": "MEDIA_STATUS",
+            "requestId": 0,
+            "status": [{
+                "mediaSessionId": 1,
+                "items": (0..400)
+                    .map(|item_id| serde_json::json!({
+                        "itemId": item_id,
+                        "media": { "contentId": format!("http://example.com/{}.mp3", item_id) }
+                    }))
+                    .collect::<Vec<_>>()
+            }]
         })
+        .to_string();
+        assert!(payload.len() > READ_CHUNK_LENGTH);
+
+        let frame = encode_message(&create_message(&payload)).unwrap();
+        let (first, second) = frame.split_at(frame.len() / 2);
+
+        let mut stream = MockTcpStream::new();
+        stream.read_buffer = first.to_vec();
+        stream.timeout_when_empty = true;
+        let message_manager = MessageManager::new(stream);
+
+        assert!(matches!(message_manager.receive(), Err(Error::Timeout(_))));
+
+        message_manager
+            .inner()
+            .read_buffer
+            .extend_from_slice(second);
+
+        let message = message_manager.receive().unwrap();
+        assert_eq!(message.payload.as_str(), Some(payload.as_str()));
+        assert!(matches!(message_manager.receive(), Err(Error::Timeout(_))));
+    }
+
+    #[test]
+    fn test_peek_type() {
+        assert_eq!(peek_type(r#"{"type":"PING"}"#), Some("PING"));
+        assert_eq!(
+            peek_type(r#"{"status":{"nested":{"type":"NOT_THIS"}},"type":"RECEIVER_STATUS"}"#),
+            Some("RECEIVER_STATUS")
+        );
+        assert_eq!(peek_type(r#"{"type":5}"#), None);
+        assert_eq!(peek_type(r#"{"type":"PI"#), None);
+        assert_eq!(peek_type("[]"), None);
+
+        let message = create_message(r#"{"type":"LOAD_FAILED","requestId":3}"#);
+        assert_eq!(message.payload.message_type(), Some("LOAD_FAILED"));
+        assert!(matches!(
+            extract_cast_error(&message),
+            Some(Error::CastError {
+                request_id: Some(3),
+                ..
+            })
+        ));
+        assert!(CastMessagePayload::Binary(vec![1, 2])
+            .message_type()
+            .is_none());
+    }
+
+    #[test]
+    fn test_receive_oversized_message() {
+        let mut stream = MockTcpStream::new();
+        stream.read_buffer = utils::write_u32_to_buffer(u32::MAX).unwrap();
+        let message_manager = MessageManager::new(stream);
+
+        match message_manager.receive() {
+            Err(Error::MessageTooLarge { size, max_size }) => {
+                assert_eq!(size, u32::MAX as usize);
+                assert_eq!(max_size, DEFAULT_MAX_MESSAGE_SIZE);
+            }
+            result => panic!("expected Error::MessageTooLarge, but got {:?}", result),
+        }
+
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&create_message(r#"{"type":"PING"}"#));
+        let message_manager = MessageManager::new(stream).with_max_message_size(16);
+
+        assert!(matches!(
+            message_manager.receive(),
+            Err(Error::MessageTooLarge { max_size: 16, .. })
+        ));
+    }
+
+    #[test]
+    fn test_receive_after_bad_frame() {
+        let mut stream = MockTcpStream::new();
+        stream.read_buffer = utils::write_u32_to_buffer(4).unwrap();
+        stream.read_buffer.extend_from_slice(&[0xff; 4]);
+        stream.add_message(&create_message(&format!(
+            r#"{{"data":"{}"}}"#,
+            "x".repeat(64)
+        )));
+        stream.add_message(&create_message(r#"{"type":"PING"}"#));
+        let message_manager = MessageManager::new(stream).with_max_message_size(100);
+
+        assert!(matches!(message_manager.receive(), Err(Error::Protobuf(_))));
+        assert!(matches!(
+            message_manager.receive(),
+            Err(Error::MessageTooLarge { .. })
+        ));
+        assert_eq!(
+            message_manager.receive().unwrap().payload.as_str(),
+            Some(r#"{"type":"PING"}"#)
+        );
     }
 }
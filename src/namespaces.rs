@@ -0,0 +1,20 @@
+//! Namespaces of the Cast protocol messages handled by the built-in channels, e.g. to route
+//! messages in the custom dispatcher (see `CastMessage::namespace`).
+
+/// Namespace of the virtual connection messages, see `channels::connection`.
+pub const CONNECTION: &str = "urn:x-cast:com.google.cast.tp.connection";
+
+/// Namespace of the device authentication messages.
+pub const DEVICE_AUTH: &str = "urn:x-cast:com.google.cast.tp.deviceauth";
+
+/// Namespace of the keep-alive messages, see `channels::heartbeat`.
+pub const HEARTBEAT: &str = "urn:x-cast:com.google.cast.tp.heartbeat";
+
+/// Namespace of the media playback messages, see `channels::media`.
+pub const MEDIA: &str = "urn:x-cast:com.google.cast.media";
+
+/// Namespace of the multizone (speaker group) messages, see `channels::multizone`.
+pub const MULTIZONE: &str = "urn:x-cast:com.google.cast.multizone";
+
+/// Namespace of the receiver (application and volume) messages, see `channels::receiver`.
+pub const RECEIVER: &str = "urn:x-cast:com.google.cast.receiver";
@@ -0,0 +1,1050 @@
+use std::{
+    borrow::Cow,
+    io::{Read, Write},
+    time::Instant,
+};
+
+use crate::{
+    channels::{
+        connection::ConnectionChannel,
+        heartbeat::{HeartbeatChannel, HeartbeatResponse},
+        media::{
+            LoadOptions, Media, MediaChannel, MediaResponse, PlaybackClock, Status, StatusEntry,
+            StreamType,
+        },
+        receiver::{Application, CastDeviceApp, ReceiverChannel, Volume},
+    },
+    errors::Error,
+    generate_sender_id,
+    message_manager::MessageManager,
+    namespaces,
+    reconnect::Backoff,
+    tls::TlsStream,
+    CastDevice, CastDeviceBuilder, ChannelMessage, Lock, Lrc, DEFAULT_RECEIVER_ID,
+};
+
+/// High-level structure that launches receiver application on the cast device and controls media
+/// playback in it. It takes care of virtual connections, session identifiers and heartbeats, so
+/// that the most common use case doesn't require dealing with individual channels.
+pub struct Player<'a, W>
+where
+    W: Read + Write,
+{
+    pub message_manager: Lrc<MessageManager<W>>,
+
+    /// Channel that manages connection responses/requests.
+    pub connection: ConnectionChannel<'a, W>,
+
+    /// Channel that allows connection to stay alive (via ping-pong requests/responses).
+    pub heartbeat: HeartbeatChannel<'a, W>,
+
+    /// Channel that manages various media stuff.
+    pub media: MediaChannel<'a, W>,
+
+    /// Channel that manages receiving platform (e.g. Chromecast).
+    pub receiver: ReceiverChannel<'a, W>,
+
+    application: Application,
+    media_session_id: Lock<Option<i32>>,
+    current_media: Lock<Option<Media>>,
+    last_loaded_media: Lock<Option<Media>>,
+    clock: Lock<PlaybackClock>,
+}
+
+impl<'a> Player<'a, TlsStream> {
+    /// Connects to the cast device using host name and port and launches Default Media Receiver
+    /// application on it.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rust_cast::player::Player;
+    ///
+    /// let player = Player::connect("192.168.1.2", 8009)?;
+    /// player.play_url("http://example.com/video.mp4", "video/mp4")?;
+    /// # Ok::<(), rust_cast::errors::Error>(())
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `host` - Cast device host name.
+    /// * `port` - Cast device port number.
+    pub fn connect<S>(host: S, port: u16) -> Result<Player<'a, TlsStream>, Error>
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        Player::from_device(
+            CastDevice::connect(host, port)?,
+            &CastDeviceApp::DefaultMediaReceiver,
+        )
+    }
+
+    /// Launches `app` on the already connected cast device.
+    ///
+    /// # Arguments
+    ///
+    /// * `device` - `CastDevice` instance connected to the cast device;
+    /// * `app` - receiver application to launch.
+    pub fn from_device(
+        device: CastDevice<'a>,
+        app: &CastDeviceApp,
+    ) -> Result<Player<'a, TlsStream>, Error> {
+        let CastDevice {
+            message_manager,
+            connection,
+            heartbeat,
+            media,
+            receiver,
+            ..
+        } = device;
+
+        Player::launch(message_manager, connection, heartbeat, media, receiver, app)
+    }
+
+    /// Connects to the cast device and joins `app` if it's already running there, launching it
+    /// otherwise. Connection attempts that fail because of transport errors (e.g. while cast
+    /// device is rebooting) are retried according to `backoff`. Use it to restore the player
+    /// once its methods start failing with `Error::is_transport_error` errors.
+    ///
+    /// Application transport id changes whenever application is relaunched, so it's always taken
+    /// from the fresh receiver status rather than from the previous connection.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use rust_cast::{
+    ///     channels::receiver::CastDeviceApp, player::Player, reconnect::Backoff, CastDeviceBuilder,
+    /// };
+    ///
+    /// let builder = CastDeviceBuilder::new("192.168.1.2");
+    /// let player = Player::reconnect(
+    ///     &builder,
+    ///     &CastDeviceApp::DefaultMediaReceiver,
+    ///     &Backoff::default(),
+    /// )?;
+    /// # Ok::<(), rust_cast::errors::Error>(())
+    /// ```
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - `CastDeviceBuilder` configured to connect to the cast device;
+    /// * `app` - receiver application to join or launch;
+    /// * `backoff` - describes how connection attempts are retried.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::RetriesExhausted` if all attempts have failed, or the error that can't be
+    /// fixed by reconnecting (e.g. application launch error) right away.
+    pub fn reconnect(
+        builder: &CastDeviceBuilder<'a>,
+        app: &CastDeviceApp,
+        backoff: &Backoff,
+    ) -> Result<Player<'a, TlsStream>, Error> {
+        backoff.retry(|_| {
+            let CastDevice {
+                message_manager,
+                connection,
+                heartbeat,
+                media,
+                receiver,
+                ..
+            } = builder.clone().connect()?;
+
+            Player::start(
+                message_manager,
+                connection,
+                heartbeat,
+                media,
+                receiver,
+                app,
+                true,
+            )
+        })
+    }
+
+    /// Reconnects to the cast device the same way `reconnect` does and resumes playback of the
+    /// media this player has been playing (see `resume`). Use it to recover from transient
+    /// network failures, e.g. once methods of this player start failing with
+    /// `Error::is_transport_error` errors.
+    ///
+    /// # Arguments
+    ///
+    /// * `builder` - `CastDeviceBuilder` configured to connect to the cast device;
+    /// * `backoff` - describes how connection attempts are retried.
+    pub fn reconnect_and_resume(
+        &self,
+        builder: &CastDeviceBuilder<'a>,
+        backoff: &Backoff,
+    ) -> Result<Player<'a, TlsStream>, Error> {
+        let app = self
+            .application
+            .app_id
+            .parse()
+            .unwrap_or_else(|_| CastDeviceApp::Custom(self.application.app_id.clone()));
+
+        let player = Player::reconnect(builder, &app, backoff)?;
+        player.resume(self)?;
+
+        Ok(player)
+    }
+}
+
+impl<'a, W> Player<'a, W>
+where
+    W: Read + Write,
+{
+    /// Launches `app` on the cast device using provided `MessageManager`. Fresh sender id (see
+    /// `generate_sender_id`) is used for the session, so that receiver doesn't mix it up with the
+    /// previous ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `message_manager` - `MessageManager` that talks to the cast device;
+    /// * `app` - receiver application to launch.
+    pub fn new(
+        message_manager: Lrc<MessageManager<W>>,
+        app: &CastDeviceApp,
+    ) -> Result<Player<'a, W>, Error> {
+        let (connection, heartbeat, media, receiver) =
+            Player::channels(&message_manager, Cow::from(generate_sender_id()));
+
+        Player::launch(message_manager, connection, heartbeat, media, receiver, app)
+    }
+
+    /// Joins application with `app_id` that is already running on the cast device (e.g. launched
+    /// from a phone) without relaunching it, and takes over its current media session so that
+    /// playback can be controlled right away.
+    ///
+    /// If application has no media loaded, player is still joined, but `media_session_id` returns
+    /// `None` until media is loaded. Just like with `new`, fresh sender id is used for the session.
+    ///
+    /// # Arguments
+    ///
+    /// * `message_manager` - `MessageManager` that talks to the cast device;
+    /// * `app_id` - identifier of the running application (e.g. `233637DE` for YouTube).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Internal` if application with `app_id` isn't running and `Error::Namespace`
+    /// if it doesn't support media namespace.
+    pub fn join_running_app(
+        message_manager: Lrc<MessageManager<W>>,
+        app_id: &str,
+    ) -> Result<Player<'a, W>, Error> {
+        let (connection, heartbeat, media, receiver) =
+            Player::channels(&message_manager, Cow::from(generate_sender_id()));
+
+        connection.connect(DEFAULT_RECEIVER_ID)?;
+        heartbeat.ping()?;
+
+        let application = receiver
+            .get_status()?
+            .running_application(app_id)
+            .cloned()
+            .ok_or_else(|| Error::Internal(format!("Application {} is not running.", app_id)))?;
+
+        let player = Player::attach(
+            message_manager,
+            connection,
+            heartbeat,
+            media,
+            receiver,
+            application,
+        )?;
+
+        let status = player
+            .media
+            .get_status(player.application.transport_id.as_str(), None)?;
+        player.track_status(&status);
+
+        Ok(player)
+    }
+
+    /// Returns receiver application the player works with.
+    pub fn application(&self) -> &Application {
+        &self.application
+    }
+
+    /// Returns identifier of the currently loaded media session, if any.
+    pub fn media_session_id(&self) -> Option<i32> {
+        *self.media_session_id.borrow()
+    }
+
+    /// Returns description of the currently loaded media, if known. Receivers include media
+    /// description into the status only when media changes (e.g. live streams keep sending partial
+    /// updates with just the seekable range), so the last reported one is kept for as long as the
+    /// media session stays the same.
+    pub fn current_media(&self) -> Option<Media> {
+        self.current_media.borrow().clone()
+    }
+
+    /// Loads and starts playing media available at `url`.
+    ///
+    /// # Arguments
+    ///
+    /// * `url` - URL of the media to play;
+    /// * `content_type` - MIME content type of the media, e.g. `video/mp4`.
+    pub fn play_url(&self, url: &str, content_type: &str) -> Result<Status, Error> {
+        self.load(&Media {
+            content_id: Some(url.to_string()),
+            content_url: None,
+            stream_type: StreamType::Buffered,
+            content_type: content_type.to_string(),
+            metadata: None,
+            tracks: vec![],
+            text_track_style: None,
+            hls_segment_format: None,
+            hls_video_segment_format: None,
+            duration: None,
+            breaks: vec![],
+            break_clips: vec![],
+        })
+    }
+
+    /// Loads and starts playing `media`.
+    ///
+    /// # Arguments
+    ///
+    /// * `media` - `Media` instance that describes the media to play.
+    pub fn load(&self, media: &Media) -> Result<Status, Error> {
+        self.load_with_options(media, LoadOptions::default())
+    }
+
+    /// Loads `media` with additional LOAD request parameters (e.g. position to start from).
+    ///
+    /// # Arguments
+    ///
+    /// * `media` - `Media` instance that describes the media to play;
+    /// * `options` - `LoadOptions` instance with additional LOAD request parameters.
+    pub fn load_with_options(&self, media: &Media, options: LoadOptions) -> Result<Status, Error> {
+        let status = self.media.load_with_options(
+            self.application.transport_id.as_str(),
+            self.application.session_id.as_str(),
+            media,
+            options,
+        )?;
+
+        self.track_status(&status);
+        *self.last_loaded_media.borrow_mut() = Some(media.clone());
+
+        // Receiver may not echo loaded media back, but it's known anyway.
+        if self.media_session_id().is_some() && self.current_media.borrow().is_none() {
+            *self.current_media.borrow_mut() = Some(media.clone());
+        }
+
+        Ok(status)
+    }
+
+    /// Resumes playback of the loaded media.
+    pub fn play(&self) -> Result<StatusEntry, Error> {
+        self.media.play(
+            self.application.transport_id.as_str(),
+            self.require_media()?,
+        )
+    }
+
+    /// Pauses playback of the loaded media.
+    pub fn pause(&self) -> Result<StatusEntry, Error> {
+        self.media.pause(
+            self.application.transport_id.as_str(),
+            self.require_media()?,
+        )
+    }
+
+    /// Stops playback of the loaded media, media has to be loaded again to be played. See
+    /// `MediaChannel::stop` for the return value.
+    pub fn stop(&self) -> Result<Option<StatusEntry>, Error> {
+        let entry = self.media.stop(
+            self.application.transport_id.as_str(),
+            self.require_media()?,
+        )?;
+
+        *self.media_session_id.borrow_mut() = None;
+        *self.current_media.borrow_mut() = None;
+        *self.last_loaded_media.borrow_mut() = None;
+
+        Ok(entry)
+    }
+
+    /// Resumes playback that `previous` player (e.g. one whose connection has been lost) was
+    /// controlling. If media session of `previous` player is still alive on the receiver, this
+    /// player just takes it over, otherwise the last media `previous` player has loaded or
+    /// observed is loaded again starting from its last estimated position (live streams start
+    /// from the live edge). Nothing is loaded if `previous` player had no media.
+    ///
+    /// # Arguments
+    ///
+    /// * `previous` - player that was controlling the playback before.
+    ///
+    /// # Return value
+    ///
+    /// Returns media `Status` of the resumed session.
+    pub fn resume(&self, previous: &Player<'_, W>) -> Result<Status, Error> {
+        let status = self
+            .media
+            .get_status(self.application.transport_id.as_str(), None)?;
+
+        let previous_session_id = previous.media_session_id();
+        let session_alive = previous_session_id.is_some()
+            && status
+                .entries
+                .iter()
+                .any(|entry| Some(entry.media_session_id) == previous_session_id);
+
+        let media = previous
+            .current_media()
+            .or_else(|| previous.last_loaded_media.borrow().clone());
+
+        let media = match media {
+            Some(media) if !session_alive => media,
+            _ => {
+                self.track_status(&status);
+                if session_alive && self.current_media.borrow().is_none() {
+                    *self.current_media.borrow_mut() = previous.current_media();
+                }
+                *self.last_loaded_media.borrow_mut() = previous.last_loaded_media.borrow().clone();
+
+                return Ok(status);
+            }
+        };
+
+        let current_time = match media.stream_type {
+            StreamType::Live => None,
+            _ => Some(f64::from(
+                previous
+                    .clock
+                    .borrow()
+                    .estimated_current_time(Instant::now()),
+            )),
+        };
+
+        self.load_with_options(
+            &media,
+            LoadOptions {
+                current_time,
+                ..LoadOptions::default()
+            },
+        )
+    }
+
+    /// Seeks to the specified position of the loaded media.
+    ///
+    /// # Arguments
+    ///
+    /// * `current_time` - Time in seconds to seek to.
+    pub fn seek(&self, current_time: f32) -> Result<StatusEntry, Error> {
+        self.media.seek(
+            self.application.transport_id.as_str(),
+            self.require_media()?,
+            Some(current_time),
+            None,
+        )
+    }
+
+    /// Sets volume for the cast device.
+    ///
+    /// # Arguments
+    ///
+    /// * `volume` - anything that can be converted to a valid `Volume` structure (e.g. volume
+    ///   level or mute state).
+    pub fn set_volume<T>(&self, volume: T) -> Result<Volume, Error>
+    where
+        T: Into<Volume>,
+    {
+        self.receiver.set_volume(volume)
+    }
+
+    /// Keeps connection with the cast device alive, should be called periodically (e.g. before
+    /// every `receive` call).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Timeout` if cast device hasn't sent anything for too long.
+    pub fn keep_alive(&self) -> Result<(), Error> {
+        self.heartbeat.tick(Instant::now()).map(|_| ())
+    }
+
+    /// Waits for any message returned by cast device and returns its parsed version. PING messages
+    /// are answered automatically and media status updates are tracked, so that playback methods
+    /// always target the current media session.
+    pub fn receive(&self) -> Result<ChannelMessage, Error> {
+        let message = self.message_manager.receive()?;
+
+        if self.connection.can_handle(&message) {
+            return Ok(ChannelMessage::Connection(self.connection.parse(&message)?));
+        }
+
+        if self.heartbeat.can_handle(&message) {
+            let response = self.heartbeat.parse(&message)?;
+            if let HeartbeatResponse::Ping = response {
+                self.heartbeat.pong(message.source)?;
+            }
+
+            return Ok(ChannelMessage::Heartbeat(response));
+        }
+
+        if self.media.can_handle(&message) {
+            let response = self.media.parse(&message)?;
+            if let MediaResponse::Status(ref status) = response {
+                if let Some(entry) = status.entries.first() {
+                    self.track_status_entry(entry);
+                }
+            }
+
+            return Ok(ChannelMessage::Media(response));
+        }
+
+        if self.receiver.can_handle(&message) {
+            let response = self.receiver.parse(&message)?;
+            self.receiver.event(&response);
+
+            return Ok(ChannelMessage::Receiver(response));
+        }
+
+        Ok(ChannelMessage::Raw(message))
+    }
+
+    /// Creates channels the player talks to the cast device with, all of them use `sender` id.
+    fn channels(
+        message_manager: &Lrc<MessageManager<W>>,
+        sender: Cow<'a, str>,
+    ) -> (
+        ConnectionChannel<'a, W>,
+        HeartbeatChannel<'a, W>,
+        MediaChannel<'a, W>,
+        ReceiverChannel<'a, W>,
+    ) {
+        let connection = ConnectionChannel::new(sender.clone(), Lrc::clone(message_manager));
+        let heartbeat = HeartbeatChannel::new(
+            sender.clone(),
+            Cow::from(DEFAULT_RECEIVER_ID),
+            Lrc::clone(message_manager),
+        );
+        let media = MediaChannel::new(sender.clone(), Lrc::clone(message_manager));
+        let receiver = ReceiverChannel::new(
+            sender,
+            Cow::from(DEFAULT_RECEIVER_ID),
+            Lrc::clone(message_manager),
+        );
+
+        (connection, heartbeat, media, receiver)
+    }
+
+    fn launch(
+        message_manager: Lrc<MessageManager<W>>,
+        connection: ConnectionChannel<'a, W>,
+        heartbeat: HeartbeatChannel<'a, W>,
+        media: MediaChannel<'a, W>,
+        receiver: ReceiverChannel<'a, W>,
+        app: &CastDeviceApp,
+    ) -> Result<Player<'a, W>, Error> {
+        Player::start(
+            message_manager,
+            connection,
+            heartbeat,
+            media,
+            receiver,
+            app,
+            false,
+        )
+    }
+
+    /// Establishes virtual connections and launches `app`, or just joins it if `rejoin` is
+    /// requested and receiver status reports it as running.
+    fn start(
+        message_manager: Lrc<MessageManager<W>>,
+        connection: ConnectionChannel<'a, W>,
+        heartbeat: HeartbeatChannel<'a, W>,
+        media: MediaChannel<'a, W>,
+        receiver: ReceiverChannel<'a, W>,
+        app: &CastDeviceApp,
+        rejoin: bool,
+    ) -> Result<Player<'a, W>, Error> {
+        connection.connect(DEFAULT_RECEIVER_ID)?;
+        heartbeat.ping()?;
+
+        let running_application = if rejoin {
+            receiver
+                .get_status()?
+                .running_application(&app.to_string())
+                .cloned()
+        } else {
+            None
+        };
+
+        let application = match running_application {
+            Some(application) => application,
+            None => receiver.launch_app(app)?,
+        };
+
+        Player::attach(
+            message_manager,
+            connection,
+            heartbeat,
+            media,
+            receiver,
+            application,
+        )
+    }
+
+    /// Establishes virtual connection with the already running `application`, which must support
+    /// media namespace for the player to be of any use.
+    fn attach(
+        message_manager: Lrc<MessageManager<W>>,
+        connection: ConnectionChannel<'a, W>,
+        heartbeat: HeartbeatChannel<'a, W>,
+        media: MediaChannel<'a, W>,
+        receiver: ReceiverChannel<'a, W>,
+        application: Application,
+    ) -> Result<Player<'a, W>, Error> {
+        if !application.supports_namespace(namespaces::MEDIA) {
+            return Err(Error::Namespace(format!(
+                "Application {} doesn't support {} namespace.",
+                application.app_id,
+                namespaces::MEDIA
+            )));
+        }
+
+        connection.connect(application.transport_id.to_string())?;
+
+        Ok(Player {
+            message_manager,
+            connection,
+            heartbeat,
+            media,
+            receiver,
+            application,
+            media_session_id: Lock::new(None),
+            current_media: Lock::new(None),
+            last_loaded_media: Lock::new(None),
+            clock: Lock::new(PlaybackClock::default()),
+        })
+    }
+
+    /// Tracks media session reported by the status that replies to the request, it has no entries
+    /// if there is no media session at all.
+    fn track_status(&self, status: &Status) {
+        match status.entries.first() {
+            Some(entry) => self.track_status_entry(entry),
+            None => {
+                *self.media_session_id.borrow_mut() = None;
+                *self.current_media.borrow_mut() = None;
+                *self.clock.borrow_mut() = PlaybackClock::default();
+            }
+        }
+    }
+
+    /// Tracks media session of the status `entry`, previously known media description is kept
+    /// unless media session changes or `entry` describes new media.
+    fn track_status_entry(&self, entry: &StatusEntry) {
+        let mut media_session_id = self.media_session_id.borrow_mut();
+        let mut current_media = self.current_media.borrow_mut();
+
+        if *media_session_id != Some(entry.media_session_id) {
+            *media_session_id = Some(entry.media_session_id);
+            *current_media = None;
+            *self.clock.borrow_mut() = PlaybackClock::default();
+        }
+
+        if let Some(ref media) = entry.media {
+            *current_media = Some(media.clone());
+        }
+
+        self.clock.borrow_mut().update(entry, Instant::now());
+    }
+
+    fn require_media(&self) -> Result<i32, Error> {
+        self.media_session_id()
+            .ok_or_else(|| Error::Internal("No media is loaded.".to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        channels::{
+            receiver::{ReceiverEvent, ReceiverResponse},
+            tests::MockTcpStream,
+        },
+        message_manager::{CastMessage, CastMessagePayload},
+        DEFAULT_SENDER_ID,
+    };
+
+    use super::*;
+
+    fn create_message(namespace: &str, payload: serde_json::Value) -> CastMessage {
+        CastMessage {
+            namespace: namespace.to_string(),
+            source: DEFAULT_RECEIVER_ID.to_string(),
+            destination: DEFAULT_SENDER_ID.to_string(),
+            payload: CastMessagePayload::String(payload.to_string()),
+        }
+    }
+
+    #[test]
+    fn test_launch_and_play_url() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&create_message(
+            "urn:x-cast:com.google.cast.receiver",
+            serde_json::json!({
+                "type": "RECEIVER_STATUS",
+                "requestId": 1,
+                "status": {
+                    "applications": [{
+                        "appId": "CC1AD845",
+                        "sessionId": "session-1",
+                        "transportId": "web-5",
+                        "namespaces": [{ "name": "urn:x-cast:com.google.cast.media" }],
+                        "displayName": "Default Media Receiver",
+                        "statusText": ""
+                    }],
+                    "volume": { "level": 1.0, "muted": false }
+                }
+            }),
+        ));
+        stream.add_message(&media_status_message(
+            2,
+            serde_json::json!([{ "mediaSessionId": 7, "playerState": "BUFFERING" }]),
+        ));
+
+        let player = Player::new(
+            Lrc::new(MessageManager::new(stream)),
+            &CastDeviceApp::DefaultMediaReceiver,
+        )
+        .unwrap();
+
+        assert_eq!(player.application().transport_id, "web-5");
+        assert!(player.connection.is_connected("web-5"));
+        assert!(matches!(player.play(), Err(Error::Internal(_))));
+
+        player
+            .play_url("http://example.com/video.mp4", "video/mp4")
+            .unwrap();
+
+        assert_eq!(player.media_session_id(), Some(7));
+    }
+
+    #[test]
+    fn test_rejoin_running_application() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&create_message(
+            "urn:x-cast:com.google.cast.receiver",
+            serde_json::json!({
+                "type": "RECEIVER_STATUS",
+                "requestId": 1,
+                "status": {
+                    "applications": [{
+                        "appId": "CC1AD845",
+                        "sessionId": "session-2",
+                        "transportId": "web-9",
+                        "namespaces": [{ "name": "urn:x-cast:com.google.cast.media" }],
+                        "displayName": "Default Media Receiver",
+                        "statusText": ""
+                    }],
+                    "volume": { "level": 1.0, "muted": false }
+                }
+            }),
+        ));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+
+        let (connection, heartbeat, media, receiver) =
+            Player::channels(&message_manager, Cow::from(DEFAULT_SENDER_ID));
+        let player = Player::start(
+            Lrc::clone(&message_manager),
+            connection,
+            heartbeat,
+            media,
+            receiver,
+            &CastDeviceApp::DefaultMediaReceiver,
+            true,
+        )
+        .unwrap();
+
+        assert_eq!(player.application().transport_id, "web-9");
+        assert!(player.connection.is_connected("web-9"));
+
+        let written = message_manager.inner().write_buffer.clone();
+        let written = String::from_utf8_lossy(&written);
+        assert!(!written.contains("\"LAUNCH\""));
+    }
+
+    fn running_app_status_message(app_id: &str) -> CastMessage {
+        create_message(
+            "urn:x-cast:com.google.cast.receiver",
+            serde_json::json!({
+                "type": "RECEIVER_STATUS",
+                "requestId": 1,
+                "status": {
+                    "applications": [{
+                        "appId": app_id,
+                        "sessionId": "session-3",
+                        "transportId": "web-4",
+                        "namespaces": [{ "name": "urn:x-cast:com.google.cast.media" }],
+                        "displayName": "YouTube",
+                        "statusText": ""
+                    }],
+                    "volume": { "level": 1.0, "muted": false }
+                }
+            }),
+        )
+    }
+
+    fn media_status_message(request_id: u32, status: serde_json::Value) -> CastMessage {
+        create_message(
+            "urn:x-cast:com.google.cast.media",
+            serde_json::json!({
+                "type": "MEDIA_STATUS",
+                "requestId": request_id,
+                "status": status
+            }),
+        )
+    }
+
+    #[test]
+    fn test_join_running_app() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&running_app_status_message("233637DE"));
+        stream.add_message(&media_status_message(
+            2,
+            serde_json::json!([{ "mediaSessionId": 3, "playerState": "PLAYING" }]),
+        ));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+
+        let player = Player::join_running_app(Lrc::clone(&message_manager), "233637DE").unwrap();
+
+        assert_eq!(player.application().transport_id, "web-4");
+        assert!(player.connection.is_connected("web-4"));
+        assert_eq!(player.media_session_id(), Some(3));
+
+        let written = message_manager.inner().write_buffer.clone();
+        assert!(!String::from_utf8_lossy(&written).contains("\"LAUNCH\""));
+    }
+
+    #[test]
+    fn test_receive_volume_changed_event() {
+        let volume_status_message = |level: f32| {
+            create_message(
+                "urn:x-cast:com.google.cast.receiver",
+                serde_json::json!({
+                    "type": "RECEIVER_STATUS",
+                    "requestId": 0,
+                    "status": { "volume": { "level": level, "muted": false } }
+                }),
+            )
+        };
+
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&running_app_status_message("233637DE"));
+        stream.add_message(&media_status_message(2, serde_json::json!([])));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+
+        let player = Player::join_running_app(Lrc::clone(&message_manager), "233637DE").unwrap();
+
+        message_manager
+            .inner()
+            .add_message(&volume_status_message(1.0));
+        message_manager
+            .inner()
+            .add_message(&volume_status_message(0.3));
+
+        assert!(matches!(
+            player.receive(),
+            Ok(ChannelMessage::Receiver(ReceiverResponse::Status(_)))
+        ));
+        assert!(player.receiver.last_event().is_none());
+
+        assert!(matches!(
+            player.receive(),
+            Ok(ChannelMessage::Receiver(ReceiverResponse::Status(_)))
+        ));
+        match player.receiver.last_event() {
+            Some(ReceiverEvent::VolumeChanged(volume)) => assert_eq!(volume.level, Some(0.3)),
+            event => panic!("expected ReceiverEvent::VolumeChanged, but got {:?}", event),
+        }
+    }
+
+    #[test]
+    fn test_join_running_app_without_media() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&running_app_status_message("233637DE"));
+        stream.add_message(&media_status_message(2, serde_json::json!([])));
+
+        let player =
+            Player::join_running_app(Lrc::new(MessageManager::new(stream)), "233637DE").unwrap();
+
+        assert_eq!(player.media_session_id(), None);
+
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&running_app_status_message("233637DE"));
+
+        let result = Player::join_running_app(Lrc::new(MessageManager::new(stream)), "CC1AD845");
+        assert!(matches!(result, Err(Error::Internal(_))));
+    }
+
+    #[test]
+    fn test_sessions_use_fresh_sender_ids() {
+        let join = || {
+            let mut stream = MockTcpStream::new();
+            stream.add_message(&running_app_status_message("233637DE"));
+            stream.add_message(&media_status_message(2, serde_json::json!([])));
+            let message_manager = Lrc::new(MessageManager::new(stream));
+
+            Player::join_running_app(Lrc::clone(&message_manager), "233637DE").unwrap();
+
+            let written =
+                String::from_utf8_lossy(&message_manager.inner().write_buffer).to_string();
+            let start = written.find("sender-").unwrap();
+            written[start..start + "sender-".len() + 8].to_string()
+        };
+
+        let (first, second) = (join(), join());
+        assert_ne!(first, DEFAULT_SENDER_ID);
+        assert_ne!(first, second);
+    }
+
+    #[test]
+    fn test_partial_status_keeps_media() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&running_app_status_message("233637DE"));
+        stream.add_message(&media_status_message(
+            2,
+            serde_json::json!([{
+                "mediaSessionId": 3,
+                "playerState": "PLAYING",
+                "media": {
+                    "contentId": "http://example.com/live.m3u8",
+                    "streamType": "LIVE",
+                    "contentType": "application/x-mpegurl"
+                }
+            }]),
+        ));
+        stream.add_message(&media_status_message(
+            0,
+            serde_json::json!([{
+                "mediaSessionId": 3,
+                "liveSeekableRange": { "start": 10.0, "end": 70.0 },
+                "extendedStatus": { "playerState": "LOADING" }
+            }]),
+        ));
+        stream.add_message(&media_status_message(
+            0,
+            serde_json::json!([{ "mediaSessionId": 4, "playerState": "BUFFERING" }]),
+        ));
+        let player =
+            Player::join_running_app(Lrc::new(MessageManager::new(stream)), "233637DE").unwrap();
+
+        assert_eq!(
+            player.current_media().and_then(|media| media.content_id),
+            Some("http://example.com/live.m3u8".to_string())
+        );
+
+        match player.receive().unwrap() {
+            ChannelMessage::Media(MediaResponse::Status(status)) => {
+                assert!(status.entries[0].media.is_none());
+                assert!(status.entries[0].live_seekable_range.is_some());
+            }
+            message => panic!("expected media status, but got {:?}", message),
+        }
+        assert_eq!(
+            player.current_media().and_then(|media| media.content_id),
+            Some("http://example.com/live.m3u8".to_string())
+        );
+
+        player.receive().unwrap();
+        assert_eq!(player.media_session_id(), Some(4));
+        assert!(player.current_media().is_none());
+    }
+
+    #[test]
+    fn test_resume() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&running_app_status_message("233637DE"));
+        stream.add_message(&media_status_message(
+            2,
+            serde_json::json!([{
+                "mediaSessionId": 3,
+                "playerState": "PAUSED",
+                "currentTime": 30.0,
+                "media": {
+                    "contentId": "http://example.com/video.mp4",
+                    "streamType": "BUFFERED",
+                    "contentType": "video/mp4"
+                }
+            }]),
+        ));
+        let previous =
+            Player::join_running_app(Lrc::new(MessageManager::new(stream)), "233637DE").unwrap();
+
+        // Media session survived, it's just taken over.
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&running_app_status_message("233637DE"));
+        stream.add_message(&media_status_message(2, serde_json::json!([])));
+        stream.add_message(&media_status_message(
+            3,
+            serde_json::json!([{ "mediaSessionId": 3, "playerState": "PAUSED" }]),
+        ));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let player = Player::join_running_app(Lrc::clone(&message_manager), "233637DE").unwrap();
+
+        player.resume(&previous).unwrap();
+
+        assert_eq!(player.media_session_id(), Some(3));
+        assert_eq!(
+            player.current_media().and_then(|media| media.content_id),
+            Some("http://example.com/video.mp4".to_string())
+        );
+        let written = message_manager.inner().write_buffer.clone();
+        assert!(!String::from_utf8_lossy(&written).contains("\"LOAD\""));
+
+        // Media session is gone, media is loaded again from the last known position.
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&running_app_status_message("233637DE"));
+        stream.add_message(&media_status_message(2, serde_json::json!([])));
+        stream.add_message(&media_status_message(3, serde_json::json!([])));
+        stream.add_message(&media_status_message(
+            4,
+            serde_json::json!([{ "mediaSessionId": 8, "playerState": "BUFFERING" }]),
+        ));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+        let player = Player::join_running_app(Lrc::clone(&message_manager), "233637DE").unwrap();
+
+        player.resume(&previous).unwrap();
+
+        assert_eq!(player.media_session_id(), Some(8));
+        let written = message_manager.inner().write_buffer.clone();
+        let written = String::from_utf8_lossy(&written);
+        assert!(written.contains("\"LOAD\""));
+        assert!(written.contains("\"contentId\":\"http://example.com/video.mp4\""));
+        assert!(written.contains("\"currentTime\":30.0"));
+    }
+
+    #[test]
+    fn test_join_app_without_media_support() {
+        let mut stream = MockTcpStream::new();
+        stream.add_message(&create_message(
+            "urn:x-cast:com.google.cast.receiver",
+            serde_json::json!({
+                "type": "RECEIVER_STATUS",
+                "requestId": 1,
+                "status": {
+                    "applications": [{
+                        "appId": "E8C28D3C",
+                        "sessionId": "session-5",
+                        "transportId": "web-6",
+                        "namespaces": [{ "name": "urn:x-cast:com.example.custom" }],
+                        "displayName": "Backdrop",
+                        "statusText": ""
+                    }],
+                    "volume": { "level": 1.0, "muted": false }
+                }
+            }),
+        ));
+        let message_manager = Lrc::new(MessageManager::new(stream));
+
+        let result = Player::join_running_app(Lrc::clone(&message_manager), "E8C28D3C");
+        assert!(matches!(result, Err(Error::Namespace(_))));
+
+        let written = message_manager.inner().write_buffer.clone();
+        assert!(!String::from_utf8_lossy(&written).contains("web-6"));
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    thread,
+    time::Duration,
+};
+
+use crate::errors::Error;
+
+/// Describes how often and for how long failed connection attempts are retried. Delay before the
+/// retry grows exponentially starting from `base_delay` until it reaches `max_delay`.
+#[derive(Clone, Debug)]
+pub struct Backoff {
+    /// Delay before the first retry.
+    pub base_delay: Duration,
+    /// Upper bound of the delay between two consecutive attempts.
+    pub max_delay: Duration,
+    /// Fraction (`0.0` - `1.0`) of the delay that is randomized, so that multiple clients don't
+    /// retry at exactly the same moments.
+    pub jitter: f64,
+    /// Total number of attempts, including the first one.
+    pub max_attempts: u32,
+}
+
+impl Default for Backoff {
+    fn default() -> Self {
+        Backoff {
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            jitter: 0.2,
+            max_attempts: 10,
+        }
+    }
+}
+
+impl Backoff {
+    /// Calculates delay before the retry that follows failed `attempt` (zero-based).
+    pub fn delay(&self, attempt: u32) -> Duration {
+        let delay = self
+            .base_delay
+            .checked_mul(2u32.saturating_pow(attempt))
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay));
+
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 {
+            return delay;
+        }
+
+        delay.mul_f64(1.0 - jitter * random_fraction())
+    }
+
+    /// Calls `f` until it succeeds, sleeping between the attempts. Only transport errors (see
+    /// `Error::is_transport_error`) are retried, any other error is returned right away.
+    ///
+    /// # Arguments
+    ///
+    /// * `f` - function to call, receives zero-based attempt number.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::RetriesExhausted` with the last error once all attempts have failed.
+    pub fn retry<T, F>(&self, mut f: F) -> Result<T, Error>
+    where
+        F: FnMut(u32) -> Result<T, Error>,
+    {
+        let max_attempts = self.max_attempts.max(1);
+
+        for attempt in 0.. {
+            let error = match f(attempt) {
+                Ok(value) => return Ok(value),
+                Err(error) if !error.is_transport_error() => return Err(error),
+                Err(error) => error,
+            };
+
+            if attempt + 1 >= max_attempts {
+                return Err(Error::RetriesExhausted {
+                    attempts: max_attempts,
+                    last_error: Box::new(error),
+                });
+            }
+
+            let delay = self.delay(attempt);
+
+            log::debug!(
+                "Attempt {} of {} failed ({}), retrying in {:?}...",
+                attempt + 1,
+                max_attempts,
+                error,
+                delay
+            );
+
+            thread::sleep(delay);
+        }
+
+        unreachable!("Retry loop exits only by returning.")
+    }
+}
+
+/// Returns pseudo-random number in `0.0` - `1.0` range, good enough to spread retries.
+fn random_fraction() -> f64 {
+    let random = RandomState::new().build_hasher().finish();
+    (random >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{Error as IoError, ErrorKind};
+
+    use super::*;
+
+    fn backoff(max_attempts: u32) -> Backoff {
+        Backoff {
+            base_delay: Duration::ZERO,
+            max_delay: Duration::ZERO,
+            jitter: 0.0,
+            max_attempts,
+        }
+    }
+
+    #[test]
+    fn test_delay() {
+        let backoff = Backoff {
+            base_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(10),
+            jitter: 0.0,
+            max_attempts: 10,
+        };
+
+        assert_eq!(backoff.delay(0), Duration::from_secs(1));
+        assert_eq!(backoff.delay(2), Duration::from_secs(4));
+        assert_eq!(backoff.delay(4), Duration::from_secs(10));
+        assert_eq!(backoff.delay(100), Duration::from_secs(10));
+
+        let backoff = Backoff {
+            jitter: 0.5,
+            ..backoff
+        };
+        for attempt in 0..10 {
+            let delay = backoff.delay(attempt);
+            let expected = Duration::from_secs(2u64.pow(attempt).min(10));
+            assert!(delay <= expected && delay >= expected / 2);
+        }
+    }
+
+    #[test]
+    fn test_retry() {
+        let result = backoff(3).retry(|attempt| {
+            if attempt < 2 {
+                Err(Error::Io(IoError::from(ErrorKind::ConnectionRefused)))
+            } else {
+                Ok(attempt)
+            }
+        });
+        assert_eq!(result.unwrap(), 2);
+
+        match backoff(2).retry(|_| -> Result<(), Error> {
+            Err(Error::Io(IoError::from(ErrorKind::ConnectionRefused)))
+        }) {
+            Err(Error::RetriesExhausted {
+                attempts,
+                last_error,
+            }) => {
+                assert_eq!(attempts, 2);
+                assert!(matches!(*last_error, Error::Io(_)));
+            }
+            result => panic!("expected Error::RetriesExhausted, but got {:?}", result),
+        }
+
+        let mut calls = 0;
+        let result = backoff(5).retry(|_| -> Result<(), Error> {
+            calls += 1;
+            Err(Error::Parsing("Bad reply".to_string()))
+        });
+        assert!(matches!(result, Err(Error::Parsing(_))));
+        assert_eq!(calls, 1);
+    }
+}
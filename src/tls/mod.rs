@@ -0,0 +1,415 @@
+use std::{
+    fmt::{Debug, Formatter, Result as FmtResult},
+    io::{Read, Result as IoResult, Write},
+    net::TcpStream,
+    sync::Arc,
+};
+
+use crate::errors::Error;
+
+#[cfg(feature = "openssl")]
+mod openssl_backend;
+#[cfg(feature = "rustls")]
+mod rustls_backend;
+
+#[cfg(not(any(feature = "openssl", feature = "rustls")))]
+compile_error!("Either `openssl` or `rustls` feature must be enabled.");
+
+/// TLS library that performs the handshake with the cast device, every backend enabled with the
+/// corresponding feature is available.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TlsBackend {
+    /// OpenSSL, available with `openssl` feature (enabled by default).
+    #[cfg(feature = "openssl")]
+    OpenSsl,
+    /// Rustls, available with `rustls` feature.
+    #[cfg(feature = "rustls")]
+    Rustls,
+}
+
+impl Default for TlsBackend {
+    /// OpenSSL if `openssl` feature is enabled, rustls otherwise.
+    fn default() -> TlsBackend {
+        #[cfg(feature = "openssl")]
+        return TlsBackend::OpenSsl;
+
+        #[cfg(all(feature = "rustls", not(feature = "openssl")))]
+        return TlsBackend::Rustls;
+    }
+}
+
+/// TLS stream cast device communicates over, provided by the `TlsBackend` that established it.
+#[derive(Debug)]
+pub enum TlsStream {
+    /// Stream established with `TlsBackend::OpenSsl`.
+    #[cfg(feature = "openssl")]
+    OpenSsl(openssl_backend::TlsStream),
+    /// Stream established with `TlsBackend::Rustls`.
+    #[cfg(feature = "rustls")]
+    Rustls(Box<rustls_backend::TlsStream>),
+}
+
+impl TlsStream {
+    /// Returns the underlying TCP stream.
+    pub fn get_ref(&self) -> &TcpStream {
+        match *self {
+            #[cfg(feature = "openssl")]
+            TlsStream::OpenSsl(ref stream) => stream.get_ref(),
+            #[cfg(feature = "rustls")]
+            TlsStream::Rustls(ref stream) => stream.get_ref(),
+        }
+    }
+}
+
+impl Read for TlsStream {
+    fn read(&mut self, buf: &mut [u8]) -> IoResult<usize> {
+        match *self {
+            #[cfg(feature = "openssl")]
+            TlsStream::OpenSsl(ref mut stream) => stream.read(buf),
+            #[cfg(feature = "rustls")]
+            TlsStream::Rustls(ref mut stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for TlsStream {
+    fn write(&mut self, buf: &[u8]) -> IoResult<usize> {
+        match *self {
+            #[cfg(feature = "openssl")]
+            TlsStream::OpenSsl(ref mut stream) => stream.write(buf),
+            #[cfg(feature = "rustls")]
+            TlsStream::Rustls(ref mut stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> IoResult<()> {
+        match *self {
+            #[cfg(feature = "openssl")]
+            TlsStream::OpenSsl(ref mut stream) => stream.flush(),
+            #[cfg(feature = "rustls")]
+            TlsStream::Rustls(ref mut stream) => stream.flush(),
+        }
+    }
+}
+
+/// Function that decides whether DER-encoded certificate presented by the cast device should be
+/// trusted.
+pub type CertificateVerifier = Arc<dyn Fn(&[u8]) -> bool + Send + Sync>;
+
+/// Describes how the certificate presented by the cast device is verified.
+#[derive(Clone, Default)]
+pub enum TlsMode {
+    /// Any certificate is accepted. Cast devices use self-signed certificates, so that's what most
+    /// applications need.
+    #[default]
+    AcceptAny,
+    /// Certificate chain and host name are verified against the system trust store.
+    Verify,
+    /// Certificate is accepted only if its SHA-256 fingerprint (of the DER encoding) matches the
+    /// one provided, certificate chain and host name aren't verified. Useful when cast device
+    /// certificate has been captured once and shouldn't change.
+    PinnedSha256([u8; 32]),
+    /// Certificate chain is verified against PEM-encoded CA certificate(s) provided, system trust
+    /// store isn't used. Host name isn't verified since cast device certificates are issued for
+    /// the device rather than for its network address.
+    VerifyWithCa(Vec<u8>),
+    /// Certificate is accepted only if the verifier returns `true` for it, certificate chain and
+    /// host name aren't verified.
+    Custom(CertificateVerifier),
+}
+
+impl Debug for TlsMode {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match *self {
+            TlsMode::AcceptAny => f.write_str("AcceptAny"),
+            TlsMode::Verify => f.write_str("Verify"),
+            TlsMode::PinnedSha256(ref fingerprint) => {
+                write!(f, "PinnedSha256({})", format_fingerprint(fingerprint))
+            }
+            TlsMode::VerifyWithCa(_) => f.write_str("VerifyWithCa(..)"),
+            TlsMode::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+impl TlsMode {
+    /// Creates `TlsMode::PinnedSha256` from the hex-encoded fingerprint, bytes may be separated with
+    /// colons (e.g. `AB:CD:...`, as printed by `openssl x509 -fingerprint -sha256`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::Parsing` if fingerprint isn't a valid hex-encoded SHA-256 digest.
+    pub fn pinned_sha256_hex(fingerprint: &str) -> Result<TlsMode, Error> {
+        let hex = fingerprint.replace(':', "");
+        let mut digest = [0u8; 32];
+
+        if hex.len() != digest.len() * 2 || !hex.is_ascii() {
+            return Err(Error::Parsing(format!(
+                "Invalid SHA-256 fingerprint: {}",
+                fingerprint
+            )));
+        }
+
+        for (index, byte) in digest.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[index * 2..index * 2 + 2], 16).map_err(|_| {
+                Error::Parsing(format!("Invalid SHA-256 fingerprint: {}", fingerprint))
+            })?;
+        }
+
+        Ok(TlsMode::PinnedSha256(digest))
+    }
+
+    /// Performs TLS handshake over the established TCP connection, verifying cast device
+    /// certificate according to the mode.
+    pub(crate) fn connect(
+        &self,
+        backend: TlsBackend,
+        host: &str,
+        tcp_stream: TcpStream,
+    ) -> Result<TlsStream, Error> {
+        match backend {
+            #[cfg(feature = "openssl")]
+            TlsBackend::OpenSsl => {
+                openssl_backend::connect(self, host, tcp_stream).map(TlsStream::OpenSsl)
+            }
+            #[cfg(feature = "rustls")]
+            TlsBackend::Rustls => rustls_backend::connect(self, host, tcp_stream)
+                .map(|stream| TlsStream::Rustls(Box::new(stream))),
+        }
+    }
+}
+
+fn format_fingerprint(fingerprint: &[u8]) -> String {
+    fingerprint
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{
+        io::{Read, Write},
+        net::{TcpListener, TcpStream},
+        thread::{self, JoinHandle},
+    };
+
+    use openssl::{
+        asn1::Asn1Time,
+        ec::{EcGroup, EcKey},
+        nid::Nid,
+        pkey::{PKey, Private},
+        sha::sha256,
+        ssl::{SslAcceptor, SslMethod},
+        x509::{X509NameBuilder, X509},
+    };
+
+    use super::*;
+
+    /// Generates self-signed certificate the way cast devices do.
+    pub(crate) fn self_signed_certificate() -> (X509, PKey<Private>) {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+
+        let mut name = X509NameBuilder::new().unwrap();
+        name.append_entry_by_text("CN", "Chromecast").unwrap();
+        let name = name.build();
+
+        let mut builder = X509::builder().unwrap();
+        builder.set_version(2).unwrap();
+        builder.set_subject_name(&name).unwrap();
+        builder.set_issuer_name(&name).unwrap();
+        builder.set_pubkey(&key).unwrap();
+        builder
+            .set_not_before(&Asn1Time::days_from_now(0).unwrap())
+            .unwrap();
+        builder
+            .set_not_after(&Asn1Time::days_from_now(1).unwrap())
+            .unwrap();
+        builder
+            .sign(&key, openssl::hash::MessageDigest::sha256())
+            .unwrap();
+
+        (builder.build(), key)
+    }
+
+    /// Starts TLS server that accepts a single connection, returns its port.
+    pub(crate) fn serve_once(certificate: X509, key: PKey<Private>) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+        acceptor.set_certificate(&certificate).unwrap();
+        acceptor.set_private_key(&key).unwrap();
+        let acceptor = acceptor.build();
+
+        thread::spawn(move || {
+            if let Ok((stream, _)) = listener.accept() {
+                // Handshake fails whenever client rejects the certificate, that's expected.
+                let _ = acceptor.accept(stream);
+            }
+        });
+
+        port
+    }
+
+    /// Starts TLS server that accepts a single connection and reads from it until client is gone,
+    /// returns its port and the handle that yields everything that has been read.
+    pub(crate) fn serve_once_and_read(
+        certificate: X509,
+        key: PKey<Private>,
+    ) -> (u16, JoinHandle<Vec<u8>>) {
+        serve_once_and_write(certificate, key, vec![])
+    }
+
+    /// Same as `serve_once_and_read`, but writes `data` to the connection first.
+    pub(crate) fn serve_once_and_write(
+        certificate: X509,
+        key: PKey<Private>,
+        data: Vec<u8>,
+    ) -> (u16, JoinHandle<Vec<u8>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let mut acceptor = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls()).unwrap();
+        acceptor.set_certificate(&certificate).unwrap();
+        acceptor.set_private_key(&key).unwrap();
+        let acceptor = acceptor.build();
+
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut stream = acceptor.accept(stream).unwrap();
+            stream.write_all(&data).unwrap();
+
+            // Client may go away without TLS close notify, so keep whatever arrived before.
+            let mut received = vec![];
+            let mut buffer = [0; 1024];
+            while let Ok(length @ 1..) = stream.read(&mut buffer) {
+                received.extend_from_slice(&buffer[..length]);
+            }
+
+            received
+        });
+
+        (port, handle)
+    }
+
+    fn handshake(mode: TlsMode, port: u16) -> Result<TlsStream, Error> {
+        handshake_with(TlsBackend::default(), mode, port)
+    }
+
+    fn handshake_with(backend: TlsBackend, mode: TlsMode, port: u16) -> Result<TlsStream, Error> {
+        let stream = TcpStream::connect(("127.0.0.1", port))?;
+        mode.connect(backend, "127.0.0.1", stream)
+    }
+
+    #[test]
+    fn test_self_signed_certificate() {
+        let (certificate, key) = self_signed_certificate();
+        let expected = certificate.to_der().unwrap();
+
+        let port = serve_once(certificate.clone(), key.clone());
+        assert!(handshake(TlsMode::AcceptAny, port).is_ok());
+
+        let port = serve_once(certificate.clone(), key.clone());
+        assert!(matches!(
+            handshake(TlsMode::Verify, port),
+            Err(Error::Ssl(_))
+        ));
+
+        let port = serve_once(certificate.clone(), key.clone());
+        let verifier: CertificateVerifier = Arc::new(move |der| der == expected.as_slice());
+        assert!(handshake(TlsMode::Custom(verifier), port).is_ok());
+
+        let port = serve_once(certificate, key);
+        assert!(matches!(
+            handshake(TlsMode::Custom(Arc::new(|_| false)), port),
+            Err(Error::Ssl(_))
+        ));
+    }
+
+    #[test]
+    fn test_pinned_certificate() {
+        let (certificate, key) = self_signed_certificate();
+        let fingerprint = format_fingerprint(&sha256(&certificate.to_der().unwrap()));
+
+        let port = serve_once(certificate.clone(), key.clone());
+        let mode = TlsMode::pinned_sha256_hex(&fingerprint).unwrap();
+        assert!(handshake(mode, port).is_ok());
+
+        let port = serve_once(certificate, key);
+        assert!(matches!(
+            handshake(TlsMode::PinnedSha256([0; 32]), port),
+            Err(Error::Ssl(_))
+        ));
+    }
+
+    #[test]
+    fn test_ca_certificate() {
+        let (certificate, key) = self_signed_certificate();
+        let (other_certificate, _) = self_signed_certificate();
+
+        let port = serve_once(certificate.clone(), key.clone());
+        let mode = TlsMode::VerifyWithCa(certificate.to_pem().unwrap());
+        assert!(handshake(mode, port).is_ok());
+
+        let port = serve_once(certificate, key);
+        let mode = TlsMode::VerifyWithCa(other_certificate.to_pem().unwrap());
+        assert!(matches!(handshake(mode, port), Err(Error::Ssl(_))));
+    }
+
+    #[test]
+    fn test_parse_pinned_sha256_hex() {
+        let hex = "00".repeat(31) + "ff";
+        let mut expected = [0u8; 32];
+        expected[31] = 0xff;
+
+        assert!(matches!(
+            TlsMode::pinned_sha256_hex(&hex),
+            Ok(TlsMode::PinnedSha256(digest)) if digest == expected
+        ));
+        assert!(matches!(
+            TlsMode::pinned_sha256_hex(&format_fingerprint(&expected)),
+            Ok(TlsMode::PinnedSha256(digest)) if digest == expected
+        ));
+        assert!(matches!(
+            TlsMode::pinned_sha256_hex("AB:CD"),
+            Err(Error::Parsing(_))
+        ));
+        assert!(matches!(
+            TlsMode::pinned_sha256_hex(&"zz".repeat(32)),
+            Err(Error::Parsing(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(all(feature = "openssl", feature = "rustls"))]
+    fn test_both_backends() {
+        let (certificate, key) = self_signed_certificate();
+
+        let port = serve_once(certificate.clone(), key.clone());
+        assert!(matches!(
+            handshake_with(TlsBackend::OpenSsl, TlsMode::AcceptAny, port),
+            Ok(TlsStream::OpenSsl(_))
+        ));
+
+        let port = serve_once(certificate.clone(), key.clone());
+        assert!(matches!(
+            handshake_with(TlsBackend::Rustls, TlsMode::AcceptAny, port),
+            Ok(TlsStream::Rustls(_))
+        ));
+
+        let port = serve_once(certificate.clone(), key.clone());
+        assert!(matches!(
+            handshake_with(TlsBackend::OpenSsl, TlsMode::Verify, port),
+            Err(Error::Ssl(crate::errors::SslError::Handshake(_)))
+        ));
+
+        let port = serve_once(certificate, key);
+        assert!(matches!(
+            handshake_with(TlsBackend::Rustls, TlsMode::Verify, port),
+            Err(Error::Ssl(crate::errors::SslError::Rustls(_)))
+        ));
+    }
+}
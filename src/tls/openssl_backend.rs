@@ -0,0 +1,66 @@
+use std::{net::TcpStream, sync::Arc};
+
+use openssl::{
+    sha::sha256,
+    ssl::{SslConnector, SslConnectorBuilder, SslMethod, SslStream, SslVerifyMode},
+    x509::{store::X509StoreBuilder, X509},
+};
+
+use crate::{errors::Error, tls::TlsMode};
+
+pub type TlsStream = SslStream<TcpStream>;
+
+pub(crate) fn connect(
+    mode: &TlsMode,
+    host: &str,
+    tcp_stream: TcpStream,
+) -> Result<TlsStream, Error> {
+    let mut builder = SslConnector::builder(SslMethod::tls())?;
+
+    match *mode {
+        TlsMode::AcceptAny => builder.set_verify(SslVerifyMode::NONE),
+        TlsMode::Verify => {}
+        TlsMode::PinnedSha256(fingerprint) => {
+            set_leaf_verifier(&mut builder, move |der| sha256(der) == fingerprint)
+        }
+        TlsMode::VerifyWithCa(ref pem) => {
+            let mut store = X509StoreBuilder::new()?;
+            for certificate in X509::stack_from_pem(pem)? {
+                store.add_cert(certificate)?;
+            }
+            builder.set_verify_cert_store(store.build())?;
+            builder.set_verify(SslVerifyMode::PEER);
+        }
+        TlsMode::Custom(ref verifier) => {
+            let verifier = Arc::clone(verifier);
+            set_leaf_verifier(&mut builder, move |der| verifier(der));
+        }
+    }
+
+    let verify_hostname = matches!(*mode, TlsMode::Verify);
+
+    Ok(builder
+        .build()
+        .configure()?
+        .verify_hostname(verify_hostname)
+        .connect(host, tcp_stream)?)
+}
+
+/// Makes connector accept the cast device (leaf) certificate only if `verifier` returns `true` for
+/// its DER encoding, regardless of the certificate chain.
+fn set_leaf_verifier<F>(builder: &mut SslConnectorBuilder, verifier: F)
+where
+    F: Fn(&[u8]) -> bool + Send + Sync + 'static,
+{
+    builder.set_verify_callback(SslVerifyMode::PEER, move |_, context| {
+        // Only the leaf certificate that belongs to the cast device is of interest.
+        if context.error_depth() != 0 {
+            return true;
+        }
+
+        context
+            .current_cert()
+            .and_then(|certificate| certificate.to_der().ok())
+            .is_some_and(|der| verifier(&der))
+    });
+}
@@ -0,0 +1,207 @@
+use std::{io::Error as IoError, net::TcpStream, sync::Arc};
+
+use ring::digest::{digest, SHA256};
+use rustls::{
+    client::{
+        danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+        WebPkiServerVerifier,
+    },
+    crypto::{
+        ring::default_provider, verify_tls12_signature, verify_tls13_signature,
+        WebPkiSupportedAlgorithms,
+    },
+    pki_types::{pem::PemObject, CertificateDer, ServerName, UnixTime},
+    CertificateError, ClientConfig, ClientConnection, DigitallySignedStruct, Error as RustlsError,
+    RootCertStore, SignatureScheme, StreamOwned,
+};
+
+use crate::{
+    errors::{Error, SslError},
+    tls::{CertificateVerifier, TlsMode},
+};
+
+pub type TlsStream = StreamOwned<ClientConnection, TcpStream>;
+
+pub(crate) fn connect(
+    mode: &TlsMode,
+    host: &str,
+    mut tcp_stream: TcpStream,
+) -> Result<TlsStream, Error> {
+    let provider = Arc::new(default_provider());
+    let algorithms = provider.signature_verification_algorithms;
+
+    let verifier: Arc<dyn ServerCertVerifier> = match *mode {
+        TlsMode::AcceptAny => Arc::new(LeafVerifier {
+            verifier: Arc::new(|_| true),
+            algorithms,
+        }),
+        TlsMode::Verify => {
+            let mut roots = RootCertStore::empty();
+            roots.add_parsable_certificates(rustls_native_certs::load_native_certs().certs);
+
+            WebPkiServerVerifier::builder_with_provider(Arc::new(roots), Arc::clone(&provider))
+                .build()
+                .map_err(|err| Error::Internal(format!("Failed to build TLS verifier: {}", err)))?
+        }
+        TlsMode::PinnedSha256(fingerprint) => Arc::new(LeafVerifier {
+            verifier: Arc::new(move |der| digest(&SHA256, der).as_ref() == fingerprint),
+            algorithms,
+        }),
+        TlsMode::VerifyWithCa(ref pem) => {
+            let mut roots = RootCertStore::empty();
+            for certificate in CertificateDer::pem_slice_iter(pem) {
+                let certificate = certificate
+                    .map_err(|err| Error::Parsing(format!("Invalid CA certificate: {}", err)))?;
+                roots.add(certificate)?;
+            }
+
+            let verifier =
+                WebPkiServerVerifier::builder_with_provider(Arc::new(roots), Arc::clone(&provider))
+                    .build()
+                    .map_err(|err| {
+                        Error::Internal(format!("Failed to build TLS verifier: {}", err))
+                    })?;
+
+            Arc::new(AnyNameVerifier(verifier))
+        }
+        TlsMode::Custom(ref verifier) => Arc::new(LeafVerifier {
+            verifier: Arc::clone(verifier),
+            algorithms,
+        }),
+    };
+
+    let config = ClientConfig::builder_with_provider(provider)
+        .with_safe_default_protocol_versions()?
+        .dangerous()
+        .with_custom_certificate_verifier(verifier)
+        .with_no_client_auth();
+
+    let server_name = ServerName::try_from(host.to_string())
+        .map_err(|err| Error::Internal(format!("Invalid host name {}: {}", host, err)))?;
+
+    let mut connection = ClientConnection::new(Arc::new(config), server_name)?;
+
+    // Rustls performs handshake lazily, complete it right away so that certificate problems are
+    // reported on connect rather than on the first read or write.
+    while connection.is_handshaking() {
+        connection
+            .complete_io(&mut tcp_stream)
+            .map_err(handshake_error)?;
+    }
+
+    Ok(StreamOwned::new(connection, tcp_stream))
+}
+
+/// Rustls reports handshake failures as I/O errors, unwraps the underlying TLS error if any.
+fn handshake_error(err: IoError) -> Error {
+    match err
+        .get_ref()
+        .and_then(|inner| inner.downcast_ref::<RustlsError>())
+    {
+        Some(tls_error) => Error::Ssl(SslError::Rustls(tls_error.clone())),
+        None => Error::Io(err),
+    }
+}
+
+/// Accepts the cast device (leaf) certificate only if `verifier` returns `true` for its DER
+/// encoding, regardless of the certificate chain and host name. Handshake signatures are still
+/// verified.
+struct LeafVerifier {
+    verifier: CertificateVerifier,
+    algorithms: WebPkiSupportedAlgorithms,
+}
+
+impl std::fmt::Debug for LeafVerifier {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("LeafVerifier")
+    }
+}
+
+impl ServerCertVerifier for LeafVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer,
+        _intermediates: &[CertificateDer],
+        _server_name: &ServerName,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, RustlsError> {
+        if (self.verifier)(end_entity.as_ref()) {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(RustlsError::InvalidCertificate(
+                CertificateError::ApplicationVerificationFailure,
+            ))
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        certificate: &CertificateDer,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, RustlsError> {
+        verify_tls12_signature(message, certificate, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        certificate: &CertificateDer,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, RustlsError> {
+        verify_tls13_signature(message, certificate, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+}
+
+/// Verifies certificate chain with the wrapped verifier, but ignores host name mismatch since cast
+/// device certificates are issued for the device rather than for its network address.
+#[derive(Debug)]
+struct AnyNameVerifier(Arc<WebPkiServerVerifier>);
+
+impl ServerCertVerifier for AnyNameVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer,
+        intermediates: &[CertificateDer],
+        server_name: &ServerName,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, RustlsError> {
+        match self
+            .0
+            .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+        {
+            Err(RustlsError::InvalidCertificate(
+                CertificateError::NotValidForName | CertificateError::NotValidForNameContext { .. },
+            )) => Ok(ServerCertVerified::assertion()),
+            result => result,
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        certificate: &CertificateDer,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, RustlsError> {
+        self.0.verify_tls12_signature(message, certificate, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        certificate: &CertificateDer,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, RustlsError> {
+        self.0.verify_tls13_signature(message, certificate, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.supported_verify_schemes()
+    }
+}